@@ -1,26 +1,46 @@
-use std::{time::Duration};
+use std::{env, time::Duration};
 
 use mySQL_database_manager::backend::{data_base::DataBase, sql::SQL};
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 
+/// Returns the snapshot loaded by the benchmarks
+///
+/// The snapshot is read from the sql file at `BENCH_SNAPSHOT` if it is set, otherwise it is taken from the database.
+fn snap_shot(db: &DataBase) -> Vec<SQL> {
+    match env::var("BENCH_SNAPSHOT") {
+        Ok(file_path) => SQL::from_file(&file_path).unwrap(),
+        Err(_) => db.get_snapshot().unwrap(),
+    }
+}
 
 pub fn criterion_benchmark(c: &mut Criterion) {
+    let snap_shot = snap_shot(&DataBase::from_env().unwrap());
 
     c.bench_function("snap_shot loading", |b| {
         b.iter(|| {
-            
-            
             let db = DataBase::from_env().unwrap();
 
             let _result = db.rollback(
                 black_box(
-                    SQL::from_file(
-                        "C:\\Users\\hasin\\Documents\\Portfolio\\DataBaseManager\\snap_shots\\snap_shot_1672406911.sql"
-                    ).unwrap()
+                    snap_shot.clone()
                 )
             );
         });
     });
+
+    c.bench_function("snap_shot parallel loading", |b| {
+        b.iter(|| {
+            let db = DataBase::from_env().unwrap();
+
+            let _result = db.parallel_rollback(
+                black_box(
+                    snap_shot.clone()
+                ),
+                4,
+                |_progress| {}
+            );
+        });
+    });
 }
 
 criterion_group!{
@@ -31,4 +51,4 @@ criterion_group!{
         .sample_size(70);
     targets = criterion_benchmark
 }
-criterion_main!(benches);
\ No newline at end of file
+criterion_main!(benches);
@@ -0,0 +1,209 @@
+use std::{fmt, fs::File, io::{self, BufRead, BufReader, BufWriter, Read, Write}};
+
+use flate2::{read::MultiGzDecoder, write::GzEncoder};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// The first bytes of a gzip file
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+/// The first bytes of a zstd frame
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// Enum representing the compression of a snapshot file
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Compression {
+    /// Plain sql
+    #[default]
+    None,
+    /// gzip compressed sql
+    Gzip,
+    /// zstd compressed sql
+    Zstd,
+}
+
+impl Compression {
+    /// Returns the extension added after `.sql` to files with the compression.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Compression::None => "",
+            Compression::Gzip => ".gz",
+            Compression::Zstd => ".zst",
+        }
+    }
+
+    /// Returns the compression of a file from its first bytes.
+    pub fn detect(header: &[u8]) -> Compression {
+        if header.starts_with(&GZIP_MAGIC) {
+            return Compression::Gzip;
+        }
+        if header.starts_with(&ZSTD_MAGIC) {
+            return Compression::Zstd;
+        }
+        Compression::None
+    }
+
+    /// Returns a writer compressing everything written to `file`.
+    pub fn writer(&self, file: File) -> Result<CompressedWriter, io::Error> {
+        let file = ChecksumWriter::new(BufWriter::new(file));
+
+        Ok(
+            match self {
+                Compression::None => CompressedWriter::None(file),
+                Compression::Gzip => CompressedWriter::Gzip(GzEncoder::new(file, flate2::Compression::default())),
+                Compression::Zstd => CompressedWriter::Zstd(zstd::Encoder::new(file, zstd::DEFAULT_COMPRESSION_LEVEL)?),
+            }
+        )
+    }
+}
+
+impl fmt::Display for Compression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Compression::None => write!(f, "none"),
+            Compression::Gzip => write!(f, "gzip"),
+            Compression::Zstd => write!(f, "zstd"),
+        }
+    }
+}
+
+/// Returns a reader of a file, decompressing it if it is gzip or zstd compressed.
+pub fn open(file_path: &str) -> Result<Box<dyn Read>, io::Error> {
+    let mut file = BufReader::new(File::open(file_path)?);
+
+    let compression = Compression::detect(file.fill_buf()?);
+
+    Ok(
+        match compression {
+            Compression::None => Box::new(file),
+            Compression::Gzip => Box::new(MultiGzDecoder::new(file)),
+            Compression::Zstd => Box::new(zstd::Decoder::with_buffer(file)?),
+        }
+    )
+}
+
+/// Returns the sha256 checksum of a file, as lowercase hex.
+pub fn checksum(file_path: &str) -> Result<String, io::Error> {
+    let mut writer = ChecksumWriter::new(io::sink());
+
+    io::copy(&mut File::open(file_path)?, &mut writer)?;
+
+    Ok(writer.finish().1)
+}
+
+/// ChecksumWriter struct defines a writer computing the sha256 checksum of everything written through it
+pub struct ChecksumWriter<W: Write>{
+    writer: W,
+    hasher: Sha256,
+}
+
+impl<W: Write> ChecksumWriter<W> {
+    pub fn new(writer: W) -> ChecksumWriter<W> {
+        ChecksumWriter { writer, hasher: Sha256::new() }
+    }
+
+    /// Returns the inner writer & the checksum of everything written, as lowercase hex.
+    pub fn finish(self) -> (W, String) {
+        let checksum = self.hasher.finalize()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+
+        (self.writer, checksum)
+    }
+}
+
+impl<W: Write> Write for ChecksumWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.writer.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// CompressedWriter enum defines a writer compressing a file, while computing the checksum of the written file
+pub enum CompressedWriter{
+    None(ChecksumWriter<BufWriter<File>>),
+    Gzip(GzEncoder<ChecksumWriter<BufWriter<File>>>),
+    Zstd(zstd::Encoder<'static, ChecksumWriter<BufWriter<File>>>),
+}
+
+impl CompressedWriter {
+    /// Finishes the compressed stream & flushes the file.
+    ///
+    /// Returns the sha256 checksum of the file, as lowercase hex.
+    pub fn finish(self) -> Result<String, io::Error> {
+        let file = match self {
+            CompressedWriter::None(file) => file,
+            CompressedWriter::Gzip(encoder) => encoder.finish()?,
+            CompressedWriter::Zstd(encoder) => encoder.finish()?,
+        };
+
+        let (mut file, checksum) = file.finish();
+        file.flush()?;
+
+        Ok(checksum)
+    }
+}
+
+impl Write for CompressedWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            CompressedWriter::None(file) => file.write(buf),
+            CompressedWriter::Gzip(encoder) => encoder.write(buf),
+            CompressedWriter::Zstd(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            CompressedWriter::None(file) => file.flush(),
+            CompressedWriter::Gzip(encoder) => encoder.flush(),
+            CompressedWriter::Zstd(encoder) => encoder.flush(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(unused_imports)]
+    use std::{fs::{self, File}, io::Write};
+
+    use crate::{backend::sql::SQL, test_tools::dir_env::DirEnv};
+
+    use super::{Compression, checksum};
+
+    #[test]
+    fn compression_test_1() {
+        let _dir = DirEnv::new("compression_test_1");
+
+        let cmds: Vec<SQL> = vec![
+            SQL::new("CREATE TABLE patients (id int(11),name varchar(255), PRIMARY KEY(id))").unwrap(),
+            SQL::new("INSERT INTO patients(id,name) VALUES (1,'Doe'),(2,'Roe')").unwrap(),
+        ];
+
+        for compression in [Compression::None, Compression::Gzip, Compression::Zstd] {
+            let path = format!("compression_test_1/snap_shot.sql{}", compression.extension());
+
+            let mut file = compression.writer(File::create(&path).unwrap()).unwrap();
+            cmds.iter().for_each(|cmd| cmd.write_to(&mut file).unwrap());
+            let written_checksum = file.finish().unwrap();
+
+            assert_eq!(Compression::detect(&fs::read(&path).unwrap()), compression);
+            assert_eq!(checksum(&path).unwrap(), written_checksum);
+            assert_eq!(SQL::from_file(&path).unwrap(), cmds);
+        }
+    }
+
+    #[test]
+    fn checksum_test_1() {
+        let _dir = DirEnv::new("checksum_test_1");
+
+        fs::write("checksum_test_1/file.sql", "abc").unwrap();
+
+        assert_eq!(checksum("checksum_test_1/file.sql").unwrap(), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+    }
+}
@@ -1,10 +1,10 @@
 use crate::backend::relation::RelationMethods;
 
-use std::{fmt, env::{self, VarError}, collections::HashMap};
+use std::{fmt, io, env::{self, VarError}, collections::HashMap, sync::{Mutex, mpsc, atomic::{AtomicBool, Ordering}}, thread, cell::RefCell};
 
-use mysql::{prelude::*, Opts, Conn, Row, Error, TxOpts};
+use mysql::{prelude::*, Opts, Conn, Pool, Row, Error, TxOpts};
 
-use super::{sql::{SQL, QDL}, relation::{Relation, paths::{get_dependency_tree, get_generation_path}}};
+use super::{sql::{SQL, QDL, QML}, restore::{RestorePlan, TableRestore, Progress, batch_inserts, trigger_statements, orphan_checks}, dump::{DumpProgress, DumpError, PAGE_SIZE}, relation::{Relation, information_schema, table::Table, diff::Change, table::Constraint, paths::{get_dependency_tree, get_generation_path, get_cycles, get_deferred_foreign_keys, get_referenced_tables, DeferredForeignKey}}, snapshot::SnapShotKind};
 
 pub trait DatabaseExecute{
     type RowError;
@@ -12,6 +12,11 @@ pub trait DatabaseExecute{
     fn execute<T,F>(&self, row_map: F) -> Result<Vec<T>, Self::RowError> where F : FnMut(Result<Row, Error>) -> T;
 }
 
+/// A trait representing a connection that `SQL` commands can be executed on, mapping the rows returned by the command with `row_map`.
+pub trait Executor{
+    fn execute<E, F>(&self, cmd: &SQL, row_map: F) -> Result<Vec<E>, Error> where F : FnMut(Result<Row, Error>) -> E;
+}
+
 /// An enum representing errors that may occur when interacting with a database.
 #[derive(Debug)]
 pub enum DatabaseError{
@@ -32,6 +37,12 @@ macro_rules! load_env_var {
     };
 }
 
+/// The `max_allowed_packet` of MySQL 5.7, used when the server's value can't be read.
+const DEFAULT_MAX_ALLOWED_PACKET: usize = 4 * 1024 * 1024;
+
+/// The bytes of a packet reserved for anything but the statement.
+const PACKET_OVERHEAD: usize = 1024;
+
 /// A struct representing a database connection.
 #[derive(Debug)]
 pub struct DataBase {
@@ -79,16 +90,52 @@ impl DataBase {
         }
     }
 
+    /// Returns a `DataBase` connecting to another database on the same server with the same credentials.
+    ///
+    /// Returns `None` if the connection failed.
+    pub fn with_name(&self, name: &str) -> Option<DataBase> {
+        DataBase::new(
+            self.host.clone(),
+            self.port.clone(),
+            name.to_string(),
+            self.username.clone(),
+            self.password.clone(),
+        )
+    }
+
+    /// Returns the hostname & port of the database in the form of `host:port`.
+    pub fn host(&self) -> String {
+        format!("{}:{}", self.host, self.port)
+    }
+
+    /// Returns the name of the database.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the version of the database server (e.g. `8.0.33`), or `None` if it can't be read.
+    pub fn server_version(&self) -> Option<String> {
+        self.execute(
+            &SQL::Select(QDL(String::from("SELECT VERSION()"))),
+            |row| row.ok().and_then(|row| row.get::<String, usize>(0))
+        )
+        .ok()
+        .and_then(|rows| rows.into_iter().flatten().next())
+    }
+
     /// Gets a connection to the database using the connection information stored in this `DataBase`.
     fn get_conn(&self) -> mysql::Conn {
+        Conn::new(self.get_opts()).unwrap()
+    }
+
+    /// Gets the connection options of the database from the connection information stored in this `DataBase`.
+    fn get_opts(&self) -> Opts {
         let url = format!(
             "mysql://{}:{}@{}:{}/{}",
             self.username, self.password, self.host, self.port, self.name
         );
 
-        let url: Opts = Opts::from_url(&url).unwrap();
-
-        Conn::new(url).unwrap()
+        Opts::from_url(&url).unwrap()
     }
 
     /// Tests the connection to the database by sending a "ping" query.
@@ -110,6 +157,26 @@ impl DataBase {
         }
     }
 
+    /// Returns the maximum length of a statement in bytes, derived from the server's `max_allowed_packet`.
+    ///
+    /// Falls back to MySQL's default `max_allowed_packet` if the value can't be read.
+    pub fn max_statement_size(&self) -> usize {
+        let max_allowed_packet = self.execute(
+            &SQL::Select(QDL(String::from("SELECT @@max_allowed_packet"))),
+            |row| row.ok().and_then(|row| row.get::<u64, usize>(0))
+        );
+
+        let max_allowed_packet = match max_allowed_packet {
+            Ok(rows) => rows.into_iter()
+                .flatten()
+                .next()
+                .map_or(DEFAULT_MAX_ALLOWED_PACKET, |size| size as usize),
+            Err(_) => DEFAULT_MAX_ALLOWED_PACKET,
+        };
+
+        max_allowed_packet.saturating_sub(PACKET_OVERHEAD).max(1)
+    }
+
     /// Executes a given `SQL` command on the database and maps the rows returned by the query to a type `E` using the provided function `row_map`.
     ///
     /// # Arguments
@@ -139,6 +206,18 @@ impl DataBase {
                 }
             }
         }
+        else if !cmd.is_preparable() {
+            execute = {
+                let execute = tx.query_iter(cmd.to_string());
+                match execute {
+                    Ok(iter) => {
+                        rows = iter.map(row_map).collect();
+                        None
+                    },
+                    Err(err) => Some(err),
+                }
+            }
+        }
         else {
             let statement = tx.prep(cmd.to_string());
 
@@ -180,6 +259,11 @@ impl DataBase {
     pub fn execute_multiple(&self, commands: &Vec<SQL>) -> Result<(), Error> {
         let mut conn = self.get_conn();
 
+        DataBase::execute_multiple_on(&mut conn, commands)
+    }
+
+    /// Executes a list of `SQL` commands on a connection as a single transaction.
+    fn execute_multiple_on(conn: &mut Conn, commands: &[SQL]) -> Result<(), Error> {
         let mut tx = match conn.start_transaction(TxOpts::default()) {
             Ok(tx) => tx,
             Err(err) => {
@@ -191,6 +275,16 @@ impl DataBase {
         let mut fail : Option<Error> = None;
 
         for sql in commands{
+            //stored programs are not supported by the prepared statement protocol
+            if !sql.is_preparable() {
+                if let Err(err) = tx.query_drop(sql.to_string()) {
+                    log::error!("Failed to execute command({}) - Err:{:?}", sql.to_string(), err);
+                    fail = Some(err);
+                    break;
+                }
+                continue;
+            }
+
             let statement = tx.prep(sql.to_string())?;
 
             match tx.exec_iter(&statement, ()) {
@@ -226,85 +320,258 @@ impl DataBase {
     }
 
     /// Returns vector of `SQL` to recreate the current state of the database
-    pub fn get_snapshot(&self) -> Vec<SQL> {
-        let relations = Relation::get_relations().unwrap();
+    ///
+    /// Every statement is held in memory; see `stream_snapshot` to write a snapshot of a large database.
+    ///
+    /// Returns `DumpError` if the relations or rows of the database fail to be read
+    pub fn get_snapshot(&self) -> Result<Vec<SQL>, DumpError> {
+        let mut cmds: Vec<SQL> = Vec::new();
+
+        self.stream_snapshot(
+            false,
+            &SnapShotKind::Full,
+            &AtomicBool::new(false),
+            |batch| {
+                cmds.extend_from_slice(batch);
+                Ok(())
+            },
+            |_| {}
+        )?;
+
+        Ok(cmds)
+    }
+
+    /// Reads the `SQL` to recreate the state of the database at a single point in time, passing the statements to `write` as they are read
+    ///
+    /// The schema & every row are read on a single connection inside `START TRANSACTION WITH CONSISTENT SNAPSHOT`, so writes made while the snapshot is taken
+    /// don't leave rows referencing missing rows. The transaction only isolates InnoDB tables: with `read_lock`, the tables are also locked with
+    /// `FLUSH TABLES WITH READ LOCK` until the snapshot is taken, blocking every write to non-transactional tables (e.g. MyISAM) in the meantime.
+    ///
+    /// Rows are read `PAGE_SIZE` rows at a time, using keyset pagination on every column of the primary key, so only a page of rows is held in memory at once.
+    /// Tables without a primary key are read in a single query whose rows are consumed as they arrive.
+    ///
+    /// # Arguments
+    ///
+    /// * `read_lock` - Whether writes to every table are blocked while the snapshot is taken
+    /// * `kind` - The relations & statements held by the snapshot
+    /// * `cancel` - Stops the snapshot before the next page of rows once set
+    /// * `write` - Called with the statements in order of execution
+    /// * `progress` - Called with the progress of the snapshot
+    ///
+    /// Returns Error if there is a failure to connect, to lock the tables, to read the database or to write the statements, if the snapshot was cancelled,
+    /// or if a table of a subset doesn't exist
+    pub fn stream_snapshot<W, P>(&self, read_lock: bool, kind: &SnapShotKind, cancel: &AtomicBool, mut write: W, mut progress: P) -> Result<(), DumpError> where W: FnMut(&[SQL]) -> Result<(), io::Error>, P: FnMut(DumpProgress) {
+        let max_statement_size = self.max_statement_size();
+
+        let read = ConsistentRead::start(self.get_conn(), read_lock)?;
+
+        let relations = information_schema::load(&read)?;
 
         let dependencies = get_dependency_tree(&relations);
 
-        let generation_order = get_generation_path(&relations, &dependencies);
+        let (schema, data) = match kind {
+            SnapShotKind::SchemaOnly => (true, false),
+            SnapShotKind::DataOnly => (false, true),
+            _ => (true, true),
+        };
 
-        let relation_cmd: Vec<SQL> = generation_order.iter()
-            .map(|index| {
-                relations[*index].create().into()
+        //a subset of tables also holds every table they reference, & no other relation
+        let included: Vec<bool> = match kind {
+            SnapShotKind::Tables(names) => {
+                let indexes: Vec<usize> = (0..relations.len())
+                    .filter(|index| matches!(&relations[*index], Relation::Table(table) if names.contains(&table.name)))
+                    .collect();
+
+                let unknown: Vec<String> = names.iter()
+                    .filter(|name| !indexes.iter().any(|index| relations[*index].name() == **name))
+                    .cloned()
+                    .collect();
+
+                if !unknown.is_empty() {
+                    return Err(DumpError::UnknownTables(unknown));
+                }
+
+                let referenced = get_referenced_tables(&relations, &dependencies, &indexes);
+
+                (0..relations.len())
+                    .map(|index| referenced.contains(&index))
+                    .collect()
+            },
+            _ => vec![true; relations.len()],
+        };
+
+        let generation_order: Vec<usize> = get_generation_path(&relations, &dependencies)
+            .into_iter()
+            .filter(|index| included[*index])
+            .collect();
+
+        //a referenced table is always included with the tables referencing it, so cycles are either fully included or excluded
+        let cycles: Vec<Vec<String>> = get_cycles(&dependencies)
+            .iter()
+            .filter(|cycle| included[cycle[0]])
+            .map(|cycle| cycle.iter().map(|index| relations[*index].name()).collect())
+            .collect();
+
+        cycles.iter()
+            .for_each(|names: &Vec<String>| {
+                log::warn!("Tables referencing each other ({}) are created without their foreign keys to each other", names.join(", "));
+            });
+
+        let deferred_foreign_keys: Vec<DeferredForeignKey> = get_deferred_foreign_keys(&relations, &dependencies)
+            .into_iter()
+            .filter(|foreign_key| included[foreign_key.table])
+            .collect();
+
+        let tables: Vec<&Table> = generation_order.iter()
+            .filter_map(|index| {
+                match &relations[*index] {
+                    Relation::Table(table) => Some(table),
+                    _ => None,
+                }
             })
             .collect();
 
-        let mut insertion_cmd: Vec<SQL> = generation_order.iter()
+        progress(DumpProgress::Schema { tables: tables.len(), cycles });
+
+        //events are created after insertion so they aren't scheduled while rows are restored
+        let relation_cmd: Vec<SQL> = generation_order.iter()
+            .filter(|index| !matches!(relations[**index], Relation::Event(_)))
             .map(|index| {
-                &relations[*index]
-            })
-            .filter(|relation| {
-                match relation{
-                    Relation::Table(_) => true,
-                    Relation::View(_) => false,
+                match &relations[*index] {
+                    Relation::Table(table) => {
+                        let attributes: Vec<String> = deferred_foreign_keys.iter()
+                            .filter(|foreign_key| foreign_key.table == *index)
+                            .map(|foreign_key| foreign_key.attribute.clone())
+                            .collect();
+
+                        Relation::Table(table.without_foreign_keys(&attributes)).create().into()
+                    },
+                    relation => relation.create().into(),
                 }
             })
-            .map(|relation| {
-                match relation {
-                    Relation::Table(table) => table,
-                    Relation::View(_) => panic!(),
+            .collect();
+
+        if schema {
+            write(&relation_cmd)?;
+        }
+
+        for (index, table) in tables.iter().enumerate() {
+            if !data {
+                break;
+            }
+
+            //a snapshot of the data only replaces the rows of existing tables
+            if !schema {
+                write(&[SQL::Delete(QML(format!("DELETE FROM {}", table.name)))])?;
+            }
+
+            let mut rows: Vec<HashMap<String, String>> = Vec::with_capacity(PAGE_SIZE);
+            let mut written: usize = 0;
+
+            //rows are inserted in batches rather than a statement per row
+            let mut write_rows = |rows: &mut Vec<HashMap<String, String>>| -> Result<(), DumpError> {
+                if cancel.load(Ordering::Relaxed) {
+                    return Err(DumpError::Cancelled);
                 }
-            })
-            .flat_map(|table| {
-                let result = table.select()
-                    .execute(
-                        |row| {
-                        if let Err(_err) = row {
-                            return None
-                        }
-                        let row = row.unwrap();
-
-                        let mut attributes = HashMap::new();
-
-                        row.columns()
-                        .iter()
-                        .map(|column| {
-                            column.name_str().to_string()
-                        }).zip(
-                            row.unwrap().iter()
-                                .map(|val| {
-                                    val.as_sql(false).to_string()
-                                })
-                        ).for_each(|(column, value)| {
-                            attributes.insert(
-                                column,
-                                value
-                            );
+
+                let cmds: Vec<SQL> = table.insert_batches(rows, max_statement_size)
+                    .into_iter()
+                    .map(SQL::from)
+                    .collect();
+
+                write(&cmds)?;
+
+                written += rows.len();
+                rows.clear();
+
+                progress(DumpProgress::Rows { name: table.name.clone(), rows: written, table: index, tables: tables.len() });
+
+                Ok(())
+            };
+
+            let primary_key = table.primary_key_names();
+
+            match primary_key.is_empty() {
+                false => {
+                    let mut after: Option<Vec<String>> = None;
+
+                    loop {
+                        let page = table.select_page(after.as_deref(), PAGE_SIZE).unwrap();
+
+                        read.for_each_row(&SQL::Select(page), |row| -> Result<(), DumpError> {
+                            rows.push(row_values(row));
+                            Ok(())
+                        })?;
+
+                        let last_page = rows.len() < PAGE_SIZE;
+                        after = rows.last().and_then(|row| {
+                            primary_key.iter()
+                                .map(|column| row.get(*column).cloned())
+                                .collect::<Option<Vec<String>>>()
                         });
 
-                        Some(attributes)
+                        write_rows(&mut rows)?;
+
+                        if last_page || after.is_none() {
+                            break;
+                        }
                     }
-                );
+                },
+                true => {
+                    //tables without a primary key can't be paginated, so their rows are written as they arrive
+                    read.for_each_row(&SQL::Select(table.select()), |row| -> Result<(), DumpError> {
+                        rows.push(row_values(row));
+
+                        match rows.len() < PAGE_SIZE {
+                            true => Ok(()),
+                            false => write_rows(&mut rows),
+                        }
+                    })?;
+
+                    write_rows(&mut rows)?;
+                },
+            }
+        }
+
+        //foreign keys between tables referencing each other are added once every row is inserted
+        let foreign_key_cmd: Vec<SQL> = deferred_foreign_keys.iter()
+            .flat_map(|foreign_key| {
+                Change::AddForeignKey {
+                    table: relations[foreign_key.table].name(),
+                    attribute: foreign_key.attribute.clone(),
+                    foreign_key: foreign_key.foreign_key.clone()
+                }.to_sql()
+            })
+            .collect();
 
-                if let Err(_err) = result {
-                    todo!()
+        //triggers are created after insertion so they don't fire on restored rows
+        let trigger_cmd: Vec<SQL> = generation_order.iter()
+            .flat_map(|index| {
+                match &relations[*index] {
+                    Relation::Table(table) => table.triggers.iter()
+                        .map(|trigger| trigger.create().into())
+                        .collect(),
+                    _ => Vec::new(),
                 }
+            })
+            .collect();
 
-                let values = result.unwrap();
-                
-                values.iter()
-                .filter_map(|p| {
-                    p.clone()
-                })
-                .map(|val| table.insert(&val).unwrap().into())
-                .collect::<Vec<SQL>>()
-            }).collect();
-        
-        let mut cmds = relation_cmd;
+        let event_cmd: Vec<SQL> = generation_order.iter()
+            .filter(|index| matches!(relations[**index], Relation::Event(_)))
+            .map(|index| {
+                relations[*index].create().into()
+            })
+            .collect();
 
-        cmds.append(&mut insertion_cmd);
+        if schema {
+            write(&foreign_key_cmd)?;
+            write(&trigger_cmd)?;
+            write(&event_cmd)?;
+        }
+
+        read.finish()?;
 
-        cmds
+        Ok(())
     }
 
     /// Returns Vector of `SQL` to delete all relations from database
@@ -315,13 +582,41 @@ impl DataBase {
 
         let generation_order = get_generation_path(&relations, &dependencies);
 
-        generation_order.iter()
+        //tables referencing each other can't be dropped before the foreign keys between them
+        let foreign_key_cmds: Vec<SQL> = get_deferred_foreign_keys(&relations, &dependencies)
+            .iter()
+            .filter(|foreign_key| matches!(&foreign_key.foreign_key, Constraint::ForeignKey { table_name, .. } if *table_name != relations[foreign_key.table].name()))
+            .flat_map(|foreign_key| {
+                Change::DropForeignKey {
+                    table: relations[foreign_key.table].name(),
+                    attribute: foreign_key.attribute.clone(),
+                    foreign_key: foreign_key.foreign_key.clone()
+                }.to_sql()
+            })
+            .collect();
+
+        let relation_cmds: Vec<SQL> = generation_order.iter()
             .rev()
             .map(|index| {
                 &relations[*index]
             })
-            .map(|relation| SQL::from(relation.drop()))
-            .collect()
+            .flat_map(|relation| {
+                //triggers are dropped explicitly before the table they are defined on
+                let mut cmds: Vec<SQL> = match relation {
+                    Relation::Table(table) => table.triggers.iter()
+                        .rev()
+                        .map(|trigger| SQL::from(trigger.drop()))
+                        .collect(),
+                    _ => Vec::new(),
+                };
+
+                cmds.push(SQL::from(relation.drop()));
+
+                cmds
+            })
+            .collect();
+
+        [foreign_key_cmds, relation_cmds].concat()
     }
 
     /// Deletes all relations from database
@@ -339,13 +634,252 @@ impl DataBase {
     pub fn rollback(&self, new_state: Vec<SQL>)  -> Result<(), Error> {
         let rollback_cmds : Vec<SQL> = vec![
             self.get_deletion_cmds(),
-            new_state,
+            batch_inserts(new_state, self.max_statement_size()),
         ].iter()
         .flat_map(|sql| sql.clone())
         .collect();
 
         self.execute_multiple(&rollback_cmds)
     }
+
+    /// Applies a partial snapshot (e.g. the data only or a subset of tables), leaving the relations it doesn't hold untouched
+    ///
+    /// The tables created by `new_state` are dropped first, along with their triggers, & every statement runs with foreign key checks disabled,
+    /// so tables & rows can be replaced while other tables reference them. Tables referencing a replaced table keep their foreign keys to it.
+    /// The triggers of the tables whose rows are replaced without recreating them are dropped while their rows are replaced & recreated afterwards,
+    /// even if the restore fails, so they don't fire on restored rows.
+    ///
+    /// As foreign key checks are disabled, the foreign keys of the restored tables & of the tables referencing them are checked once the restore finishes.
+    ///
+    /// # Arguments
+    ///
+    /// * `new_state` - Vector of `SQL` commands of a partial snapshot
+    ///
+    /// Returns the foreign keys with rows referencing missing rows & the number of these rows,
+    /// or Error if there is a failure to connect or a failure to execute a SQL command from `new_state`
+    pub fn partial_rollback(&self, new_state: Vec<SQL>) -> Result<Vec<(String, u64)>, Error> {
+        let tables: Vec<String> = new_state.iter()
+            .filter_map(|cmd| match Relation::from_sql(cmd) {
+                Some(Relation::Table(table)) => Some(table.name),
+                _ => None,
+            })
+            .collect();
+
+        let (drop_triggers, create_triggers) = trigger_statements(&new_state, &information_schema::load(self)?);
+
+        let new_state = batch_inserts(new_state, self.max_statement_size());
+
+        let mut conn = self.get_conn();
+
+        conn.query_drop("SET FOREIGN_KEY_CHECKS = 0")?;
+
+        let result = DataBase::execute_multiple_on(&mut conn, &drop_triggers)
+            .and_then(|_| tables.iter().try_for_each(|table| conn.query_drop(format!("DROP TABLE IF EXISTS {}", table))))
+            .and_then(|_| DataBase::execute_multiple_on(&mut conn, &new_state));
+
+        let result = result.and(DataBase::execute_multiple_on(&mut conn, &create_triggers));
+
+        let _result = conn.query_drop("SET FOREIGN_KEY_CHECKS = 1");
+
+        result?;
+
+        orphan_checks(&new_state, &information_schema::load(self)?).into_iter()
+            .map(|(foreign_key, query)| Ok((foreign_key, conn.query_first::<u64, _>(query.to_string())?.unwrap_or(0))))
+            .filter(|orphans| !matches!(orphans, Ok((_foreign_key, 0))))
+            .collect()
+    }
+
+    /// Updates the state of database to what is defined in the `new_state` parameter, restoring the tables of each dependency layer concurrently
+    ///
+    /// Unlike `rollback`, the restore is not atomic: the tables are restored in a transaction per table over a pool of connections,
+    /// so a failure leaves the tables of the previous layers restored.
+    ///
+    /// # Arguments
+    ///
+    /// * `new_state` - Vector of `SQL` commands to generate new state of database
+    /// * `connections` - The maximum number of tables restored at the same time
+    /// * `progress` - Called with the progress of the restore
+    ///
+    /// Returns Error if there is a failure to connect or a failure to execute a SQL command from `new_state`
+    pub fn parallel_rollback<F>(&self, new_state: Vec<SQL>, connections: usize, mut progress: F) -> Result<(), Error> where F: FnMut(Progress) {
+        let plan = RestorePlan::from(batch_inserts(new_state, self.max_statement_size()));
+        let tables = plan.tables();
+
+        let pool = Pool::new_manual(1, connections.max(1), self.get_opts())?;
+
+        DataBase::execute_multiple_on(pool.get_conn()?.as_mut(), &self.get_deletion_cmds())?;
+        progress(Progress::Cleared);
+
+        let mut restored: usize = 0;
+
+        for (layer, table_restores) in plan.layers.iter().enumerate() {
+            progress(Progress::Layer { layer, layers: plan.layers.len() });
+
+            let queue: Mutex<Vec<&TableRestore>> = Mutex::new(table_restores.iter().rev().collect());
+            let (sender, receiver) = mpsc::channel::<Result<String, Error>>();
+
+            thread::scope(|scope| {
+                (0..connections.max(1).min(table_restores.len()))
+                    .for_each(|_| {
+                        let (queue, sender, pool) = (&queue, sender.clone(), &pool);
+
+                        scope.spawn(move || {
+                            let mut conn = match pool.get_conn() {
+                                Ok(conn) => conn,
+                                Err(err) => {
+                                    let _result = sender.send(Err(err));
+                                    return;
+                                },
+                            };
+
+                            loop {
+                                let table_restore = match queue.lock().unwrap().pop() {
+                                    Some(table_restore) => table_restore,
+                                    None => break,
+                                };
+
+                                let result = DataBase::execute_multiple_on(conn.as_mut(), &table_restore.cmds)
+                                    .map(|_| table_restore.name.clone());
+
+                                let failed = result.is_err();
+                                let _result = sender.send(result);
+
+                                //tables left in the queue are not restored once a table fails
+                                if failed {
+                                    queue.lock().unwrap().clear();
+                                    break;
+                                }
+                            }
+                        });
+                    });
+
+                drop(sender);
+
+                let mut fail: Option<Error> = None;
+
+                for result in receiver {
+                    match result {
+                        Ok(name) => {
+                            restored += 1;
+                            progress(Progress::Table { name, restored, tables });
+                        },
+                        Err(err) => {
+                            log::error!("Failed to restore table - Err:{:?}", err);
+                            fail = fail.or(Some(err));
+                        },
+                    }
+                }
+
+                match fail {
+                    Some(err) => Err(err),
+                    None => Ok(()),
+                }
+            })?;
+        }
+
+        progress(Progress::Remaining { statements: plan.remaining.len() });
+
+        DataBase::execute_multiple_on(pool.get_conn()?.as_mut(), &plan.remaining)?;
+
+        progress(Progress::Done);
+
+        Ok(())
+    }
+}
+
+impl Executor for DataBase {
+    fn execute<E, F>(&self, cmd: &SQL, row_map: F) -> Result<Vec<E>, Error> where F : FnMut(Result<Row, Error>) -> E {
+        DataBase::execute(self, cmd, row_map)
+    }
+}
+
+/// Returns the values of a row as SQL literals, keyed by column name
+fn row_values(row: Row) -> HashMap<String, String> {
+    let columns: Vec<String> = row.columns_ref()
+        .iter()
+        .map(|column| column.name_str().to_string())
+        .collect();
+
+    columns.into_iter()
+        .zip(
+            row.unwrap()
+                .iter()
+                .map(|val| val.as_sql(false))
+        )
+        .collect()
+}
+
+/// ConsistentRead struct defines a connection reading the database inside a `START TRANSACTION WITH CONSISTENT SNAPSHOT` transaction,
+/// so every command executed on it sees the database at the same point in time
+///
+/// Dropping it without calling `finish` closes the connection, which also ends the transaction & releases the read lock.
+pub struct ConsistentRead{
+    conn: RefCell<Conn>,
+    /// Whether the tables are locked with `FLUSH TABLES WITH READ LOCK` until the read ends.
+    read_lock: bool,
+}
+
+impl ConsistentRead {
+    /// Starts a consistent read on a connection, locking every table first if `read_lock` is set.
+    ///
+    /// Returns Error if the tables can't be locked (e.g. the user lacks the `RELOAD` privilege) or the transaction can't be started
+    pub fn start(mut conn: Conn, read_lock: bool) -> Result<ConsistentRead, Error> {
+        conn.query_drop("SET SESSION TRANSACTION ISOLATION LEVEL REPEATABLE READ")?;
+
+        //the global read lock isn't released by starting the transaction, only by UNLOCK TABLES
+        if read_lock {
+            conn.query_drop("FLUSH TABLES WITH READ LOCK")?;
+        }
+
+        if let Err(err) = conn.query_drop("START TRANSACTION WITH CONSISTENT SNAPSHOT") {
+            if read_lock {
+                let _result = conn.query_drop("UNLOCK TABLES");
+            }
+            return Err(err);
+        }
+
+        Ok(ConsistentRead { conn: RefCell::new(conn), read_lock })
+    }
+
+    /// Ends the read, committing the transaction & releasing the read lock.
+    pub fn finish(self) -> Result<(), Error> {
+        let mut conn = self.conn.borrow_mut();
+
+        conn.query_drop("COMMIT")?;
+
+        if self.read_lock {
+            conn.query_drop("UNLOCK TABLES")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl ConsistentRead {
+    /// Executes a `SQL` command, passing every row to `row_map` as it arrives instead of collecting the rows
+    ///
+    /// Stops at the first error returned by `row_map`.
+    pub fn for_each_row<E, F>(&self, cmd: &SQL, mut row_map: F) -> Result<(), E> where E: From<Error>, F: FnMut(Row) -> Result<(), E> {
+        let mut conn = self.conn.borrow_mut();
+
+        for row in conn.query_iter(cmd.to_string())? {
+            row_map(row?)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Executor for ConsistentRead {
+    fn execute<E, F>(&self, cmd: &SQL, row_map: F) -> Result<Vec<E>, Error> where F : FnMut(Result<Row, Error>) -> E {
+        let mut conn = self.conn.borrow_mut();
+
+        let rows = conn.query_iter(cmd.to_string())?
+            .map(row_map)
+            .collect();
+
+        Ok(rows)
+    }
 }
 
 impl fmt::Display for DataBase {
@@ -365,7 +899,7 @@ impl fmt::Display for DataBase {
 #[cfg(test)]
 mod tests{
     #[allow(unused_imports)]
-    use std::{thread, time::Duration};
+    use std::{thread, time::Duration, collections::HashSet};
 
     #[allow(unused_imports)]
     use lazy_static::lazy_static;
@@ -375,7 +909,7 @@ mod tests{
     use serial_test::serial;
 
     #[allow(unused_imports)]
-    use crate::{backend::sql::{SQL, DDL, QML}, test_tools::db_env::DbEnv};
+    use crate::{backend::{sql::{SQL, DDL, QML}, dump::PAGE_SIZE, snapshot_diff::SnapShotState}, test_tools::db_env::DbEnv};
 
     #[allow(unused_imports)]
     use super::DataBase;
@@ -625,6 +1159,7 @@ mod tests{
         let actual: Vec<String> = DataBase::from_env()
             .unwrap()
             .get_snapshot()
+            .unwrap()
             .iter()
             .filter(|cmd| {
                 lazy_static!{
@@ -678,6 +1213,7 @@ mod tests{
         let actual: Vec<String> = DataBase::from_env()
             .unwrap()
             .get_snapshot()
+            .unwrap()
             .iter()
             .filter(|cmd| {
                 lazy_static!{
@@ -716,21 +1252,49 @@ mod tests{
             .for_each(|(actual, expected)| assert_eq!(actual, expected));
     }
 
+    #[test]
+    #[serial]
+    fn get_snapshot_test_3() {
+        //the first page ends part way through the rows of order 333, which must still be read in full
+        let rows: Vec<String> = (0..PAGE_SIZE + 2)
+            .map(|index| format!("({},{})", index / 3, index % 3))
+            .collect();
+
+        let _env = DbEnv::new(
+            vec![
+                SQL::new("CREATE TABLE order_items (order_id INT, product_id INT, PRIMARY KEY(order_id, product_id))").unwrap(),
+                SQL::new(&format!("INSERT INTO order_items (order_id, product_id) VALUES {}", rows.join(","))).unwrap(),
+            ],
+            vec![
+                SQL::new("DROP TABLE order_items").unwrap(),
+            ]
+        );
+
+        let state = SnapShotState::from_db(&DataBase::from_env().unwrap()).unwrap();
+
+        let actual: HashSet<(String, String)> = state.rows["order_items"].iter()
+            .map(|row| (row["order_id"].clone(), row["product_id"].clone()))
+            .collect();
+
+        assert_eq!(state.rows["order_items"].len(), PAGE_SIZE + 2);
+        assert_eq!(actual.len(), PAGE_SIZE + 2);
+    }
+
     #[test]
     #[serial]
     fn deletion_test() {
         let db = DataBase::from_env().unwrap();
 
-        for cmd in db.get_snapshot() {
+        for cmd in db.get_snapshot().unwrap() {
             println!("{:?}", cmd);
         }
 
         let _env = DbEnv::new(
             db.get_deletion_cmds(),
-            db.get_snapshot()
+            db.get_snapshot().unwrap()
         );
 
-        let actual = db.get_snapshot();
+        let actual = db.get_snapshot().unwrap();
 
         assert_eq!(actual, vec![])
     }
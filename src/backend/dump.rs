@@ -0,0 +1,85 @@
+use std::{fmt, io};
+
+use mysql::Error;
+
+/// The number of rows read from a table at a time while a snapshot is written
+pub const PAGE_SIZE: usize = 1000;
+
+/// Enum representing the progress of a snapshot being written
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DumpProgress {
+    /// Indicates that the relations of the database were read, along with the names of the tables referencing each other
+    Schema{tables: usize, cycles: Vec<Vec<String>>},
+    /// Indicates that a page of rows of a table was written
+    Rows{name: String, rows: usize, table: usize, tables: usize},
+    /// Indicates that the snapshot was saved, along with the names of the tables referencing each other & the number of snapshots pruned afterwards
+    Done{cycles: Vec<Vec<String>>, pruned: usize},
+    /// Indicates that the snapshot was cancelled & its file removed
+    Cancelled,
+    /// Indicates that the snapshot failed & its file was removed
+    Failed(String),
+}
+
+impl fmt::Display for DumpProgress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DumpProgress::Schema { tables, .. } => write!(f, "reading {} table(s)", tables),
+            DumpProgress::Rows { name, rows, table, tables } => write!(f, "written {} row(s) of {} ({}/{} tables)", rows, name, table + 1, tables),
+            DumpProgress::Done { cycles, pruned } => {
+                write!(f, "added snapshot")?;
+
+                if *pruned > 0 {
+                    write!(f, ", pruned {} snapshot(s)", pruned)?;
+                }
+
+                if cycles.is_empty() {
+                    return Ok(());
+                }
+
+                let cycles: Vec<String> = cycles.iter()
+                    .map(|cycle| format!("({})", cycle.join(", ")))
+                    .collect();
+
+                write!(f, ", tables referencing each other {} are restored before their foreign keys to each other", cycles.join(" "))
+            },
+            DumpProgress::Cancelled => write!(f, "cancelled snapshot"),
+            DumpProgress::Failed(err) => write!(f, "failed to add snapshot: {}", err),
+        }
+    }
+}
+
+/// Enum representing the errors that can occur while a snapshot is written
+#[derive(Debug)]
+pub enum DumpError {
+    /// Indicates that the database couldn't be read
+    Database(Error),
+    /// Indicates that the statements couldn't be written
+    Write(io::Error),
+    /// Indicates that the snapshot was cancelled
+    Cancelled,
+    /// Indicates that tables of a subset don't exist
+    UnknownTables(Vec<String>),
+}
+
+impl fmt::Display for DumpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DumpError::Database(err) => write!(f, "{}", err),
+            DumpError::Write(err) => write!(f, "{}", err),
+            DumpError::Cancelled => write!(f, "cancelled"),
+            DumpError::UnknownTables(tables) => write!(f, "unknown table(s) {}", tables.join(", ")),
+        }
+    }
+}
+
+impl From<Error> for DumpError {
+    fn from(err: Error) -> Self {
+        DumpError::Database(err)
+    }
+}
+
+impl From<io::Error> for DumpError {
+    fn from(err: io::Error) -> Self {
+        DumpError::Write(err)
+    }
+}
@@ -0,0 +1,714 @@
+use std::{
+    fmt,
+    fs::{self, File},
+    io::{Read, Write},
+    path::PathBuf,
+    env,
+};
+
+use regex::Regex;
+use lazy_static::lazy_static;
+use sha2::{Sha256, Digest};
+use time::OffsetDateTime;
+
+use super::{data_base::DataBase, sql::{SQL, DDL, QDL, QML}};
+
+/// The table recording the migrations applied to a database
+pub const MIGRATIONS_TABLE: &str = "schema_migrations";
+
+/// Enum representing the errors that can occur when applying or reverting migrations
+#[derive(Debug)]
+pub enum Error {
+    /// Indicates that there was an error while reading or writing a migration script
+    File(std::io::Error),
+    /// Indicates that there was an error while executing a migration on the database
+    Execution(mysql::Error),
+    /// Indicates that the name of a new migration is not a valid identifier
+    InvalidName(String),
+    /// Indicates that applied migrations were edited after they were applied
+    Modified(Vec<String>),
+    /// Indicates that the scripts of an applied migration no longer exist
+    Missing(String),
+    /// Indicates that a migration can't be reverted as it has no down script
+    Irreversible(String),
+    /// Indicates that migrations failed part way through & must be resolved before any other migration is applied or reverted
+    Dirty(Vec<String>),
+}
+
+/// Struct representing a migration stored in the migrations directory
+///
+/// A migration is made of an up script named `<version>_<name>.up.sql` & an optional down script named `<version>_<name>.down.sql`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Migration{
+    /// The version of the migration. Migrations are applied in ascending numeric order of version (e.g. `2` before `10`).
+    pub version: String,
+    /// The name of the migration.
+    pub name: String,
+    /// The path of the script applying the migration.
+    pub up: PathBuf,
+    /// The path of the script reverting the migration.
+    pub down: Option<PathBuf>,
+    /// The sha256 checksum of the up & down scripts.
+    pub checksum: String,
+}
+
+impl Migration {
+    /// Returns the commands of the up script.
+    pub fn up_cmds(&self) -> Result<Vec<SQL>, Error> {
+        SQL::from_file(self.up.to_str().unwrap()).map_err(Error::File)
+    }
+
+    /// Returns the commands of the down script.
+    pub fn down_cmds(&self) -> Result<Vec<SQL>, Error> {
+        match &self.down {
+            Some(down) => SQL::from_file(down.to_str().unwrap()).map_err(Error::File),
+            None => Err(Error::Irreversible(self.version.clone())),
+        }
+    }
+}
+
+/// Struct representing a row of the `schema_migrations` table
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AppliedMigration{
+    /// The version of the applied migration.
+    pub version: String,
+    /// The name of the applied migration.
+    pub name: String,
+    /// The checksum of the migration's scripts when it was applied.
+    pub checksum: String,
+    /// When the migration was applied.
+    pub applied_at: String,
+    /// Whether the migration failed after it started being applied, so it may be partially applied.
+    pub dirty: bool,
+}
+
+/// MigrationStatus defines whether a migration is applied to a database
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MigrationStatus{
+    /// The migration has not been applied.
+    Pending,
+    /// The migration has been applied.
+    Applied,
+    /// The migration has been applied, but its scripts were edited since.
+    Modified,
+    /// The migration has been applied, but its scripts no longer exist.
+    Missing,
+    /// The migration failed part way through & may be partially applied.
+    Dirty,
+}
+
+impl fmt::Display for MigrationStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MigrationStatus::Pending => write!(f, "Pending"),
+            MigrationStatus::Applied => write!(f, "Applied"),
+            MigrationStatus::Modified => write!(f, "Modified"),
+            MigrationStatus::Missing => write!(f, "Missing"),
+            MigrationStatus::Dirty => write!(f, "Dirty"),
+        }
+    }
+}
+
+/// Struct representing the status of a single migration
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MigrationState{
+    /// The version of the migration.
+    pub version: String,
+    /// The name of the migration.
+    pub name: String,
+    /// Whether the migration is applied.
+    pub status: MigrationStatus,
+    /// When the migration was applied.
+    pub applied_at: Option<String>,
+}
+
+/// Struct representing the directory containing the migrations of a database
+#[derive(Clone, Debug)]
+pub struct Migrations{
+    /// The path of the migrations directory.
+    pub path: PathBuf,
+}
+
+impl Migrations {
+    /// Returns the migrations stored in the directory ordered by version.
+    ///
+    /// Returns an empty vector if the directory does not exist.
+    pub fn load(&self) -> Result<Vec<Migration>, Error> {
+        lazy_static!{
+            static ref MIGRATION_FILE : Regex = Regex::new(r"^([0-9]+)_([a-zA-Z0-9_]+)\.(up|down)\.[Ss][Qq][Ll]$").unwrap();
+        };
+
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut migrations: Vec<Migration> = Vec::new();
+
+        for entry in fs::read_dir(&self.path).map_err(Error::File)? {
+            let path = entry.map_err(Error::File)?.path();
+
+            let captures = match path.file_name().and_then(|file_name| file_name.to_str()).and_then(|file_name| MIGRATION_FILE.captures(file_name)) {
+                Some(captures) => captures,
+                None => continue,
+            };
+
+            let version = captures.get(1).unwrap().as_str();
+            let name = captures.get(2).unwrap().as_str();
+            let up = captures.get(3).unwrap().as_str() == "up";
+
+            let migration = match migrations.iter_mut().find(|migration| migration.version == version) {
+                Some(migration) => migration,
+                None => {
+                    migrations.push(
+                        Migration {
+                            version: version.to_string(),
+                            name: name.to_string(),
+                            up: PathBuf::new(),
+                            down: None,
+                            checksum: String::new(),
+                        }
+                    );
+                    migrations.last_mut().unwrap()
+                },
+            };
+
+            match up {
+                true => migration.up = path,
+                false => migration.down = Some(path),
+            }
+        }
+
+        migrations.retain(|migration| {
+            if migration.up.as_os_str().is_empty() {
+                log::error!("Migration {}_{} has no up script", migration.version, migration.name);
+            }
+            !migration.up.as_os_str().is_empty()
+        });
+
+        for migration in migrations.iter_mut() {
+            migration.checksum = checksum(migration)?;
+        }
+
+        migrations.sort_by(|a, b| version_order(&a.version).cmp(&version_order(&b.version)));
+
+        Ok(migrations)
+    }
+
+    /// Creates empty up & down scripts for a new migration.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the migration
+    /// * `time_stamp` - The time the migration is created, used as its version
+    pub fn create(&self, name: &str, time_stamp: OffsetDateTime) -> Result<Migration, Error> {
+        lazy_static!{
+            static ref MIGRATION_NAME : Regex = Regex::new(r"^[a-zA-Z0-9_]+$").unwrap();
+        };
+
+        if !MIGRATION_NAME.is_match(name) {
+            return Err(Error::InvalidName(name.to_string()));
+        }
+
+        fs::create_dir_all(&self.path).map_err(Error::File)?;
+
+        let version = format!(
+            "{:04}{:02}{:02}{:02}{:02}{:02}",
+            time_stamp.year(),
+            u8::from(time_stamp.month()),
+            time_stamp.day(),
+            time_stamp.hour(),
+            time_stamp.minute(),
+            time_stamp.second()
+        );
+
+        let up = self.path.join(format!("{}_{}.up.sql", version, name));
+        let down = self.path.join(format!("{}_{}.down.sql", version, name));
+
+        File::create(&up)
+            .and_then(|mut file| file.write_all(format!("-- {} up\n", name).as_bytes()))
+            .map_err(Error::File)?;
+        File::create(&down)
+            .and_then(|mut file| file.write_all(format!("-- {} down\n", name).as_bytes()))
+            .map_err(Error::File)?;
+
+        let mut migration = Migration {
+            version,
+            name: name.to_string(),
+            up,
+            down: Some(down),
+            checksum: String::new(),
+        };
+        migration.checksum = checksum(&migration)?;
+
+        Ok(migration)
+    }
+
+    /// Returns the status of every migration in the directory & every migration applied to the database.
+    pub fn status(&self, db: &DataBase) -> Result<Vec<MigrationState>, Error> {
+        Ok(states(&self.load()?, &applied(db)?))
+    }
+
+    /// Applies pending migrations in order of version & returns the applied migrations.
+    ///
+    /// Nothing is applied if an applied migration was edited or is dirty. Stops at the first migration that fails.
+    ///
+    /// MySQL commits DDL statements implicitly, so a migration failing part way through can't be rolled back. Each migration is therefore recorded
+    /// as dirty before its up script runs & only marked as applied once the script succeeds, so a failed migration stays dirty until it is resolved by `resolve`.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - The database the migrations are applied to
+    /// * `count` - The number of migrations to apply. Every pending migration is applied if `None`.
+    pub fn up(&self, db: &DataBase, count: Option<usize>) -> Result<Vec<Migration>, Error> {
+        let pending = self.pending(db, count)?;
+
+        for migration in &pending {
+            log::info!("Applying migration {}_{}", migration.version, migration.name);
+
+            let up_cmds = migration.up_cmds()?;
+
+            db.execute_multiple(
+                &vec![
+                    SQL::from(
+                        QML(
+                            format!(
+                                "INSERT INTO {} (version, name, checksum, dirty) VALUES ('{}', '{}', '{}', 1)",
+                                MIGRATIONS_TABLE,
+                                migration.version,
+                                migration.name,
+                                migration.checksum
+                            )
+                        )
+                    )
+                ]
+            ).map_err(Error::Execution)?;
+
+            let cmds = [
+                up_cmds,
+                vec![SQL::from(QML(format!("UPDATE {} SET dirty = 0 WHERE version = '{}'", MIGRATIONS_TABLE, migration.version)))],
+            ].concat();
+
+            db.execute_multiple(&cmds).map_err(Error::Execution)?;
+        }
+
+        Ok(pending)
+    }
+
+    /// Returns the migrations `up` would apply, in the order they would be applied, without applying them.
+    ///
+    /// Returns an error if an applied migration was edited or is dirty.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - The database the migrations would be applied to
+    /// * `count` - The number of migrations to apply. Every pending migration is returned if `None`.
+    pub fn pending(&self, db: &DataBase, count: Option<usize>) -> Result<Vec<Migration>, Error> {
+        let migrations = self.load()?;
+        let applied = applied(db)?;
+        let states = states(&migrations, &applied);
+
+        check_dirty(&states)?;
+        check_modified(&states)?;
+
+        Ok(
+            migrations.into_iter()
+                .filter(|migration| !applied.iter().any(|applied| applied.version == migration.version))
+                .take(count.unwrap_or(usize::MAX))
+                .collect()
+        )
+    }
+
+    /// Reverts the most recently applied migrations in reverse order of version & returns the reverted migrations.
+    ///
+    /// Nothing is reverted if a migration is dirty, or if one of the migrations was edited, is missing or has no down script.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - The database the migrations are reverted from
+    /// * `count` - The number of migrations to revert
+    pub fn down(&self, db: &DataBase, count: usize) -> Result<Vec<Migration>, Error> {
+        let reverted = self.reverting(db, count)?;
+
+        for migration in &reverted {
+            log::info!("Reverting migration {}_{}", migration.version, migration.name);
+
+            let cmds = [
+                migration.down_cmds()?,
+                vec![SQL::from(QML(format!("DELETE FROM {} WHERE version = '{}'", MIGRATIONS_TABLE, migration.version)))],
+            ].concat();
+
+            db.execute_multiple(&cmds).map_err(Error::Execution)?;
+        }
+
+        Ok(reverted)
+    }
+
+    /// Returns the migrations `down` would revert, in the order they would be reverted, without reverting them.
+    ///
+    /// Returns an error if a migration is dirty, or if one of the migrations was edited, is missing or has no down script.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - The database the migrations would be reverted from
+    /// * `count` - The number of migrations to revert
+    pub fn reverting(&self, db: &DataBase, count: usize) -> Result<Vec<Migration>, Error> {
+        let migrations = self.load()?;
+        let applied = applied(db)?;
+        let states = states(&migrations, &applied);
+
+        check_dirty(&states)?;
+
+        applied.iter()
+            .rev()
+            .take(count)
+            .map(|applied| {
+                let migration = migrations.iter()
+                    .find(|migration| migration.version == applied.version)
+                    .ok_or_else(|| Error::Missing(applied.version.clone()))?;
+
+                if states.iter().any(|state| state.version == applied.version && state.status == MigrationStatus::Modified) {
+                    return Err(Error::Modified(vec![applied.version.clone()]));
+                }
+                if migration.down.is_none() {
+                    return Err(Error::Irreversible(applied.version.clone()));
+                }
+
+                Ok(migration.clone())
+            })
+            .collect()
+    }
+
+    /// Resolves a dirty migration once the database was repaired by hand & returns whether the migration was dirty.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - The database the migration failed on
+    /// * `version` - The version of the dirty migration
+    /// * `applied` - Whether the migration is recorded as applied, with the checksum of its current scripts, or its record is removed so it is pending again
+    pub fn resolve(&self, db: &DataBase, version: &str, applied: bool) -> Result<bool, Error> {
+        let dirty = self::applied(db)?
+            .iter()
+            .any(|migration| migration.version == version && migration.dirty);
+
+        if !dirty {
+            return Ok(false);
+        }
+
+        let cmd = match applied {
+            true => {
+                let migration = self.load()?
+                    .into_iter()
+                    .find(|migration| migration.version == version)
+                    .ok_or_else(|| Error::Missing(version.to_string()))?;
+
+                format!("UPDATE {} SET dirty = 0, checksum = '{}' WHERE version = '{}'", MIGRATIONS_TABLE, migration.checksum, version)
+            },
+            false => format!("DELETE FROM {} WHERE version = '{}'", MIGRATIONS_TABLE, version),
+        };
+
+        db.execute_multiple(&vec![SQL::from(QML(cmd))]).map_err(Error::Execution)?;
+
+        Ok(true)
+    }
+}
+
+impl Default for Migrations {
+    /// Returns the `migrations` directory of the current directory.
+    fn default() -> Self {
+        let mut path = env::current_dir().unwrap();
+        path.push("migrations");
+
+        Migrations { path }
+    }
+}
+
+/// Returns the migrations applied to a database ordered by version, including the dirty migrations.
+///
+/// Creates the `schema_migrations` table if it does not exist, & adds the `dirty` column to tables created by older versions.
+pub fn applied(db: &DataBase) -> Result<Vec<AppliedMigration>, Error> {
+    db.execute(
+        &SQL::from(
+            DDL(
+                format!(
+                    "CREATE TABLE IF NOT EXISTS {} (version varchar(255) NOT NULL, name varchar(255) NOT NULL, checksum char(64) NOT NULL, applied_at timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP, dirty tinyint(1) NOT NULL DEFAULT 0, PRIMARY KEY(version))",
+                    MIGRATIONS_TABLE
+                )
+            )
+        ),
+        |_| ()
+    ).map_err(Error::Execution)?;
+
+    let dirty_column: Vec<u64> = db.execute(
+        &SQL::Select(QDL(format!("SELECT COUNT(*) FROM information_schema.COLUMNS WHERE TABLE_SCHEMA = DATABASE() AND TABLE_NAME = '{}' AND COLUMN_NAME = 'dirty'", MIGRATIONS_TABLE))),
+        |row| row.ok().and_then(|row| row.get(0)).unwrap_or(0)
+    ).map_err(Error::Execution)?;
+
+    if dirty_column.first() == Some(&0) {
+        db.execute(
+            &SQL::from(DDL(format!("ALTER TABLE {} ADD COLUMN dirty tinyint(1) NOT NULL DEFAULT 0", MIGRATIONS_TABLE))),
+            |_| ()
+        ).map_err(Error::Execution)?;
+    }
+
+    let rows: Vec<Option<AppliedMigration>> = db.execute(
+        &SQL::Select(QDL(format!("SELECT version, name, checksum, CAST(applied_at AS CHAR), dirty FROM {} ORDER BY version", MIGRATIONS_TABLE))),
+        |row| {
+            let row = row.ok()?;
+
+            Some(
+                AppliedMigration {
+                    version: row.get_opt(0)?.ok()?,
+                    name: row.get_opt(1)?.ok()?,
+                    checksum: row.get_opt(2)?.ok()?,
+                    applied_at: row.get_opt(3)?.ok()?,
+                    dirty: row.get_opt::<u8, usize>(4)?.ok()? != 0,
+                }
+            )
+        }
+    ).map_err(Error::Execution)?;
+
+    let mut applied: Vec<AppliedMigration> = rows.into_iter().flatten().collect();
+    applied.sort_by(|a, b| version_order(&a.version).cmp(&version_order(&b.version)));
+
+    Ok(applied)
+}
+
+/// Returns the status of the migrations in a directory & the migrations applied to a database, ordered by version.
+///
+/// # Arguments
+///
+/// * `migrations` - The migrations of the migrations directory
+/// * `applied` - The migrations applied to the database
+pub fn states(migrations: &[Migration], applied: &[AppliedMigration]) -> Vec<MigrationState> {
+    let mut states: Vec<MigrationState> = migrations.iter()
+        .map(|migration| {
+            let applied = applied.iter().find(|applied| applied.version == migration.version);
+
+            MigrationState {
+                version: migration.version.clone(),
+                name: migration.name.clone(),
+                status: match applied {
+                    Some(applied) if applied.dirty => MigrationStatus::Dirty,
+                    Some(applied) if applied.checksum == migration.checksum => MigrationStatus::Applied,
+                    Some(_) => MigrationStatus::Modified,
+                    None => MigrationStatus::Pending,
+                },
+                applied_at: applied.map(|applied| applied.applied_at.clone()),
+            }
+        })
+        .collect();
+
+    applied.iter()
+        .filter(|applied| !migrations.iter().any(|migration| migration.version == applied.version))
+        .for_each(|applied| {
+            states.push(
+                MigrationState {
+                    version: applied.version.clone(),
+                    name: applied.name.clone(),
+                    status: match applied.dirty {
+                        true => MigrationStatus::Dirty,
+                        false => MigrationStatus::Missing,
+                    },
+                    applied_at: Some(applied.applied_at.clone()),
+                }
+            )
+        });
+
+    states.sort_by(|a, b| version_order(&a.version).cmp(&version_order(&b.version)));
+
+    states
+}
+
+/// Returns the key migrations are ordered by, so versions are compared as numbers rather than strings.
+///
+/// Versions too long to be a `u64` are placed last, & versions with the same number are ordered as strings.
+fn version_order(version: &str) -> (u64, &str) {
+    (version.parse::<u64>().unwrap_or(u64::MAX), version)
+}
+
+/// Returns an error listing the versions of the modified migrations, if any.
+fn check_modified(states: &[MigrationState]) -> Result<(), Error> {
+    let modified: Vec<String> = states.iter()
+        .filter(|state| state.status == MigrationStatus::Modified)
+        .map(|state| state.version.clone())
+        .collect();
+
+    match modified.is_empty() {
+        true => Ok(()),
+        false => Err(Error::Modified(modified)),
+    }
+}
+
+/// Returns an error listing the versions of the dirty migrations, if any.
+fn check_dirty(states: &[MigrationState]) -> Result<(), Error> {
+    let dirty: Vec<String> = states.iter()
+        .filter(|state| state.status == MigrationStatus::Dirty)
+        .map(|state| state.version.clone())
+        .collect();
+
+    match dirty.is_empty() {
+        true => Ok(()),
+        false => Err(Error::Dirty(dirty)),
+    }
+}
+
+/// Returns the sha256 checksum of the up & down scripts of a migration.
+fn checksum(migration: &Migration) -> Result<String, Error> {
+    let mut hasher = Sha256::new();
+
+    for path in [Some(&migration.up), migration.down.as_ref()].into_iter().flatten() {
+        let mut contents = Vec::new();
+
+        File::open(path)
+            .and_then(|mut file| file.read_to_end(&mut contents))
+            .map_err(Error::File)?;
+
+        hasher.update(&contents);
+    }
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(unused_imports)]
+    use std::{path::PathBuf, fs};
+
+    use time::macros::datetime;
+
+    use crate::test_tools::{dir_env::DirEnv, file_env::FileEnv};
+
+    use super::{Migrations, Migration, AppliedMigration, MigrationStatus, Error, states, check_dirty};
+
+    #[test]
+    fn load_test_1() {
+        let _dir = DirEnv::new("migrations_load_test_1");
+        let _files = [
+            FileEnv::new("migrations_load_test_1/20230102000000_add_tags.up.sql", "ALTER TABLE tag ADD COLUMN colour CHAR(6);"),
+            FileEnv::new("migrations_load_test_1/20230101000000_create_tag.up.sql", "CREATE TABLE tag (id INT, PRIMARY KEY(id));"),
+            FileEnv::new("migrations_load_test_1/20230101000000_create_tag.down.sql", "DROP TABLE tag;"),
+            FileEnv::new("migrations_load_test_1/notes.txt", ""),
+        ];
+
+        let migrations = Migrations { path: PathBuf::from("migrations_load_test_1") }.load().unwrap();
+
+        assert_eq!(
+            migrations.iter().map(|migration| (migration.version.as_str(), migration.name.as_str(), migration.down.is_some())).collect::<Vec<_>>(),
+            vec![("20230101000000", "create_tag", true), ("20230102000000", "add_tags", false)]
+        );
+        assert_eq!(migrations[0].up_cmds().unwrap().len(), 1);
+        assert_eq!(migrations[0].checksum.len(), 64);
+        assert!(migrations[1].down_cmds().is_err());
+    }
+
+    #[test]
+    fn load_test_2() {
+        //versions are ordered as numbers rather than strings
+        let _dir = DirEnv::new("migrations_load_test_2");
+        let _files = [
+            FileEnv::new("migrations_load_test_2/10_add_y.up.sql", ""),
+            FileEnv::new("migrations_load_test_2/2_add_x.up.sql", ""),
+            FileEnv::new("migrations_load_test_2/1_create_tag.up.sql", ""),
+        ];
+
+        let migrations = Migrations { path: PathBuf::from("migrations_load_test_2") }.load().unwrap();
+
+        assert_eq!(
+            migrations.iter().map(|migration| migration.version.as_str()).collect::<Vec<&str>>(),
+            vec!["1", "2", "10"]
+        );
+        assert_eq!(
+            states(&migrations, &[]).iter().map(|state| state.version.as_str()).collect::<Vec<&str>>(),
+            vec!["1", "2", "10"]
+        );
+    }
+
+    #[test]
+    fn create_test_1() {
+        let _dir = DirEnv::new("migrations_create_test_1");
+        let migrations = Migrations { path: PathBuf::from("migrations_create_test_1") };
+
+        let migration = migrations.create("add_users", datetime!(2023-03-04 05:06:07 UTC)).unwrap();
+
+        assert_eq!(migration.version, "20230304050607");
+        assert!(migration.up.ends_with("20230304050607_add_users.up.sql"));
+        assert_eq!(migrations.load().unwrap(), vec![migration.clone()]);
+        assert!(migration.up_cmds().unwrap().is_empty());
+
+        assert!(migrations.create("add users", datetime!(2023-03-04 05:06:07 UTC)).is_err());
+    }
+
+    #[test]
+    fn checksum_test_1() {
+        let _dir = DirEnv::new("migrations_checksum_test_1");
+        let migrations = Migrations { path: PathBuf::from("migrations_checksum_test_1") };
+
+        let migration = migrations.create("add_users", datetime!(2023-03-04 05:06:07 UTC)).unwrap();
+
+        fs::write(&migration.up, "CREATE TABLE users (id INT);").unwrap();
+
+        assert_ne!(migrations.load().unwrap()[0].checksum, migration.checksum);
+    }
+
+    #[test]
+    fn states_test_1() {
+        let migration = |version: &str, checksum: &str| Migration {
+            version: version.to_string(),
+            name: format!("migration_{}", version),
+            up: PathBuf::new(),
+            down: None,
+            checksum: checksum.to_string(),
+        };
+        let applied = |version: &str, checksum: &str| AppliedMigration {
+            version: version.to_string(),
+            name: format!("migration_{}", version),
+            checksum: checksum.to_string(),
+            applied_at: String::from("2023-01-01 00:00:00"),
+            dirty: false,
+        };
+
+        let actual = states(
+            &[migration("1", "a"), migration("2", "b"), migration("4", "d")],
+            &[applied("1", "a"), applied("2", "x"), applied("3", "c")]
+        );
+
+        assert_eq!(
+            actual.iter().map(|state| (state.version.as_str(), state.status.clone())).collect::<Vec<_>>(),
+            vec![
+                ("1", MigrationStatus::Applied),
+                ("2", MigrationStatus::Modified),
+                ("3", MigrationStatus::Missing),
+                ("4", MigrationStatus::Pending),
+            ]
+        );
+        assert_eq!(actual[3].applied_at, None);
+    }
+
+    #[test]
+    fn states_test_2() {
+        //migrations that failed part way through stay dirty, whatever their checksum, until they are resolved
+        let migration = Migration {
+            version: String::from("1"),
+            name: String::from("migration_1"),
+            up: PathBuf::new(),
+            down: None,
+            checksum: String::from("a"),
+        };
+        let applied = |version: &str, dirty: bool| AppliedMigration {
+            version: version.to_string(),
+            name: format!("migration_{}", version),
+            checksum: String::from("a"),
+            applied_at: String::from("2023-01-01 00:00:00"),
+            dirty,
+        };
+
+        let actual = states(&[migration.clone()], &[applied("1", true), applied("2", true)]);
+
+        assert_eq!(
+            actual.iter().map(|state| (state.version.as_str(), state.status.clone())).collect::<Vec<_>>(),
+            vec![("1", MigrationStatus::Dirty), ("2", MigrationStatus::Dirty)]
+        );
+        assert!(matches!(check_dirty(&actual), Err(Error::Dirty(versions)) if versions == vec!["1", "2"]));
+        assert!(check_dirty(&states(&[migration], &[applied("1", false)])).is_ok());
+    }
+}
@@ -1,4 +1,10 @@
 pub mod relation;
 pub mod data_base;
 pub mod sql;
-pub mod snapshot;
\ No newline at end of file
+pub mod snapshot;
+pub mod migration;
+pub mod restore;
+pub mod dump;
+pub mod compression;
+pub mod snapshot_diff;
+pub mod retention;
\ No newline at end of file
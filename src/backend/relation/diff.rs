@@ -0,0 +1,674 @@
+use std::{fmt, collections::HashMap};
+
+use crate::backend::sql::{SQL, DDL};
+
+use super::{Relation, RelationMethods, table::{Table, Attribute, Constraint}, paths::{get_dependency_tree, get_generation_path, get_deferred_foreign_keys, DeferredForeignKey}};
+
+/// A single difference between two schemas
+#[derive(Clone, Debug)]
+pub enum Change{
+    /// A relation that only exists in the new schema.
+    CreateRelation(Relation),
+    /// A relation that only exists in the old schema, or a view that must be redefined.
+    DropRelation(Relation),
+    /// A column that only exists in the new table.
+    AddColumn{table: String, attribute: Attribute},
+    /// A column that only exists in the old table.
+    DropColumn{table: String, attribute: Attribute},
+    /// A column whose type, `NOT NULL` or `AUTO_INCREMENT` constraint changed.
+    ModifyColumn{table: String, old: Attribute, new: Attribute},
+    /// A unique constraint added to an existing column.
+    AddUnique{table: String, attribute: String},
+    /// A unique constraint removed from a column.
+    DropUnique{table: String, attribute: String},
+    /// A foreign key added to a column.
+    AddForeignKey{table: String, attribute: String, foreign_key: Constraint},
+    /// A foreign key removed from a column.
+    DropForeignKey{table: String, attribute: String, foreign_key: Constraint},
+    /// The primary key of a table changed.
+    ChangePrimaryKey{table: String, old: Option<String>, new: Option<String>},
+}
+
+impl Change {
+    /// Returns the `SQL` commands that apply the change.
+    ///
+    /// A foreign key without a known constraint name can't be dropped, so no command is returned for it.
+    pub fn to_sql(&self) -> Vec<SQL> {
+        let ddl: Vec<DDL> = match self {
+            Change::CreateRelation(relation) => vec![relation.create()],
+            Change::DropRelation(relation) => vec![relation.drop()],
+            Change::AddColumn { table, attribute } => vec![
+                DDL(format!("ALTER TABLE {} ADD COLUMN {}", table, column_definition(attribute, true)))
+            ],
+            Change::DropColumn { table, attribute } => vec![
+                DDL(format!("ALTER TABLE {} DROP COLUMN {}", table, attribute.name))
+            ],
+            Change::ModifyColumn { table, new, .. } => vec![
+                DDL(format!("ALTER TABLE {} MODIFY COLUMN {}", table, column_definition(new, false)))
+            ],
+            //unique columns are indexed with an index named after the column
+            Change::AddUnique { table, attribute } => vec![
+                DDL(format!("ALTER TABLE {} ADD UNIQUE ({})", table, attribute))
+            ],
+            Change::DropUnique { table, attribute } => vec![
+                DDL(format!("ALTER TABLE {} DROP INDEX {}", table, attribute))
+            ],
+            Change::AddForeignKey { table, attribute, foreign_key } => match foreign_key.foreign_key_clause(attribute) {
+                Some(clause) => vec![
+                    DDL(format!("ALTER TABLE {} ADD {}", table, clause))
+                ],
+                None => Vec::new(),
+            },
+            Change::DropForeignKey { table, attribute, foreign_key } => match foreign_key {
+                Constraint::ForeignKey { constraint_name: Some(constraint_name), .. } => vec![
+                    DDL(format!("ALTER TABLE {} DROP FOREIGN KEY {}", table, constraint_name))
+                ],
+                _ => {
+                    log::error!("Foreign key of {}.{} has no constraint name & can't be dropped", table, attribute);
+                    Vec::new()
+                },
+            },
+            Change::ChangePrimaryKey { table, old, new } => match (old, new) {
+                (Some(_), Some(new)) => vec![DDL(format!("ALTER TABLE {} DROP PRIMARY KEY, ADD PRIMARY KEY({})", table, new))],
+                (Some(_), None) => vec![DDL(format!("ALTER TABLE {} DROP PRIMARY KEY", table))],
+                (None, Some(new)) => vec![DDL(format!("ALTER TABLE {} ADD PRIMARY KEY({})", table, new))],
+                (None, None) => Vec::new(),
+            },
+        };
+
+        ddl.into_iter()
+            .map(SQL::from)
+            .collect()
+    }
+
+    /// Returns true if applying the change can lose data.
+    ///
+    /// Dropping a table or a column & changing the type of a column are destructive. Views hold no data, so redefining them is not.
+    pub fn is_destructive(&self) -> bool {
+        match self {
+            Change::DropRelation(Relation::Table(_)) |
+            Change::DropColumn { .. } => true,
+            Change::ModifyColumn { old, new, .. } => old.data_type.to_string() != new.data_type.to_string(),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::CreateRelation(relation) => write!(f, "+ {}", relation.name()),
+            Change::DropRelation(relation) => write!(f, "- {}", relation.name()),
+            Change::AddColumn { table, attribute } => write!(f, "+ {}.{} {}", table, attribute.name, attribute.data_type),
+            Change::DropColumn { table, attribute } => write!(f, "- {}.{}", table, attribute.name),
+            Change::ModifyColumn { table, old, new } => write!(f, "~ {}.{} {} -> {}", table, new.name, column_definition(old, false), column_definition(new, false)),
+            Change::AddUnique { table, attribute } => write!(f, "+ {}.{} Unique", table, attribute),
+            Change::DropUnique { table, attribute } => write!(f, "- {}.{} Unique", table, attribute),
+            Change::AddForeignKey { table, attribute, foreign_key } => write!(f, "+ {}.{} -> {}", table, attribute, foreign_key),
+            Change::DropForeignKey { table, attribute, foreign_key } => write!(f, "- {}.{} -> {}", table, attribute, foreign_key),
+            Change::ChangePrimaryKey { table, old, new } => write!(
+                f,
+                "~ {} PRIMARY KEY({}) -> PRIMARY KEY({})",
+                table,
+                old.clone().unwrap_or_default(),
+                new.clone().unwrap_or_default()
+            ),
+        }
+    }
+}
+
+/// SchemaDiff is the ordered list of changes required to turn one schema into another
+#[derive(Clone, Debug, Default)]
+pub struct SchemaDiff{
+    /// The changes, in the order they must be applied.
+    pub changes: Vec<Change>,
+}
+
+impl SchemaDiff {
+    /// Returns the `SQL` commands that turn the old schema into the new schema.
+    pub fn to_sql(&self) -> Vec<SQL> {
+        self.changes.iter()
+            .flat_map(|change| change.to_sql())
+            .collect()
+    }
+
+    /// Returns true if both schemas are the same.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Returns true if any change can lose data.
+    pub fn is_destructive(&self) -> bool {
+        self.changes.iter().any(Change::is_destructive)
+    }
+}
+
+/// Returns the changes required to turn the `old` relations into the `new` relations.
+///
+/// Tables are compared column by column & views by their definition. Routines, events & triggers are not compared.
+///
+/// The changes are ordered so that dependents are dropped before their dependencies, using the dependency tree of `old`,
+/// and dependencies are created before their dependents, using the dependency tree of `new`.
+/// Tables referencing each other are created without the foreign keys between them, which are added once every table exists.
+///
+/// # Arguments
+///
+/// * `old` - The relations of the current schema
+/// * `new` - The relations of the target schema
+pub fn diff(old: &[Relation], new: &[Relation]) -> SchemaDiff {
+    let old = comparable(old);
+    let new = comparable(new);
+
+    let old_tree = get_dependency_tree(&old);
+    let new_tree = get_dependency_tree(&new);
+
+    let old_order = get_generation_path(&old, &old_tree);
+    let new_order = get_generation_path(&new, &new_tree);
+
+    let old_deferred = get_deferred_foreign_keys(&old, &old_tree);
+    let new_deferred = get_deferred_foreign_keys(&new, &new_tree);
+
+    let old_names: HashMap<String, &Relation> = old.iter().map(|relation| (relation.name(), relation)).collect();
+    let new_names: HashMap<String, &Relation> = new.iter().map(|relation| (relation.name(), relation)).collect();
+
+    let mut drop_views: Vec<Change> = Vec::new();
+    let mut drop_foreign_keys: Vec<Change> = Vec::new();
+    let mut drop_tables: Vec<Change> = Vec::new();
+    let mut alter_tables: Vec<Change> = Vec::new();
+    let mut create_tables: Vec<Change> = Vec::new();
+    let mut add_foreign_keys: Vec<Change> = Vec::new();
+    let mut create_views: Vec<Change> = Vec::new();
+
+    //dependents are dropped first
+    old_order.iter()
+        .rev()
+        .map(|index| &old[*index])
+        .for_each(|relation| {
+            match (relation, new_names.get(&relation.name())) {
+                (Relation::View(old_view), Some(Relation::View(new_view))) => {
+                    if old_view.create() != new_view.create() {
+                        drop_views.push(Change::DropRelation(relation.clone()));
+                    }
+                },
+                (Relation::View(_), _) => drop_views.push(Change::DropRelation(relation.clone())),
+                (Relation::Table(_), Some(Relation::Table(_))) => {},
+                (_, _) => drop_tables.push(Change::DropRelation(relation.clone())),
+            }
+        });
+
+    //tables referencing each other can't be dropped before the foreign keys between them
+    old_deferred.iter()
+        .filter(|foreign_key| !matches!(new_names.get(&old[foreign_key.table].name()), Some(Relation::Table(_))))
+        .filter(|foreign_key| matches!(&foreign_key.foreign_key, Constraint::ForeignKey { table_name, .. } if *table_name != old[foreign_key.table].name()))
+        .for_each(|foreign_key| drop_foreign_keys.push(
+            Change::DropForeignKey { table: old[foreign_key.table].name(), attribute: foreign_key.attribute.clone(), foreign_key: foreign_key.foreign_key.clone() }
+        ));
+
+    //dependencies are created first
+    new_order.iter()
+        .map(|index| (*index, &new[*index]))
+        .for_each(|(index, relation)| {
+            match (old_names.get(&relation.name()), relation) {
+                (Some(Relation::Table(old_table)), Relation::Table(new_table)) => {
+                    let (drop_foreign_key, alter_table, add_foreign_key) = diff_table(old_table, new_table);
+
+                    drop_foreign_keys.extend(drop_foreign_key);
+                    alter_tables.extend(alter_table);
+                    add_foreign_keys.extend(add_foreign_key);
+                },
+                (Some(Relation::View(old_view)), Relation::View(new_view)) => {
+                    if old_view.create() != new_view.create() {
+                        create_views.push(Change::CreateRelation(relation.clone()));
+                    }
+                },
+                (_, Relation::Table(new_table)) => {
+                    //tables referencing each other are created before the foreign keys between them
+                    let deferred: Vec<&DeferredForeignKey> = new_deferred.iter()
+                        .filter(|foreign_key| foreign_key.table == index)
+                        .collect();
+
+                    let attributes: Vec<String> = deferred.iter()
+                        .map(|foreign_key| foreign_key.attribute.clone())
+                        .collect();
+
+                    create_tables.push(Change::CreateRelation(Relation::Table(new_table.without_foreign_keys(&attributes))));
+
+                    deferred.iter()
+                        .for_each(|foreign_key| add_foreign_keys.push(
+                            Change::AddForeignKey { table: new_table.name.clone(), attribute: foreign_key.attribute.clone(), foreign_key: foreign_key.foreign_key.clone() }
+                        ));
+                },
+                (_, _) => create_views.push(Change::CreateRelation(relation.clone())),
+            }
+        });
+
+    SchemaDiff {
+        changes: [
+            drop_views,
+            drop_foreign_keys,
+            drop_tables,
+            alter_tables,
+            create_tables,
+            add_foreign_keys,
+            create_views,
+        ].concat()
+    }
+}
+
+/// Returns the tables & views of `relations`
+fn comparable(relations: &[Relation]) -> Vec<Relation> {
+    relations.iter()
+        .filter(|relation| matches!(relation, Relation::Table(_) | Relation::View(_)))
+        .cloned()
+        .collect()
+}
+
+/// Returns the changes of a table that exists in both schemas, split into foreign keys to drop, alterations & foreign keys to add.
+fn diff_table(old: &Table, new: &Table) -> (Vec<Change>, Vec<Change>, Vec<Change>) {
+    let table = new.name.clone();
+
+    let mut drop_foreign_keys: Vec<Change> = Vec::new();
+    let mut drop_unique: Vec<Change> = Vec::new();
+    let mut add_columns: Vec<Change> = Vec::new();
+    let mut modify_columns: Vec<Change> = Vec::new();
+    let mut change_primary_key: Vec<Change> = Vec::new();
+    let mut drop_columns: Vec<Change> = Vec::new();
+    let mut add_unique: Vec<Change> = Vec::new();
+    let mut add_foreign_keys: Vec<Change> = Vec::new();
+
+    old.attributes.iter()
+        .for_each(|old_attribute| {
+            let new_attribute = new.attributes.iter().find(|attribute| attribute.name == old_attribute.name);
+
+            //foreign keys are dropped whenever they are removed or changed
+            if let Some(foreign_key) = foreign_key(old_attribute) {
+                if new_attribute.and_then(foreign_key_target) != foreign_key_target(old_attribute) {
+                    drop_foreign_keys.push(
+                        Change::DropForeignKey { table: table.clone(), attribute: old_attribute.name.clone(), foreign_key: foreign_key.clone() }
+                    );
+                }
+            }
+
+            match new_attribute {
+                Some(new_attribute) => {
+                    if column_definition(old_attribute, false) != column_definition(new_attribute, false) {
+                        modify_columns.push(
+                            Change::ModifyColumn { table: table.clone(), old: old_attribute.clone(), new: new_attribute.clone() }
+                        );
+                    }
+
+                    match (old_attribute.constraint.contains(&Constraint::Unique), new_attribute.constraint.contains(&Constraint::Unique)) {
+                        (true, false) => drop_unique.push(Change::DropUnique { table: table.clone(), attribute: old_attribute.name.clone() }),
+                        (false, true) => add_unique.push(Change::AddUnique { table: table.clone(), attribute: old_attribute.name.clone() }),
+                        _ => {},
+                    }
+                },
+                None => drop_columns.push(Change::DropColumn { table: table.clone(), attribute: old_attribute.clone() }),
+            }
+        });
+
+    new.attributes.iter()
+        .for_each(|new_attribute| {
+            let old_attribute = old.attributes.iter().find(|attribute| attribute.name == new_attribute.name);
+
+            if old_attribute.is_none() {
+                add_columns.push(Change::AddColumn { table: table.clone(), attribute: new_attribute.clone() });
+            }
+
+            if let Some(foreign_key) = foreign_key(new_attribute) {
+                if old_attribute.and_then(foreign_key_target) != foreign_key_target(new_attribute) {
+                    add_foreign_keys.push(
+                        Change::AddForeignKey { table: table.clone(), attribute: new_attribute.name.clone(), foreign_key: foreign_key.clone() }
+                    );
+                }
+            }
+        });
+
+    let old_primary_key = Some(old.primary_key_names().join(",")).filter(|primary_key| !primary_key.is_empty());
+    let new_primary_key = Some(new.primary_key_names().join(",")).filter(|primary_key| !primary_key.is_empty());
+
+    if old_primary_key != new_primary_key {
+        change_primary_key.push(Change::ChangePrimaryKey { table: table.clone(), old: old_primary_key, new: new_primary_key });
+    }
+
+    (
+        drop_foreign_keys,
+        [drop_unique, add_columns, modify_columns, change_primary_key, drop_columns, add_unique].concat(),
+        add_foreign_keys
+    )
+}
+
+/// Returns the foreign key constraint of an attribute
+fn foreign_key(attribute: &Attribute) -> Option<&Constraint> {
+    attribute.constraint.iter()
+        .find(|constraint| matches!(constraint, Constraint::ForeignKey { .. }))
+}
+
+/// The table, attribute & remaining column pairs referenced by a foreign key
+type ForeignKeyTarget = (String, String, Vec<(String, String)>);
+
+/// Returns the table & attributes referenced by the foreign key of an attribute
+fn foreign_key_target(attribute: &Attribute) -> Option<ForeignKeyTarget> {
+    match foreign_key(attribute) {
+        Some(Constraint::ForeignKey { table_name, attribute_name, other_columns, .. }) => Some((table_name.clone(), attribute_name.clone(), other_columns.clone())),
+        _ => None,
+    }
+}
+
+/// Returns the column definition of an attribute used by `ADD COLUMN` & `MODIFY COLUMN`.
+fn column_definition(attribute: &Attribute, unique: bool) -> String {
+    let mut definition = format!("{} {}", attribute.name, attribute.data_type);
+
+    if attribute.constraint.contains(&Constraint::NotNull) {
+        definition.push_str(" Not Null");
+    }
+    if attribute.constraint.contains(&Constraint::AutoIncrement) {
+        definition.push_str(" Auto_increment");
+    }
+    if unique && attribute.constraint.contains(&Constraint::Unique) {
+        definition.push_str(" Unique");
+    }
+
+    definition
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(unused_imports)]
+    use std::collections::HashSet;
+
+    use crate::backend::{relation::{Relation, table::{Table, Attribute, AttributeType, Constraint}, view::View}, sql::{SQL, QDL}};
+
+    use super::diff;
+
+    #[allow(unused_macros)]
+    macro_rules! attribute {
+        ($name: literal, $data_type: expr) => {
+            Attribute{
+                name: String::from($name),
+                data_type: $data_type,
+                constraint: HashSet::new()
+            }
+        };
+        ($name: literal, $data_type: expr, $($constraint: expr),+) => {
+            Attribute{
+                name: String::from($name),
+                data_type: $data_type,
+                constraint: HashSet::from([$($constraint),+])
+            }
+        };
+    }
+
+    #[allow(unused_macros)]
+    macro_rules! assert_sql {
+        ($actual: expr, $expected: expr) => {
+            let actual: Vec<String> = $actual.iter().map(|sql| sql.to_string()).collect();
+            let expected: Vec<&str> = $expected;
+
+            assert_eq!(actual, expected);
+        };
+    }
+
+    #[test]
+    fn diff_test_1() {
+        //same schema
+        let relations = vec![
+            Relation::Table(
+                Table{
+                    name: String::from("table_1"),
+                    attributes: vec![attribute!("attr_1", AttributeType::Int(11), Constraint::NotNull)],
+                    primary_key: vec![0],
+                    triggers: vec![],
+                }
+            ),
+            Relation::View(View::new("view_1", QDL(String::from("SELECT * FROM table_1")))),
+        ];
+
+        assert!(diff(&relations, &relations).is_empty());
+    }
+
+    #[test]
+    fn diff_test_2() {
+        //added & removed relations
+        let old = vec![
+            Relation::Table(
+                Table{
+                    name: String::from("table_1"),
+                    attributes: vec![attribute!("attr_1", AttributeType::Int(11))],
+                    primary_key: vec![0],
+                    triggers: vec![],
+                }
+            ),
+            Relation::View(View::new("view_1", QDL(String::from("SELECT * FROM table_1")))),
+        ];
+        let new = vec![
+            Relation::Table(
+                Table{
+                    name: String::from("table_2"),
+                    attributes: vec![attribute!("attr_1", AttributeType::Int(11))],
+                    primary_key: vec![0],
+                    triggers: vec![],
+                }
+            ),
+            Relation::View(View::new("view_2", QDL(String::from("SELECT * FROM table_2")))),
+        ];
+
+        assert_sql!(
+            diff(&old, &new).to_sql(),
+            vec![
+                "DROP VIEW view_1",
+                "DROP TABLE table_1",
+                "CREATE TABLE table_2 (attr_1 int(11), PRIMARY KEY(attr_1))",
+                "CREATE ALGORITHM=UNDEFINED SQL SECURITY DEFINER VIEW view_2 AS SELECT * FROM table_2",
+            ]
+        );
+        assert!(diff(&old, &new).is_destructive());
+    }
+
+    #[test]
+    fn diff_test_3() {
+        //changed columns, constraints & views
+        let old = vec![
+            Relation::Table(
+                Table{
+                    name: String::from("table_1"),
+                    attributes: vec![
+                        attribute!("attr_1", AttributeType::Int(11)),
+                    ],
+                    primary_key: vec![0],
+                    triggers: vec![],
+                }
+            ),
+            Relation::Table(
+                Table{
+                    name: String::from("table_2"),
+                    attributes: vec![
+                        attribute!("attr_1", AttributeType::Int(11)),
+                        attribute!("attr_2", AttributeType::VarChar(50), Constraint::Unique),
+                        attribute!("attr_3", AttributeType::Int(11), Constraint::ForeignKey{
+                            table_name: String::from("table_1"),
+                            attribute_name: String::from("attr_1"),
+                            constraint_name: Some(String::from("table_2_ibfk_1")),
+                            other_columns: Vec::new()
+                        }),
+                    ],
+                    primary_key: vec![0],
+                    triggers: vec![],
+                }
+            ),
+            Relation::View(View::new("view_1", QDL(String::from("SELECT attr_1 FROM table_2")))),
+        ];
+        let new = vec![
+            Relation::Table(
+                Table{
+                    name: String::from("table_1"),
+                    attributes: vec![
+                        attribute!("attr_1", AttributeType::Int(11)),
+                    ],
+                    primary_key: vec![0],
+                    triggers: vec![],
+                }
+            ),
+            Relation::Table(
+                Table{
+                    name: String::from("table_2"),
+                    attributes: vec![
+                        attribute!("attr_1", AttributeType::Int(11)),
+                        attribute!("attr_2", AttributeType::VarChar(255), Constraint::NotNull),
+                        attribute!("attr_4", AttributeType::Int(11), Constraint::ForeignKey{
+                            table_name: String::from("table_1"),
+                            attribute_name: String::from("attr_1"),
+                            constraint_name: Some(String::from("table_2_ibfk_2")),
+                            other_columns: Vec::new()
+                        }),
+                    ],
+                    primary_key: vec![0],
+                    triggers: vec![],
+                }
+            ),
+            Relation::View(View::new("view_1", QDL(String::from("SELECT attr_1, attr_2 FROM table_2")))),
+        ];
+
+        assert_sql!(
+            diff(&old, &new).to_sql(),
+            vec![
+                "DROP VIEW view_1",
+                "ALTER TABLE table_2 DROP FOREIGN KEY table_2_ibfk_1",
+                "ALTER TABLE table_2 DROP INDEX attr_2",
+                "ALTER TABLE table_2 ADD COLUMN attr_4 int(11)",
+                "ALTER TABLE table_2 MODIFY COLUMN attr_2 varchar(255) Not Null",
+                "ALTER TABLE table_2 DROP COLUMN attr_3",
+                "ALTER TABLE table_2 ADD CONSTRAINT table_2_ibfk_2 FOREIGN KEY(attr_4) REFERENCES table_1(attr_1)",
+                "CREATE ALGORITHM=UNDEFINED SQL SECURITY DEFINER VIEW view_1 AS SELECT attr_1, attr_2 FROM table_2",
+            ]
+        );
+        assert!(diff(&old, &new).is_destructive());
+    }
+
+    #[test]
+    fn diff_test_4() {
+        //a parsed snapshot matches the schema it was generated from
+        let relations = vec![
+            Relation::Table(
+                Table{
+                    name: String::from("table_1"),
+                    attributes: vec![
+                        attribute!("attr_1", AttributeType::Int(11), Constraint::NotNull, Constraint::AutoIncrement),
+                        attribute!("attr_2", AttributeType::VarChar(255), Constraint::Unique),
+                    ],
+                    primary_key: vec![0],
+                    triggers: vec![],
+                }
+            ),
+            Relation::Table(
+                Table{
+                    name: String::from("table_2"),
+                    attributes: vec![
+                        attribute!("attr_1", AttributeType::Int(11), Constraint::ForeignKey{
+                            table_name: String::from("table_1"),
+                            attribute_name: String::from("attr_1"),
+                            constraint_name: Some(String::from("table_2_ibfk_1")),
+                            other_columns: Vec::new()
+                        }),
+                    ],
+                    primary_key: Vec::new(),
+                    triggers: vec![],
+                }
+            ),
+        ];
+
+        let parsed: Vec<Relation> = relations.iter()
+            .map(|relation| Relation::from_sql(&SQL::from(crate::backend::relation::RelationMethods::create(relation))).unwrap())
+            .collect();
+
+        assert!(diff(&relations, &parsed).is_empty());
+    }
+
+    #[test]
+    fn diff_test_5() {
+        //non destructive changes
+        let old = vec![
+            Relation::Table(
+                Table{
+                    name: String::from("table_1"),
+                    attributes: vec![
+                        attribute!("attr_1", AttributeType::Int(11)),
+                        attribute!("attr_2", AttributeType::VarChar(50), Constraint::Unique),
+                    ],
+                    primary_key: vec![0],
+                    triggers: vec![],
+                }
+            ),
+            Relation::View(View::new("view_1", QDL(String::from("SELECT attr_1 FROM table_1")))),
+        ];
+        let new = vec![
+            Relation::Table(
+                Table{
+                    name: String::from("table_1"),
+                    attributes: vec![
+                        attribute!("attr_1", AttributeType::Int(11)),
+                        attribute!("attr_2", AttributeType::VarChar(50), Constraint::NotNull),
+                        attribute!("attr_3", AttributeType::Text),
+                    ],
+                    primary_key: vec![0],
+                    triggers: vec![],
+                }
+            ),
+            Relation::View(View::new("view_1", QDL(String::from("SELECT attr_1, attr_3 FROM table_1")))),
+        ];
+
+        let actual = diff(&old, &new);
+
+        assert_eq!(actual.changes.len(), 5);
+        assert!(!actual.is_destructive());
+    }
+
+    #[test]
+    fn diff_test_6() {
+        //tables referencing each other
+        let cycle = vec![
+            Relation::Table(
+                Table{
+                    name: String::from("table_1"),
+                    attributes: vec![
+                        attribute!("attr_1", AttributeType::Int(11)),
+                        attribute!("attr_2", AttributeType::Int(11), Constraint::ForeignKey { table_name: String::from("table_2"), attribute_name: String::from("attr_1"), constraint_name: Some(String::from("fk_1")), other_columns: Vec::new() }),
+                    ],
+                    primary_key: vec![0],
+                    triggers: vec![],
+                }
+            ),
+            Relation::Table(
+                Table{
+                    name: String::from("table_2"),
+                    attributes: vec![
+                        attribute!("attr_1", AttributeType::Int(11)),
+                        attribute!("attr_2", AttributeType::Int(11), Constraint::ForeignKey { table_name: String::from("table_1"), attribute_name: String::from("attr_1"), constraint_name: Some(String::from("fk_2")), other_columns: Vec::new() }),
+                    ],
+                    primary_key: vec![0],
+                    triggers: vec![],
+                }
+            ),
+        ];
+
+        assert_sql!(
+            diff(&[], &cycle).to_sql(),
+            vec![
+                "CREATE TABLE table_2 (attr_1 int(11),attr_2 int(11), PRIMARY KEY(attr_1))",
+                "CREATE TABLE table_1 (attr_1 int(11),attr_2 int(11), PRIMARY KEY(attr_1))",
+                "ALTER TABLE table_2 ADD CONSTRAINT fk_2 FOREIGN KEY(attr_2) REFERENCES table_1(attr_1)",
+                "ALTER TABLE table_1 ADD CONSTRAINT fk_1 FOREIGN KEY(attr_2) REFERENCES table_2(attr_1)",
+            ]
+        );
+
+        assert_sql!(
+            diff(&cycle, &[]).to_sql(),
+            vec![
+                "ALTER TABLE table_1 DROP FOREIGN KEY fk_1",
+                "ALTER TABLE table_2 DROP FOREIGN KEY fk_2",
+                "DROP TABLE table_1",
+                "DROP TABLE table_2",
+            ]
+        );
+    }
+}
@@ -0,0 +1,356 @@
+use std::{fmt, fs};
+
+use petgraph::visit::EdgeRef;
+
+use super::{Relation, table::{Table, Attribute, Constraint}, paths::get_dependency_tree};
+
+/// ErdFormat defines the text formats an entity relationship diagram can be exported to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErdFormat{
+    /// Graphviz DOT
+    Dot,
+    /// Mermaid `erDiagram`
+    Mermaid,
+}
+
+impl ErdFormat {
+    /// Returns the `ErdFormat` corresponding to its name or file extension.
+    pub fn from(raw_str: &str) -> Option<ErdFormat> {
+        match raw_str.to_ascii_lowercase().as_str() {
+            "dot" | "gv" => Some(ErdFormat::Dot),
+            "mermaid" | "mmd" => Some(ErdFormat::Mermaid),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ErdFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErdFormat::Dot => write!(f, "dot"),
+            ErdFormat::Mermaid => write!(f, "mermaid"),
+        }
+    }
+}
+
+/// A dependency between two relations of the diagram
+struct Edge<'a>{
+    /// The relation that depends on `to` (e.g. the table holding the foreign key).
+    from: &'a Relation,
+    /// The relation depended on (e.g. the referenced table).
+    to: &'a Relation,
+    /// The foreign keys of `from` referencing `to`, as `(attribute, referenced attribute)`. Empty for view dependencies.
+    foreign_keys: Vec<(&'a Attribute, String)>,
+}
+
+/// Returns the tables & views of `relations` & the dependencies between them, taken from the dependency tree.
+///
+/// Routines & events are not part of the diagram.
+fn diagram(relations: &[Relation]) -> (Vec<Relation>, Vec<(usize, usize)>) {
+    let relations: Vec<Relation> = relations.iter()
+        .filter(|relation| matches!(relation, Relation::Table(_) | Relation::View(_)))
+        .cloned()
+        .collect();
+
+    let dependency_tree = get_dependency_tree(&relations);
+
+    let mut dependencies: Vec<(usize, usize)> = Vec::new();
+
+    dependency_tree.edge_references()
+        .map(|edge| (dependency_tree[edge.target()] as usize, dependency_tree[edge.source()] as usize))
+        .for_each(|dependency| {
+            if !dependencies.contains(&dependency) {
+                dependencies.push(dependency);
+            }
+        });
+
+    (relations, dependencies)
+}
+
+/// Returns the edges of the diagram
+fn edges<'a>(relations: &'a [Relation], dependencies: &[(usize, usize)]) -> Vec<Edge<'a>> {
+    dependencies.iter()
+        .map(|(from, to)| {
+            let (from, to) = (&relations[*from], &relations[*to]);
+
+            let foreign_keys = match from {
+                Relation::Table(table) => table.attributes.iter()
+                    .filter_map(|attribute| match foreign_key(attribute) {
+                        Some((table_name, attribute_name)) if *table_name == to.name() => Some((attribute, attribute_name.clone())),
+                        _ => None,
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            };
+
+            Edge { from, to, foreign_keys }
+        })
+        .collect()
+}
+
+/// Returns the referenced table & attribute of an attribute's foreign key
+fn foreign_key(attribute: &Attribute) -> Option<(&String, &String)> {
+    attribute.constraint.iter()
+        .find_map(|constraint| match constraint {
+            Constraint::ForeignKey { table_name, attribute_name, .. } => Some((table_name, attribute_name)),
+            _ => None,
+        })
+}
+
+/// Returns the PK, FK & UK markers of an attribute
+fn keys(table: &Table, index: usize) -> Vec<&'static str> {
+    let attribute = &table.attributes[index];
+    let mut keys = Vec::new();
+
+    if table.primary_key.contains(&index) {
+        keys.push("PK");
+    }
+    if foreign_key(attribute).is_some() {
+        keys.push("FK");
+    }
+    if attribute.constraint.contains(&Constraint::Unique) {
+        keys.push("UK");
+    }
+
+    keys
+}
+
+/// Returns a Graphviz DOT digraph of the tables & views of `relations`.
+///
+/// Tables are drawn as records listing their columns. Foreign keys are drawn as solid edges from the referencing table, labelled with the referencing columns,
+/// & view dependencies as dashed edges from the view.
+pub fn to_dot(relations: &[Relation]) -> String {
+    let (relations, dependencies) = diagram(relations);
+
+    let escape = |text: &str| -> String {
+        text.chars()
+            .flat_map(|c| match c {
+                '{' | '}' | '|' | '<' | '>' | '"' | '\\' => vec!['\\', c],
+                _ => vec![c],
+            })
+            .collect()
+    };
+
+    let mut dot = String::from("digraph erd {\n    rankdir=LR;\n    node [shape=record];\n");
+
+    relations.iter()
+        .for_each(|relation| match relation {
+            Relation::Table(table) => {
+                let columns: String = table.attributes.iter()
+                    .enumerate()
+                    .map(|(index, attribute)| {
+                        let keys = keys(table, index);
+
+                        match keys.len() {
+                            0 => format!("{} : {}\\l", escape(&attribute.name), escape(&attribute.data_type.to_string())),
+                            _ => format!("{} {} : {}\\l", keys.join(","), escape(&attribute.name), escape(&attribute.data_type.to_string())),
+                        }
+                    })
+                    .collect();
+
+                dot.push_str(&format!("    \"{}\" [label=\"{{{}|{}}}\"];\n", table.name, escape(&table.name), columns));
+            },
+            Relation::View(view) => {
+                dot.push_str(&format!("    \"{}\" [label=\"{{{}|view\\l}}\", style=dashed];\n", view.name, escape(&view.name)));
+            },
+            _ => {},
+        });
+
+    edges(&relations, &dependencies).iter()
+        .for_each(|edge| {
+            match edge.foreign_keys.len() {
+                0 => dot.push_str(&format!("    \"{}\" -> \"{}\" [style=dashed];\n", edge.from.name(), edge.to.name())),
+                _ => {
+                    let label: Vec<String> = edge.foreign_keys.iter()
+                        .map(|(attribute, referenced)| format!("{} -> {}", attribute.name, referenced))
+                        .collect();
+
+                    dot.push_str(&format!("    \"{}\" -> \"{}\" [label=\"{}\"];\n", edge.from.name(), edge.to.name(), label.join(", ")));
+                },
+            }
+        });
+
+    dot.push_str("}\n");
+
+    dot
+}
+
+/// Returns a Mermaid `erDiagram` of the tables & views of `relations`.
+///
+/// Mermaid attribute types can't contain parameters, so only the name of each type is written (e.g. `varchar` for `varchar(255)`).
+/// Foreign keys are drawn as identifying relationships labelled with the referencing columns, & view dependencies as non-identifying relationships.
+pub fn to_mermaid(relations: &[Relation]) -> String {
+    let (relations, dependencies) = diagram(relations);
+
+    let mut mermaid = String::from("erDiagram\n");
+
+    relations.iter()
+        .for_each(|relation| match relation {
+            Relation::Table(table) if !table.attributes.is_empty() => {
+                mermaid.push_str(&format!("    {} {{\n", table.name));
+
+                table.attributes.iter()
+                    .enumerate()
+                    .for_each(|(index, attribute)| {
+                        let data_type = attribute.data_type.to_string();
+                        let data_type = data_type.split('(').next().unwrap();
+
+                        match keys(table, index).join(", ") {
+                            keys if keys.is_empty() => mermaid.push_str(&format!("        {} {}\n", data_type, attribute.name)),
+                            keys => mermaid.push_str(&format!("        {} {} {}\n", data_type, attribute.name, keys)),
+                        }
+                    });
+
+                mermaid.push_str("    }\n");
+            },
+            _ => mermaid.push_str(&format!("    {}\n", relation.name())),
+        });
+
+    edges(&relations, &dependencies).iter()
+        .for_each(|edge| {
+            match edge.foreign_keys.len() {
+                0 => mermaid.push_str(&format!("    {} }}o..o{{ {} : \"view\"\n", edge.from.name(), edge.to.name())),
+                _ => {
+                    //a unique foreign key references at most one row per referenced row
+                    let cardinality = match edge.foreign_keys.iter().all(|(attribute, _)| attribute.constraint.contains(&Constraint::Unique)) {
+                        true => "|o",
+                        false => "}o",
+                    };
+
+                    let label: Vec<String> = edge.foreign_keys.iter()
+                        .map(|(attribute, _)| attribute.name.clone())
+                        .collect();
+
+                    mermaid.push_str(&format!("    {} {}--|| {} : \"{}\"\n", edge.from.name(), cardinality, edge.to.name(), label.join(", ")));
+                },
+            }
+        });
+
+    mermaid
+}
+
+/// Writes an entity relationship diagram of the tables & views of `relations` to a file.
+///
+/// # Arguments
+///
+/// * `relations` - The relations to draw
+/// * `file_path` - The path of the file to write
+/// * `format` - The format of the diagram
+pub fn export(relations: &[Relation], file_path: &str, format: ErdFormat) -> Result<(), std::io::Error> {
+    let content = match format {
+        ErdFormat::Dot => to_dot(relations),
+        ErdFormat::Mermaid => to_mermaid(relations),
+    };
+
+    fs::write(file_path, content)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(unused_imports)]
+    use std::collections::HashSet;
+
+    use indoc::indoc;
+
+    use crate::backend::{relation::{Relation, table::{Table, Attribute, AttributeType, Constraint}, view::View}, sql::QDL};
+
+    use super::{to_dot, to_mermaid, ErdFormat};
+
+    #[allow(dead_code)]
+    fn relations() -> Vec<Relation> {
+        vec![
+            Relation::Table(
+                Table{
+                    name: String::from("patients"),
+                    attributes: vec![
+                        Attribute{
+                            name: String::from("id"),
+                            data_type: AttributeType::Int(11),
+                            constraint: HashSet::from([Constraint::NotNull])
+                        },
+                        Attribute{
+                            name: String::from("name"),
+                            data_type: AttributeType::VarChar(255),
+                            constraint: HashSet::new()
+                        },
+                    ],
+                    primary_key: vec![0],
+                    triggers: vec![],
+                }
+            ),
+            Relation::Table(
+                Table{
+                    name: String::from("visits"),
+                    attributes: vec![
+                        Attribute{
+                            name: String::from("id"),
+                            data_type: AttributeType::Int(11),
+                            constraint: HashSet::new()
+                        },
+                        Attribute{
+                            name: String::from("patient_id"),
+                            data_type: AttributeType::Int(11),
+                            constraint: HashSet::from([
+                                Constraint::ForeignKey{
+                                    table_name: String::from("patients"),
+                                    attribute_name: String::from("id"),
+                                    constraint_name: None,
+                                    other_columns: Vec::new()
+                                }
+                            ])
+                        },
+                    ],
+                    primary_key: vec![0],
+                    triggers: vec![],
+                }
+            ),
+            Relation::View(View::new("patient_names", QDL(String::from("SELECT name FROM patients")))),
+        ]
+    }
+
+    #[test]
+    fn to_dot_test_1() {
+        assert_eq!(
+            to_dot(&relations()),
+            indoc!{r#"
+                digraph erd {
+                    rankdir=LR;
+                    node [shape=record];
+                    "patients" [label="{patients|PK id : int(11)\lname : varchar(255)\l}"];
+                    "visits" [label="{visits|PK id : int(11)\lFK patient_id : int(11)\l}"];
+                    "patient_names" [label="{patient_names|view\l}", style=dashed];
+                    "visits" -> "patients" [label="patient_id -> id"];
+                    "patient_names" -> "patients" [style=dashed];
+                }
+            "#}
+        );
+    }
+
+    #[test]
+    fn to_mermaid_test_1() {
+        assert_eq!(
+            to_mermaid(&relations()),
+            indoc!{r#"
+                erDiagram
+                    patients {
+                        int id PK
+                        varchar name
+                    }
+                    visits {
+                        int id PK
+                        int patient_id FK
+                    }
+                    patient_names
+                    visits }o--|| patients : "patient_id"
+                    patient_names }o..o{ patients : "view"
+            "#}
+        );
+    }
+
+    #[test]
+    fn format_test_1() {
+        assert_eq!(ErdFormat::from("DOT"), Some(ErdFormat::Dot));
+        assert_eq!(ErdFormat::from("mmd"), Some(ErdFormat::Mermaid));
+        assert_eq!(ErdFormat::from("png"), None);
+    }
+}
@@ -0,0 +1,197 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backend::sql::{QDL, DDL};
+
+use super::{RelationMethods, definer_sql};
+
+/// A struct representing a scheduled event of the MySQL event scheduler
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Event{
+    /// The name of the event.
+    pub name: String,
+    /// When the event is executed.
+    pub schedule: Schedule,
+    /// Whether the event is enabled.
+    pub status: EventStatus,
+    /// Whether the event is kept after its schedule has expired.
+    pub preserve: bool,
+    /// The statement executed by the event (e.g. `BEGIN ... END`).
+    pub body: String,
+    /// The account that defined the event (e.g. `root@localhost`).
+    pub definer: String,
+    /// The comment of the event.
+    pub comment: String,
+}
+
+impl Event {
+    /// Returns a `DDL` enabling the event.
+    pub fn enable(&self) -> DDL {
+        DDL(format!("ALTER EVENT {} {}", self.name, EventStatus::Enabled))
+    }
+    /// Returns a `DDL` disabling the event.
+    pub fn disable(&self) -> DDL {
+        DDL(format!("ALTER EVENT {} {}", self.name, EventStatus::Disabled))
+    }
+}
+
+impl RelationMethods for Event {
+    /// Returns a `QDL` selecting the event's definition from `information_schema.EVENTS`.
+    fn select(&self) -> QDL {
+        QDL(format!("SELECT * FROM information_schema.EVENTS WHERE EVENT_SCHEMA = DATABASE() AND EVENT_NAME = '{}'", self.name))
+    }
+    /// Returns a `DDL` representing a `DROP` statement for the event.
+    fn drop(&self) -> DDL {
+        DDL(format!("DROP EVENT {}", self.name))
+    }
+    /// Returns a `DDL` representing a `CREATE` statement for the event.
+    fn create(&self) -> DDL {
+        let preserve = match self.preserve {
+            true => "PRESERVE",
+            false => "NOT PRESERVE",
+        };
+
+        let comment = match self.comment.len() {
+            0 => String::new(),
+            _ => format!(" COMMENT '{}'", self.comment.replace('\\', "\\\\").replace('\'', "''")),
+        };
+
+        DDL(
+            format!(
+                "CREATE DEFINER={} EVENT {} ON SCHEDULE {} ON COMPLETION {} {}{} DO {}",
+                definer_sql(&self.definer),
+                self.name,
+                self.schedule,
+                preserve,
+                self.status,
+                comment,
+                self.body
+            )
+        )
+    }
+}
+
+/// Schedule defines when an event is executed
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Schedule{
+    /// The event is executed once at the given time stamp.
+    At(String),
+    /// The event is executed repeatedly.
+    Every{
+        /// The quantity of the interval (e.g. `1` or `1:30`).
+        interval_value: String,
+        /// The unit of the interval (e.g. `DAY` or `HOUR_MINUTE`).
+        interval_field: String,
+        /// The time stamp the event starts repeating at.
+        starts: Option<String>,
+        /// The time stamp the event stops repeating at.
+        ends: Option<String>,
+    },
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Schedule::At(time_stamp) => write!(f, "AT '{}'", time_stamp),
+            Schedule::Every { interval_value, interval_field, starts, ends } => {
+                write!(f, "EVERY '{}' {}", interval_value, interval_field)?;
+
+                if let Some(starts) = starts {
+                    write!(f, " STARTS '{}'", starts)?;
+                }
+                if let Some(ends) = ends {
+                    write!(f, " ENDS '{}'", ends)?;
+                }
+
+                Ok(())
+            },
+        }
+    }
+}
+
+/// EventStatus defines whether the event scheduler executes an event
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum EventStatus{
+    Enabled,
+    Disabled,
+    /// The event was created on a source server & is not executed on this replica.
+    DisabledOnReplica,
+}
+
+impl EventStatus {
+    /// Returns the `EventStatus` corresponding to `information_schema.EVENTS.STATUS`.
+    pub fn from(raw_str: &str) -> Option<EventStatus> {
+        match raw_str.to_ascii_uppercase().as_str() {
+            "ENABLED" => Some(EventStatus::Enabled),
+            "DISABLED" => Some(EventStatus::Disabled),
+            "SLAVESIDE_DISABLED" | "REPLICA_SIDE_DISABLED" => Some(EventStatus::DisabledOnReplica),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for EventStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EventStatus::Enabled => write!(f, "ENABLE"),
+            EventStatus::Disabled => write!(f, "DISABLE"),
+            EventStatus::DisabledOnReplica => write!(f, "DISABLE ON SLAVE"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(unused_imports)]
+    use crate::backend::{relation::RelationMethods, sql::SQL};
+
+    use super::{Event, Schedule, EventStatus};
+
+    #[test]
+    fn create_test_1() {
+        let event = Event{
+            name: String::from("purge_sessions"),
+            schedule: Schedule::Every{
+                interval_value: String::from("1"),
+                interval_field: String::from("DAY"),
+                starts: Some(String::from("2023-01-01 00:00:00")),
+                ends: None,
+            },
+            status: EventStatus::Enabled,
+            preserve: true,
+            body: String::from("DELETE FROM sessions WHERE expires < NOW()"),
+            definer: String::from("root@localhost"),
+            comment: String::from("nightly clean up"),
+        };
+
+        assert_eq!(
+            *event.create(),
+            "CREATE DEFINER=`root`@`localhost` EVENT purge_sessions ON SCHEDULE EVERY '1' DAY STARTS '2023-01-01 00:00:00' ON COMPLETION PRESERVE ENABLE COMMENT 'nightly clean up' DO DELETE FROM sessions WHERE expires < NOW()"
+        );
+        assert_eq!(*event.drop(), "DROP EVENT purge_sessions");
+        assert_eq!(*event.disable(), "ALTER EVENT purge_sessions DISABLE");
+
+        assert!(!SQL::from(event.create()).is_preparable());
+        assert!(!SQL::from(event.disable()).is_preparable());
+    }
+
+    #[test]
+    fn create_test_2() {
+        let event = Event{
+            name: String::from("archive_2023"),
+            schedule: Schedule::At(String::from("2024-01-01 00:00:00")),
+            status: EventStatus::Disabled,
+            preserve: false,
+            body: String::from("BEGIN INSERT INTO archive SELECT * FROM orders; DELETE FROM orders; END"),
+            definer: String::from("admin@%"),
+            comment: String::new(),
+        };
+
+        assert_eq!(
+            *event.create(),
+            "CREATE DEFINER=`admin`@`%` EVENT archive_2023 ON SCHEDULE AT '2024-01-01 00:00:00' ON COMPLETION NOT PRESERVE DISABLE DO BEGIN INSERT INTO archive SELECT * FROM orders; DELETE FROM orders; END"
+        );
+        assert_eq!(*event.enable(), "ALTER EVENT archive_2023 ENABLE");
+    }
+}
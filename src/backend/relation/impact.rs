@@ -0,0 +1,241 @@
+use std::collections::VecDeque;
+
+use petgraph::{Direction, Outgoing, Incoming, visit::NodeIndexable};
+
+use crate::backend::sql::SQL;
+
+use super::{Relation, table::Constraint, diff::Change, paths::{get_dependency_tree, get_generation_path, DependencyTree}};
+
+/// Impact struct defines the relations affected by a relation, found by walking the dependency tree in both directions
+#[derive(Debug)]
+pub struct Impact{
+    /// The analysed relation.
+    pub relation: Relation,
+    /// The relations depending on the relation, directly or through other relations, along with their distance from it.
+    pub dependents: Vec<(Relation, usize)>,
+    /// The relations the relation depends on, directly or through other relations, along with their distance from it.
+    pub dependencies: Vec<(Relation, usize)>,
+    /// The changes removing the relation without leaving broken relations, in order of execution.
+    pub script: Vec<Change>,
+}
+
+impl Impact {
+    /// Returns the impact of the relation with the given name, or `None` if no relation has the name.
+    ///
+    /// The removal script emulates `DROP ... CASCADE`: the views, routines & events depending on the relation, directly or through other views, are dropped before it,
+    /// while tables referencing it only lose their foreign keys to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `relations` - Every relation of the database
+    /// * `name` - The name of the analysed relation
+    pub fn from(relations: &[Relation], name: &str) -> Option<Impact> {
+        let relations = relations.to_vec();
+        let index = relations.iter().position(|relation| relation.name() == name)?;
+
+        let dependency_tree = get_dependency_tree(&relations);
+
+        let to_relations = |indexes: Vec<(usize, usize)>| -> Vec<(Relation, usize)> {
+            indexes.into_iter()
+                .map(|(index, distance)| (relations[index].clone(), distance))
+                .collect()
+        };
+
+        Some(
+            Impact {
+                relation: relations[index].clone(),
+                dependents: to_relations(walk(&relations, &dependency_tree, index, Outgoing)),
+                dependencies: to_relations(walk(&relations, &dependency_tree, index, Incoming)),
+                script: script(&relations, &dependency_tree, index),
+            }
+        )
+    }
+
+    /// Returns the `SQL` commands of the removal script.
+    pub fn to_sql(&self) -> Vec<SQL> {
+        self.script.iter()
+            .flat_map(Change::to_sql)
+            .collect()
+    }
+}
+
+/// Returns the indexes of the relations reachable from a relation in a direction of the dependency tree, along with their distance from it.
+///
+/// The relations are sorted by distance, then by name.
+fn walk(relations: &[Relation], dependency_tree: &DependencyTree, index: usize, direction: Direction) -> Vec<(usize, usize)> {
+    let mut distances: Vec<Option<usize>> = vec![None; relations.len()];
+    distances[index] = Some(0);
+
+    let mut queue: VecDeque<usize> = VecDeque::from([index]);
+
+    while let Some(node) = queue.pop_front() {
+        let distance = distances[node].unwrap();
+
+        dependency_tree.neighbors_directed(dependency_tree.from_index(node), direction)
+            .map(|neighbor| dependency_tree[neighbor] as usize)
+            .for_each(|neighbor| {
+                if distances[neighbor].is_none() {
+                    distances[neighbor] = Some(distance + 1);
+                    queue.push_back(neighbor);
+                }
+            });
+    }
+
+    let mut reachable: Vec<(usize, usize)> = distances.iter()
+        .enumerate()
+        .filter(|(node, _)| *node != index)
+        .filter_map(|(node, distance)| distance.map(|distance| (node, distance)))
+        .collect();
+
+    reachable.sort_by_key(|(node, distance)| (*distance, relations[*node].name()));
+
+    reachable
+}
+
+/// Returns the changes removing a relation without leaving broken relations
+fn script(relations: &[Relation], dependency_tree: &DependencyTree, index: usize) -> Vec<Change> {
+    let name = relations[index].name();
+
+    let mut visited: Vec<bool> = vec![false; relations.len()];
+    let mut dropped: Vec<bool> = vec![false; relations.len()];
+    visited[index] = true;
+    dropped[index] = true;
+
+    let mut queue: VecDeque<usize> = VecDeque::from([index]);
+
+    //tables only depend on tables, so a table is never reached through a dropped view
+    while let Some(node) = queue.pop_front() {
+        dependency_tree.neighbors_directed(dependency_tree.from_index(node), Outgoing)
+            .map(|neighbor| dependency_tree[neighbor] as usize)
+            .for_each(|neighbor| {
+                if !visited[neighbor] {
+                    visited[neighbor] = true;
+
+                    if !matches!(relations[neighbor], Relation::Table(_)) {
+                        dropped[neighbor] = true;
+                        queue.push_back(neighbor);
+                    }
+                }
+            });
+    }
+
+    let order = get_generation_path(&relations.to_vec(), dependency_tree);
+
+    let drop_foreign_keys = order.iter()
+        .filter(|node| visited[**node] && !dropped[**node])
+        .flat_map(|node| match &relations[*node] {
+            Relation::Table(table) => table.attributes.iter()
+                .flat_map(|attribute| {
+                    attribute.constraint.iter()
+                        .filter(|constraint| matches!(constraint, Constraint::ForeignKey { table_name, .. } if *table_name == name))
+                        .map(|foreign_key| Change::DropForeignKey { table: table.name.clone(), attribute: attribute.name.clone(), foreign_key: foreign_key.clone() })
+                })
+                .collect(),
+            _ => Vec::new(),
+        });
+
+    //dependents are dropped first
+    let drop_relations = order.iter()
+        .rev()
+        .filter(|node| dropped[**node])
+        .map(|node| Change::DropRelation(relations[*node].clone()));
+
+    drop_foreign_keys.chain(drop_relations).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(unused_imports)]
+    use std::collections::HashSet;
+
+    use crate::backend::{relation::{Relation, table::{Table, Attribute, AttributeType, Constraint}, view::View}, sql::QDL};
+
+    use super::Impact;
+
+    #[allow(dead_code)]
+    fn table(name: &str, foreign_keys: &[&str]) -> Relation {
+        Relation::Table(
+            Table{
+                name: name.to_string(),
+                attributes: [
+                    vec![
+                        Attribute{
+                            name: String::from("id"),
+                            data_type: AttributeType::Int(11),
+                            constraint: HashSet::new()
+                        }
+                    ],
+                    foreign_keys.iter()
+                        .map(|table_name| Attribute{
+                            name: format!("{}_id", table_name),
+                            data_type: AttributeType::Int(11),
+                            constraint: HashSet::from([
+                                Constraint::ForeignKey{
+                                    table_name: table_name.to_string(),
+                                    attribute_name: String::from("id"),
+                                    constraint_name: Some(format!("{}_{}_fk", name, table_name)),
+                                    other_columns: Vec::new()
+                                }
+                            ])
+                        })
+                        .collect()
+                ].concat(),
+                primary_key: vec![0],
+                triggers: vec![],
+            }
+        )
+    }
+
+    #[test]
+    fn impact_test_1() {
+        let relations = vec![
+            table("patients", &[]),
+            table("doctors", &[]),
+            table("visits", &["patients", "doctors"]),
+            Relation::View(View::new("patient_visits", QDL(String::from("SELECT * FROM visits")))),
+            Relation::View(View::new("patient_names", QDL(String::from("SELECT name FROM patients")))),
+            Relation::View(View::new("recent_names", QDL(String::from("SELECT * FROM patient_names LIMIT 10")))),
+        ];
+
+        let impact = Impact::from(&relations, "patients").unwrap();
+
+        let names = |relations: &Vec<(Relation, usize)>| -> Vec<(String, usize)> {
+            relations.iter().map(|(relation, distance)| (relation.name(), *distance)).collect()
+        };
+
+        assert_eq!(
+            names(&impact.dependents),
+            vec![
+                (String::from("patient_names"), 1),
+                (String::from("visits"), 1),
+                (String::from("patient_visits"), 2),
+                (String::from("recent_names"), 2),
+            ]
+        );
+        assert!(impact.dependencies.is_empty());
+
+        assert_eq!(
+            impact.to_sql().iter().map(|sql| sql.to_string()).collect::<Vec<String>>(),
+            vec![
+                "ALTER TABLE visits DROP FOREIGN KEY visits_patients_fk",
+                "DROP VIEW recent_names",
+                "DROP VIEW patient_names",
+                "DROP TABLE patients",
+            ]
+        );
+
+        let impact = Impact::from(&relations, "patient_visits").unwrap();
+
+        assert!(impact.dependents.is_empty());
+        assert_eq!(
+            names(&impact.dependencies),
+            vec![
+                (String::from("visits"), 1),
+                (String::from("doctors"), 2),
+                (String::from("patients"), 2),
+            ]
+        );
+
+        assert!(Impact::from(&relations, "prescriptions").is_none());
+    }
+}
@@ -0,0 +1,1103 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use lazy_static::lazy_static;
+use mysql::{Error, Row};
+use regex::Regex;
+
+use crate::backend::{data_base::Executor, sql::{SQL, QDL, DDL}};
+
+use super::{Relation, table::{Table, Attribute, AttributeType, Constraint}, view::{View, ViewAlgorithm, CheckOption}, event::{Event, Schedule, EventStatus}, trigger::{Trigger, TriggerTiming, TriggerEvent}, routine::{Routine, RoutineType, Parameter, ParameterMode, Characteristics, DataAccess, SqlSecurity}};
+
+/// SchemaRows holds the `INFORMATION_SCHEMA` rows required to build every `Relation` of a database
+#[derive(Clone, Debug, Default)]
+pub struct SchemaRows{
+    /// Rows of `information_schema.COLUMNS`
+    pub columns: Vec<ColumnRow>,
+    /// Rows of `information_schema.KEY_COLUMN_USAGE` that reference another table
+    pub foreign_keys: Vec<ForeignKeyRow>,
+    /// Rows of `information_schema.STATISTICS`
+    pub indexes: Vec<IndexRow>,
+    /// Rows of `information_schema.VIEWS`
+    pub views: Vec<ViewRow>,
+    /// Rows of `information_schema.ROUTINES`
+    pub routines: Vec<RoutineRow>,
+    /// Rows of `information_schema.PARAMETERS`, excluding function return values
+    pub parameters: Vec<ParameterRow>,
+    /// Rows of `information_schema.TRIGGERS`
+    pub triggers: Vec<TriggerRow>,
+    /// Rows of `information_schema.EVENTS`
+    pub events: Vec<EventRow>,
+}
+
+/// A row of `information_schema.COLUMNS`
+#[derive(Clone, Debug)]
+pub struct ColumnRow{
+    /// The name of the table or view the column belongs to.
+    pub table_name: String,
+    /// The name of the column.
+    pub column_name: String,
+    /// The position of the column in the table, starting at 1.
+    pub ordinal_position: u64,
+    /// The full type of the column (e.g. `int(11)`).
+    pub column_type: String,
+    /// Whether the column accepts `NULL` values.
+    pub is_nullable: bool,
+    /// Extra information about the column (e.g. `auto_increment`).
+    pub extra: String,
+}
+
+/// A row of `information_schema.KEY_COLUMN_USAGE` describing a foreign key
+#[derive(Clone, Debug)]
+pub struct ForeignKeyRow{
+    /// The name of the table that owns the foreign key.
+    pub table_name: String,
+    /// The name of the foreign key column.
+    pub column_name: String,
+    /// The name of the referenced table.
+    pub referenced_table_name: String,
+    /// The name of the referenced column.
+    pub referenced_column_name: String,
+    /// The name of the foreign key constraint.
+    pub constraint_name: String,
+    /// The position of the column within the foreign key, starting at 1.
+    pub ordinal_position: u64,
+}
+
+/// A row of `information_schema.STATISTICS`
+#[derive(Clone, Debug)]
+pub struct IndexRow{
+    /// The name of the table the index belongs to.
+    pub table_name: String,
+    /// The name of the index. The primary key is always named `PRIMARY`.
+    pub index_name: String,
+    /// The name of the indexed column.
+    pub column_name: String,
+    /// Whether the index allows duplicate values.
+    pub non_unique: bool,
+}
+
+/// A row of `information_schema.VIEWS`
+#[derive(Clone, Debug)]
+pub struct ViewRow{
+    /// The schema the view belongs to.
+    pub table_schema: String,
+    /// The name of the view.
+    pub table_name: String,
+    /// The query defining the view.
+    pub view_definition: String,
+    /// The algorithm of the view. Only MariaDB exposes `ALGORITHM` in `information_schema.VIEWS`, so it is read from `SHOW CREATE VIEW` on MySQL.
+    pub algorithm: Option<String>,
+    /// The account that defined the view.
+    pub definer: String,
+    /// Either `DEFINER` or `INVOKER`.
+    pub security_type: String,
+    /// Either `NONE`, `LOCAL` or `CASCADED`.
+    pub check_option: String,
+}
+
+/// A row of `information_schema.ROUTINES`
+#[derive(Clone, Debug)]
+pub struct RoutineRow{
+    /// The name of the routine.
+    pub routine_name: String,
+    /// Either `PROCEDURE` or `FUNCTION`.
+    pub routine_type: String,
+    /// The return type of a function.
+    pub dtd_identifier: Option<String>,
+    /// The body of the routine. `None` if the user lacks the privilege to see it.
+    pub routine_definition: Option<String>,
+    /// Whether the routine is deterministic.
+    pub is_deterministic: bool,
+    /// The data access characteristic of the routine (e.g. `READS SQL DATA`).
+    pub sql_data_access: String,
+    /// Either `DEFINER` or `INVOKER`.
+    pub security_type: String,
+    /// The comment of the routine.
+    pub routine_comment: String,
+    /// The account that defined the routine.
+    pub definer: String,
+}
+
+/// A row of `information_schema.PARAMETERS`
+#[derive(Clone, Debug)]
+pub struct ParameterRow{
+    /// The name of the routine the parameter belongs to.
+    pub specific_name: String,
+    /// Either `PROCEDURE` or `FUNCTION`.
+    pub routine_type: String,
+    /// The position of the parameter, starting at 1.
+    pub ordinal_position: u64,
+    /// `IN`, `OUT` or `INOUT` for procedures. `None` for functions.
+    pub parameter_mode: Option<String>,
+    /// The name of the parameter.
+    pub parameter_name: String,
+    /// The data type of the parameter.
+    pub dtd_identifier: String,
+}
+
+/// A row of `information_schema.TRIGGERS`
+#[derive(Clone, Debug)]
+pub struct TriggerRow{
+    /// The name of the trigger.
+    pub trigger_name: String,
+    /// Either `INSERT`, `UPDATE` or `DELETE`.
+    pub event_manipulation: String,
+    /// The name of the table the trigger is defined on.
+    pub event_object_table: String,
+    /// The position of the trigger among triggers with the same timing & event.
+    pub action_order: u64,
+    /// The body of the trigger.
+    pub action_statement: String,
+    /// Either `BEFORE` or `AFTER`.
+    pub action_timing: String,
+    /// The account that defined the trigger.
+    pub definer: String,
+}
+
+/// A row of `information_schema.EVENTS`
+///
+/// Time stamps are formatted as `%Y-%m-%d %H:%i:%s` by the query.
+#[derive(Clone, Debug)]
+pub struct EventRow{
+    /// The name of the event.
+    pub event_name: String,
+    /// The account that defined the event.
+    pub definer: String,
+    /// Either `ONE TIME` or `RECURRING`.
+    pub event_type: String,
+    /// The time stamp a one time event is executed at.
+    pub execute_at: Option<String>,
+    /// The quantity of the interval of a recurring event.
+    pub interval_value: Option<String>,
+    /// The unit of the interval of a recurring event.
+    pub interval_field: Option<String>,
+    /// The time stamp a recurring event starts at.
+    pub starts: Option<String>,
+    /// The time stamp a recurring event ends at.
+    pub ends: Option<String>,
+    /// Either `ENABLED`, `DISABLED` or `SLAVESIDE_DISABLED`.
+    pub status: String,
+    /// Either `PRESERVE` or `NOT PRESERVE`.
+    pub on_completion: String,
+    /// The body of the event.
+    pub event_definition: String,
+    /// The comment of the event.
+    pub event_comment: String,
+}
+
+/// Returns every `Relation` of the connected database using a fixed number of `INFORMATION_SCHEMA` queries.
+///
+/// # Arguments
+///
+/// * `db` - The database to load the relations from.
+///
+/// # Errors
+///
+/// This function will return an error if any of the `INFORMATION_SCHEMA` queries fail.
+pub fn load<D: Executor>(db: &D) -> Result<Vec<Relation>, Error> {
+    let columns: Vec<ColumnRow> = load_columns(db, "")?;
+
+    let foreign_keys: Vec<ForeignKeyRow> = query(
+        db,
+        "SELECT TABLE_NAME, COLUMN_NAME, REFERENCED_TABLE_NAME, REFERENCED_COLUMN_NAME, CONSTRAINT_NAME, ORDINAL_POSITION FROM information_schema.KEY_COLUMN_USAGE WHERE TABLE_SCHEMA = DATABASE() AND REFERENCED_TABLE_NAME IS NOT NULL ORDER BY TABLE_NAME, CONSTRAINT_NAME, ORDINAL_POSITION",
+        |row| {
+            Some(
+                ForeignKeyRow {
+                    table_name: row.get_opt(0)?.ok()?,
+                    column_name: row.get_opt(1)?.ok()?,
+                    referenced_table_name: row.get_opt(2)?.ok()?,
+                    referenced_column_name: row.get_opt(3)?.ok()?,
+                    constraint_name: row.get_opt(4)?.ok()?,
+                    ordinal_position: row.get_opt(5)?.ok()?,
+                }
+            )
+        }
+    )?;
+
+    let indexes: Vec<IndexRow> = query(
+        db,
+        "SELECT TABLE_NAME, INDEX_NAME, COLUMN_NAME, NON_UNIQUE FROM information_schema.STATISTICS WHERE TABLE_SCHEMA = DATABASE() ORDER BY TABLE_NAME, INDEX_NAME, SEQ_IN_INDEX",
+        |row| {
+            Some(
+                IndexRow {
+                    table_name: row.get_opt(0)?.ok()?,
+                    index_name: row.get_opt(1)?.ok()?,
+                    column_name: row.get_opt(2)?.ok()?,
+                    non_unique: row.get_opt::<i64, usize>(3)?.ok()? != 0,
+                }
+            )
+        }
+    )?;
+
+    let views: Vec<ViewRow> = load_views(db, "")?;
+
+    let routines: Vec<RoutineRow> = query(
+        db,
+        "SELECT ROUTINE_NAME, ROUTINE_TYPE, DTD_IDENTIFIER, ROUTINE_DEFINITION, IS_DETERMINISTIC, SQL_DATA_ACCESS, SECURITY_TYPE, ROUTINE_COMMENT, DEFINER FROM information_schema.ROUTINES WHERE ROUTINE_SCHEMA = DATABASE() ORDER BY ROUTINE_NAME",
+        |row| {
+            Some(
+                RoutineRow {
+                    routine_name: row.get_opt(0)?.ok()?,
+                    routine_type: row.get_opt(1)?.ok()?,
+                    dtd_identifier: row.get_opt(2)?.ok()?,
+                    routine_definition: row.get_opt(3)?.ok()?,
+                    is_deterministic: row.get_opt::<String, usize>(4)?.ok()? == "YES",
+                    sql_data_access: row.get_opt(5)?.ok()?,
+                    security_type: row.get_opt(6)?.ok()?,
+                    routine_comment: row.get_opt(7)?.ok()?,
+                    definer: row.get_opt(8)?.ok()?,
+                }
+            )
+        }
+    )?;
+
+    let parameters: Vec<ParameterRow> = query(
+        db,
+        "SELECT SPECIFIC_NAME, ROUTINE_TYPE, ORDINAL_POSITION, PARAMETER_MODE, PARAMETER_NAME, DTD_IDENTIFIER FROM information_schema.PARAMETERS WHERE SPECIFIC_SCHEMA = DATABASE() AND ORDINAL_POSITION > 0 ORDER BY SPECIFIC_NAME, ORDINAL_POSITION",
+        |row| {
+            Some(
+                ParameterRow {
+                    specific_name: row.get_opt(0)?.ok()?,
+                    routine_type: row.get_opt(1)?.ok()?,
+                    ordinal_position: row.get_opt(2)?.ok()?,
+                    parameter_mode: row.get_opt(3)?.ok()?,
+                    parameter_name: row.get_opt(4)?.ok()?,
+                    dtd_identifier: row.get_opt(5)?.ok()?,
+                }
+            )
+        }
+    )?;
+
+    let triggers: Vec<TriggerRow> = query(
+        db,
+        "SELECT TRIGGER_NAME, EVENT_MANIPULATION, EVENT_OBJECT_TABLE, ACTION_ORDER, ACTION_STATEMENT, ACTION_TIMING, DEFINER FROM information_schema.TRIGGERS WHERE TRIGGER_SCHEMA = DATABASE() ORDER BY EVENT_OBJECT_TABLE, ACTION_TIMING, EVENT_MANIPULATION, ACTION_ORDER",
+        |row| {
+            Some(
+                TriggerRow {
+                    trigger_name: row.get_opt(0)?.ok()?,
+                    event_manipulation: row.get_opt(1)?.ok()?,
+                    event_object_table: row.get_opt(2)?.ok()?,
+                    action_order: row.get_opt(3)?.ok()?,
+                    action_statement: row.get_opt(4)?.ok()?,
+                    action_timing: row.get_opt(5)?.ok()?,
+                    definer: row.get_opt(6)?.ok()?,
+                }
+            )
+        }
+    )?;
+
+    let events: Vec<EventRow> = query(
+        db,
+        "SELECT EVENT_NAME, DEFINER, EVENT_TYPE, DATE_FORMAT(EXECUTE_AT, '%Y-%m-%d %H:%i:%s'), INTERVAL_VALUE, INTERVAL_FIELD, DATE_FORMAT(STARTS, '%Y-%m-%d %H:%i:%s'), DATE_FORMAT(ENDS, '%Y-%m-%d %H:%i:%s'), STATUS, ON_COMPLETION, EVENT_DEFINITION, EVENT_COMMENT FROM information_schema.EVENTS WHERE EVENT_SCHEMA = DATABASE() ORDER BY EVENT_NAME",
+        |row| {
+            Some(
+                EventRow {
+                    event_name: row.get_opt(0)?.ok()?,
+                    definer: row.get_opt(1)?.ok()?,
+                    event_type: row.get_opt(2)?.ok()?,
+                    execute_at: row.get_opt(3)?.ok()?,
+                    interval_value: row.get_opt(4)?.ok()?,
+                    interval_field: row.get_opt(5)?.ok()?,
+                    starts: row.get_opt(6)?.ok()?,
+                    ends: row.get_opt(7)?.ok()?,
+                    status: row.get_opt(8)?.ok()?,
+                    on_completion: row.get_opt(9)?.ok()?,
+                    event_definition: row.get_opt(10)?.ok()?,
+                    event_comment: row.get_opt(11)?.ok()?,
+                }
+            )
+        }
+    )?;
+
+    Ok(
+        assemble(
+            &SchemaRows {
+                columns,
+                foreign_keys,
+                indexes,
+                views,
+                routines,
+                parameters,
+                triggers,
+                events,
+            }
+        )
+    )
+}
+
+/// Returns the `View` with the given name from the connected database, or `None` if the view does not exist.
+///
+/// # Arguments
+///
+/// * `db` - The database to load the view from.
+/// * `name` - The name of the view.
+///
+/// # Errors
+///
+/// This function will return an error if any of the `INFORMATION_SCHEMA` queries fail.
+pub fn load_view<D: Executor>(db: &D, name: &str) -> Result<Option<View>, Error> {
+    let condition = format!(" AND TABLE_NAME = '{}'", name.replace('\'', "''"));
+
+    let columns = load_columns(db, &condition)?;
+    let views = load_views(db, &condition)?;
+
+    Ok(views.first().map(|view| view_from_row(view, &columns)))
+}
+
+/// Returns the rows of `information_schema.COLUMNS` of the current schema that satisfy the additional `condition`.
+fn load_columns<D: Executor>(db: &D, condition: &str) -> Result<Vec<ColumnRow>, Error> {
+    query(
+        db,
+        &format!("SELECT TABLE_NAME, COLUMN_NAME, ORDINAL_POSITION, COLUMN_TYPE, IS_NULLABLE, EXTRA FROM information_schema.COLUMNS WHERE TABLE_SCHEMA = DATABASE(){} ORDER BY TABLE_NAME, ORDINAL_POSITION", condition),
+        |row| {
+            Some(
+                ColumnRow {
+                    table_name: row.get_opt(0)?.ok()?,
+                    column_name: row.get_opt(1)?.ok()?,
+                    ordinal_position: row.get_opt(2)?.ok()?,
+                    column_type: row.get_opt(3)?.ok()?,
+                    is_nullable: row.get_opt::<String, usize>(4)?.ok()? == "YES",
+                    extra: row.get_opt(5)?.ok()?,
+                }
+            )
+        }
+    )
+}
+
+/// Returns the rows of `information_schema.VIEWS` of the current schema that satisfy the additional `condition`.
+///
+/// Every column is selected so that `ALGORITHM` is read when the server provides it. Otherwise it is read from the `SHOW CREATE VIEW` statement of each view.
+fn load_views<D: Executor>(db: &D, condition: &str) -> Result<Vec<ViewRow>, Error> {
+    let mut views: Vec<ViewRow> = query(
+        db,
+        &format!("SELECT * FROM information_schema.VIEWS WHERE TABLE_SCHEMA = DATABASE(){} ORDER BY TABLE_NAME", condition),
+        |row| {
+            Some(
+                ViewRow {
+                    table_schema: row.get_opt("TABLE_SCHEMA")?.ok()?,
+                    table_name: row.get_opt("TABLE_NAME")?.ok()?,
+                    view_definition: row.get_opt("VIEW_DEFINITION")?.ok()?,
+                    algorithm: row.get_opt("ALGORITHM").and_then(|algorithm| algorithm.ok()),
+                    definer: row.get_opt("DEFINER")?.ok()?,
+                    security_type: row.get_opt("SECURITY_TYPE")?.ok()?,
+                    check_option: row.get_opt("CHECK_OPTION")?.ok()?,
+                }
+            )
+        }
+    )?;
+
+    for view in views.iter_mut().filter(|view| view.algorithm.is_none()) {
+        let create_view: Vec<Option<String>> = db.execute(
+            &SQL::Show(DDL(format!("SHOW CREATE VIEW `{}`", view.table_name))),
+            |row| row.ok().and_then(|row| row.get_opt(1)).and_then(|create_view| create_view.ok())
+        )?;
+
+        view.algorithm = create_view.into_iter()
+            .flatten()
+            .find_map(|create_view| create_view_algorithm(&create_view));
+    }
+
+    Ok(views)
+}
+
+/// Returns the algorithm of a view from its `SHOW CREATE VIEW` statement (e.g. `CREATE ALGORITHM=MERGE DEFINER=...`).
+fn create_view_algorithm(create_view: &str) -> Option<String> {
+    lazy_static!{
+        static ref ALGORITHM : Regex = Regex::new(r"(?i)^\s*CREATE\s+(?:OR\s+REPLACE\s+)?ALGORITHM\s*=\s*([a-zA-Z]+)").unwrap();
+    };
+
+    ALGORITHM.captures(create_view)
+        .map(|captures| captures[1].to_ascii_uppercase())
+}
+
+/// Builds a `View` from its row of `information_schema.VIEWS` & the rows of `information_schema.COLUMNS` of the view.
+fn view_from_row(view: &ViewRow, columns: &[ColumnRow]) -> View {
+    //views on the same schema are referenced without the schema prefix
+    let query = view.view_definition.replace(&format!("`{}`.", view.table_schema), "");
+
+    let mut view_columns: Vec<&ColumnRow> = columns.iter()
+        .filter(|column| column.table_name == view.table_name)
+        .collect();
+    view_columns.sort_by_key(|column| column.ordinal_position);
+
+    View {
+        name: view.table_name.clone(),
+        query: QDL(query),
+        algorithm: view.algorithm.as_ref()
+            .and_then(|algorithm| ViewAlgorithm::from(algorithm))
+            .unwrap_or(ViewAlgorithm::Undefined),
+        definer: view.definer.clone(),
+        security: SqlSecurity::from(&view.security_type).unwrap_or(SqlSecurity::Definer),
+        check_option: CheckOption::from(&view.check_option).unwrap_or(CheckOption::None),
+        columns: view_columns.iter()
+            .map(|column| column.column_name.clone())
+            .collect(),
+    }
+}
+
+/// Executes an `INFORMATION_SCHEMA` query and maps every row with `row_map`, skipping rows that fail to load or map.
+fn query<D: Executor, T, F>(db: &D, cmd: &str, row_map: F) -> Result<Vec<T>, Error> where F: Fn(Row) -> Option<T> {
+    let rows: Vec<Option<T>> = db.execute(
+        &SQL::Select(QDL(cmd.to_string())),
+        |row| {
+            match row {
+                Ok(row) => row_map(row),
+                Err(err) => {
+                    log::error!("Failed to load row of \"{}\" - Err:{:?}", cmd, err);
+                    None
+                }
+            }
+        }
+    )?;
+
+    Ok(rows.into_iter().flatten().collect())
+}
+
+/// Builds the `Relation`s described by `INFORMATION_SCHEMA` rows.
+///
+/// Every table found in `rows.columns` that is not listed in `rows.views` is treated as a base table.
+/// Tables & views are returned ordered by name, followed by the routines & then the events ordered by name.
+///
+/// # Arguments
+///
+/// * `rows` - The `INFORMATION_SCHEMA` rows of a database
+pub fn assemble(rows: &SchemaRows) -> Vec<Relation> {
+    let SchemaRows { columns, foreign_keys, indexes, views, routines, parameters, triggers, events } = rows;
+
+    let view_names: HashSet<&str> = views.iter()
+        .map(|view| view.table_name.as_str())
+        .collect();
+
+    //group every index by (table, index) so that single column unique indexes & primary keys can be found
+    let index_columns: BTreeMap<(&str, &str), (bool, Vec<&str>)> = {
+        let mut index_columns: BTreeMap<(&str, &str), (bool, Vec<&str>)> = BTreeMap::new();
+
+        indexes.iter()
+            .for_each(|index| {
+                index_columns.entry((&index.table_name, &index.index_name))
+                    .or_insert_with(|| (index.non_unique, Vec::new()))
+                    .1
+                    .push(&index.column_name);
+            });
+
+        index_columns
+    };
+
+    let primary_keys: HashMap<&str, &Vec<&str>> = index_columns.iter()
+        .filter(|((_table, index), _)| *index == "PRIMARY")
+        .map(|((table, _index), (_non_unique, columns))| (*table, columns))
+        .collect();
+
+    let unique_columns: HashSet<(&str, &str)> = index_columns.iter()
+        .filter(|((_table, index), (non_unique, columns))| *index != "PRIMARY" && !non_unique && columns.len() == 1)
+        .map(|((table, _index), (_non_unique, columns))| (*table, columns[0]))
+        .collect();
+
+    //group every foreign key column by (table, constraint) in key order so that composite foreign keys become a single constraint
+    let foreign_key_columns: BTreeMap<(&str, &str), Vec<&ForeignKeyRow>> = {
+        let mut foreign_key_columns: BTreeMap<(&str, &str), Vec<&ForeignKeyRow>> = BTreeMap::new();
+
+        foreign_keys.iter()
+            .for_each(|foreign_key| {
+                foreign_key_columns.entry((&foreign_key.table_name, &foreign_key.constraint_name))
+                    .or_default()
+                    .push(foreign_key);
+            });
+
+        foreign_key_columns.values_mut()
+            .for_each(|columns| columns.sort_by_key(|column| column.ordinal_position));
+
+        foreign_key_columns
+    };
+
+    let mut relations: BTreeMap<String, Relation> = BTreeMap::new();
+
+    let mut table_columns: BTreeMap<&str, Vec<&ColumnRow>> = BTreeMap::new();
+    columns.iter()
+        .filter(|column| !view_names.contains(column.table_name.as_str()))
+        .for_each(|column| table_columns.entry(&column.table_name).or_default().push(column));
+
+    table_columns.into_iter()
+        .for_each(|(table_name, mut columns)| {
+            columns.sort_by_key(|column| column.ordinal_position);
+
+            let attributes: Vec<Attribute> = columns.iter()
+                .map(|column| {
+                    let data_type = AttributeType::from_raw(&column.column_type);
+
+                    let mut constraint: HashSet<Constraint> = HashSet::new();
+
+                    if !column.is_nullable {
+                        constraint.insert(Constraint::NotNull);
+                    }
+                    if column.extra == "auto_increment" {
+                        constraint.insert(Constraint::AutoIncrement);
+                    }
+                    if unique_columns.contains(&(table_name, column.column_name.as_str())) {
+                        constraint.insert(Constraint::Unique);
+                    }
+                    //a foreign key is declared on the first of its columns
+                    foreign_key_columns.iter()
+                        .filter(|((table, _constraint), foreign_key)| *table == table_name && foreign_key[0].column_name == column.column_name)
+                        .for_each(|((_table, constraint_name), foreign_key)| {
+                            constraint.insert(
+                                Constraint::ForeignKey {
+                                    table_name: foreign_key[0].referenced_table_name.clone(),
+                                    attribute_name: foreign_key[0].referenced_column_name.clone(),
+                                    constraint_name: Some(constraint_name.to_string()),
+                                    other_columns: foreign_key[1..].iter()
+                                        .map(|foreign_key| (foreign_key.column_name.clone(), foreign_key.referenced_column_name.clone()))
+                                        .collect()
+                                }
+                            );
+                        });
+
+                    Attribute {
+                        name: column.column_name.clone(),
+                        data_type,
+                        constraint
+                    }
+                })
+                .collect();
+
+            let primary_key = primary_keys.get(table_name)
+                .map(|primary_key| {
+                    primary_key.iter()
+                        .filter_map(|primary_key| attributes.iter().position(|attr| &attr.name == primary_key))
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            let mut table_triggers: Vec<Trigger> = triggers.iter()
+                .filter(|trigger| trigger.event_object_table == table_name)
+                .filter_map(|trigger| {
+                    Some(
+                        Trigger {
+                            name: trigger.trigger_name.clone(),
+                            table_name: table_name.to_string(),
+                            timing: TriggerTiming::from(&trigger.action_timing)?,
+                            event: TriggerEvent::from(&trigger.event_manipulation)?,
+                            order: trigger.action_order,
+                            body: trigger.action_statement.clone(),
+                            definer: trigger.definer.clone(),
+                        }
+                    )
+                })
+                .collect();
+            table_triggers.sort_by_key(|trigger| trigger.order);
+
+            relations.insert(
+                table_name.to_string(),
+                Relation::Table(
+                    Table {
+                        name: table_name.to_string(),
+                        attributes,
+                        primary_key,
+                        triggers: table_triggers,
+                    }
+                )
+            );
+        });
+
+    views.iter()
+        .for_each(|view| {
+            relations.insert(
+                view.table_name.clone(),
+                Relation::View(view_from_row(view, columns))
+            );
+        });
+
+    let mut relations: Vec<Relation> = relations.into_values().collect();
+
+    routines.iter()
+        .filter_map(|routine| {
+            let routine_type = RoutineType::from(&routine.routine_type)?;
+
+            let body = match &routine.routine_definition {
+                Some(body) => body.clone(),
+                None => {
+                    log::error!("Missing privilege to load the definition of routine {}", routine.routine_name);
+                    return None
+                }
+            };
+
+            Some(
+                Routine {
+                    name: routine.routine_name.clone(),
+                    parameters: parameters.iter()
+                        .filter(|parameter| parameter.specific_name == routine.routine_name && RoutineType::from(&parameter.routine_type).as_ref() == Some(&routine_type))
+                        .map(|parameter| {
+                            Parameter {
+                                name: parameter.parameter_name.clone(),
+                                mode: parameter.parameter_mode.as_ref().and_then(|mode| ParameterMode::from(mode)),
+                                data_type: parameter.dtd_identifier.clone(),
+                            }
+                        })
+                        .collect(),
+                    return_type: match routine_type {
+                        RoutineType::Function => routine.dtd_identifier.clone(),
+                        RoutineType::Procedure => None,
+                    },
+                    routine_type,
+                    body,
+                    definer: routine.definer.clone(),
+                    characteristics: Characteristics {
+                        deterministic: routine.is_deterministic,
+                        data_access: DataAccess::from(&routine.sql_data_access).unwrap_or(DataAccess::ContainsSql),
+                        security: SqlSecurity::from(&routine.security_type).unwrap_or(SqlSecurity::Definer),
+                        comment: routine.routine_comment.clone(),
+                    },
+                }
+            )
+        })
+        .for_each(|routine| relations.push(Relation::Routine(routine)));
+
+    events.iter()
+        .filter_map(|event| {
+            let schedule = match event.event_type.to_ascii_uppercase().as_str() {
+                "ONE TIME" => Schedule::At(event.execute_at.clone()?),
+                "RECURRING" => Schedule::Every {
+                    interval_value: event.interval_value.clone()?,
+                    interval_field: event.interval_field.clone()?,
+                    starts: event.starts.clone(),
+                    ends: event.ends.clone(),
+                },
+                _ => return None,
+            };
+
+            Some(
+                Event {
+                    name: event.event_name.clone(),
+                    schedule,
+                    status: EventStatus::from(&event.status)?,
+                    preserve: event.on_completion.eq_ignore_ascii_case("PRESERVE"),
+                    body: event.event_definition.clone(),
+                    definer: event.definer.clone(),
+                    comment: event.event_comment.clone(),
+                }
+            )
+        })
+        .for_each(|event| relations.push(Relation::Event(event)));
+
+    relations
+}
+
+#[cfg(test)]
+mod tests{
+    #![allow(unused_imports)]
+    use std::collections::HashSet;
+
+    use crate::backend::{relation::{Relation, RelationMethods, table::{AttributeType, Constraint}, event::EventStatus, routine::SqlSecurity, view::{ViewAlgorithm, CheckOption}}, sql::QDL};
+
+    use super::{assemble, create_view_algorithm, SchemaRows, ColumnRow, ForeignKeyRow, IndexRow, ViewRow, RoutineRow, ParameterRow, TriggerRow, EventRow};
+
+    #[allow(unused_macros)]
+    macro_rules! column {
+        ($table_name: literal, $column_name: literal, $ordinal_position: literal, $column_type: literal, $is_nullable: literal, $extra: literal) => {
+            ColumnRow {
+                table_name: String::from($table_name),
+                column_name: String::from($column_name),
+                ordinal_position: $ordinal_position,
+                column_type: String::from($column_type),
+                is_nullable: $is_nullable,
+                extra: String::from($extra),
+            }
+        };
+    }
+
+    #[allow(unused_macros)]
+    macro_rules! index {
+        ($table_name: literal, $index_name: literal, $column_name: literal, $non_unique: literal) => {
+            IndexRow {
+                table_name: String::from($table_name),
+                index_name: String::from($index_name),
+                column_name: String::from($column_name),
+                non_unique: $non_unique,
+            }
+        };
+    }
+
+    #[test]
+    fn assemble_test_1() {
+        let columns = vec![
+            column!("patients", "id", 1, "int(11)", false, "auto_increment"),
+            column!("patients", "email", 2, "varchar(255)", true, ""),
+            column!("appointments", "patient_id", 2, "int(11)", true, ""),
+            column!("appointments", "id", 1, "int(11)", false, ""),
+        ];
+        let foreign_keys = vec![
+            ForeignKeyRow {
+                table_name: String::from("appointments"),
+                column_name: String::from("patient_id"),
+                referenced_table_name: String::from("patients"),
+                referenced_column_name: String::from("id"),
+                constraint_name: String::from("appointments_ibfk_1"),
+                ordinal_position: 1,
+            }
+        ];
+        let indexes = vec![
+            index!("patients", "PRIMARY", "id", false),
+            index!("patients", "email", "email", false),
+            index!("appointments", "PRIMARY", "id", false),
+            index!("appointments", "patient_id", "patient_id", true),
+        ];
+
+        let actual = assemble(&SchemaRows { columns, foreign_keys, indexes, ..Default::default() });
+
+        assert_eq!(actual.len(), 2);
+
+        match &actual[0] {
+            Relation::Table(table) => {
+                assert_eq!(table.name, "appointments");
+                assert_eq!(
+                    table.attributes.iter().map(|attr| attr.name.clone()).collect::<Vec<String>>(),
+                    vec![String::from("id"), String::from("patient_id")]
+                );
+                assert_eq!(table.primary_key, vec![0]);
+                assert_eq!(
+                    table.get_foreign_keys(),
+                    Some(vec![(String::from("patients"), String::from("id"))])
+                );
+                assert!(!table.attributes[1].constraint.contains(&Constraint::Unique));
+            },
+            _ => panic!("expected a table"),
+        }
+
+        match &actual[1] {
+            Relation::Table(table) => {
+                assert_eq!(table.name, "patients");
+                assert_eq!(table.primary_key, vec![0]);
+                assert!(table.attributes[0].constraint.contains(&Constraint::NotNull));
+                assert!(table.attributes[0].constraint.contains(&Constraint::AutoIncrement));
+                assert!(!table.attributes[1].constraint.contains(&Constraint::NotNull));
+                assert!(table.attributes[1].constraint.contains(&Constraint::Unique));
+            },
+            _ => panic!("expected a table"),
+        }
+    }
+
+    #[test]
+    fn assemble_test_2() {
+        //columns with unsupported types are kept with the type written by the database
+        let columns = vec![
+            column!("table_1", "attr_1", 1, "geometry", true, ""),
+            column!("table_1", "attr_2", 2, "int(11)", false, ""),
+        ];
+        let indexes = vec![
+            index!("table_1", "PRIMARY", "attr_2", false),
+        ];
+
+        let actual = assemble(&SchemaRows { columns, indexes, ..Default::default() });
+
+        match &actual[0] {
+            Relation::Table(table) => {
+                assert_eq!(table.attributes.len(), 2);
+                assert_eq!(table.attributes[0].data_type.to_string(), "geometry");
+                assert_eq!(table.primary_key, vec![1]);
+            },
+            _ => panic!("expected a table"),
+        }
+    }
+
+    #[test]
+    fn assemble_test_3() {
+        //composite unique indexes do not mark any single column as unique
+        let columns = vec![
+            column!("table_1", "attr_1", 1, "int(11)", true, ""),
+            column!("table_1", "attr_2", 2, "int(11)", true, ""),
+        ];
+        let indexes = vec![
+            index!("table_1", "attr_1_attr_2", "attr_1", false),
+            index!("table_1", "attr_1_attr_2", "attr_2", false),
+        ];
+
+        let actual = assemble(&SchemaRows { columns, indexes, ..Default::default() });
+
+        match &actual[0] {
+            Relation::Table(table) => {
+                assert!(table.primary_key.is_empty());
+                assert!(table.attributes.iter().all(|attr| !attr.constraint.contains(&Constraint::Unique)));
+            },
+            _ => panic!("expected a table"),
+        }
+    }
+
+    #[test]
+    fn assemble_test_4() {
+        //view columns are not loaded as tables & the schema prefix is removed from the definition
+        let columns = vec![
+            column!("table_1", "attr_1", 1, "int(11)", true, ""),
+            column!("view_1", "attr_1", 1, "int(11)", true, ""),
+        ];
+        let views = vec![
+            ViewRow {
+                table_schema: String::from("db"),
+                table_name: String::from("view_1"),
+                view_definition: String::from("select `db`.`table_1`.`attr_1` AS `attr_1` from `db`.`table_1`"),
+                algorithm: None,
+                definer: String::from("root@localhost"),
+                security_type: String::from("INVOKER"),
+                check_option: String::from("LOCAL"),
+            }
+        ];
+
+        let actual = assemble(&SchemaRows { columns, views, ..Default::default() });
+
+        assert_eq!(actual.len(), 2);
+
+        match &actual[1] {
+            Relation::View(view) => {
+                assert_eq!(view.name, "view_1");
+                assert_eq!(view.query, QDL(String::from("select `table_1`.`attr_1` AS `attr_1` from `table_1`")));
+                assert_eq!(view.columns, vec![String::from("attr_1")]);
+                assert_eq!(view.security, SqlSecurity::Invoker);
+                assert_eq!(view.check_option, CheckOption::Local);
+                assert_eq!(view.algorithm, ViewAlgorithm::Undefined);
+            },
+            _ => panic!("expected a view"),
+        }
+    }
+
+    #[test]
+    fn assemble_test_5() {
+        //routines are placed after tables & views with their parameters in order
+        let columns = vec![
+            column!("table_1", "attr_1", 1, "int(11)", true, ""),
+        ];
+        let routines = vec![
+            RoutineRow {
+                routine_name: String::from("routine_1"),
+                routine_type: String::from("FUNCTION"),
+                dtd_identifier: Some(String::from("int")),
+                routine_definition: Some(String::from("RETURN a + b")),
+                is_deterministic: true,
+                sql_data_access: String::from("NO SQL"),
+                security_type: String::from("INVOKER"),
+                routine_comment: String::new(),
+                definer: String::from("root@localhost"),
+            },
+            RoutineRow {
+                routine_name: String::from("routine_2"),
+                routine_type: String::from("PROCEDURE"),
+                dtd_identifier: None,
+                routine_definition: None,
+                is_deterministic: false,
+                sql_data_access: String::from("CONTAINS SQL"),
+                security_type: String::from("DEFINER"),
+                routine_comment: String::new(),
+                definer: String::from("root@localhost"),
+            },
+        ];
+        let parameters = vec![
+            ParameterRow {
+                specific_name: String::from("routine_1"),
+                routine_type: String::from("FUNCTION"),
+                ordinal_position: 2,
+                parameter_mode: None,
+                parameter_name: String::from("b"),
+                dtd_identifier: String::from("int"),
+            },
+            ParameterRow {
+                specific_name: String::from("routine_1"),
+                routine_type: String::from("FUNCTION"),
+                ordinal_position: 1,
+                parameter_mode: None,
+                parameter_name: String::from("a"),
+                dtd_identifier: String::from("int"),
+            },
+        ];
+
+        let mut parameters = parameters;
+        parameters.sort_by_key(|parameter| parameter.ordinal_position);
+
+        let actual = assemble(&SchemaRows { columns, routines, parameters, ..Default::default() });
+
+        //routine_2's definition could not be loaded
+        assert_eq!(actual.len(), 2);
+
+        match &actual[1] {
+            Relation::Routine(routine) => {
+                assert_eq!(routine.name, "routine_1");
+                assert_eq!(routine.signature(), "(a int, b int) RETURNS int");
+                assert_eq!(routine.body, "RETURN a + b");
+            },
+            _ => panic!("expected a routine"),
+        }
+    }
+
+    #[test]
+    fn assemble_test_6() {
+        //triggers are attached to their table in execution order
+        let columns = vec![
+            column!("table_1", "attr_1", 1, "int(11)", true, ""),
+            column!("table_2", "attr_1", 1, "int(11)", true, ""),
+        ];
+        let triggers = vec![
+            TriggerRow {
+                trigger_name: String::from("trigger_2"),
+                event_manipulation: String::from("INSERT"),
+                event_object_table: String::from("table_1"),
+                action_order: 2,
+                action_statement: String::from("SET NEW.attr_1 = NEW.attr_1 * 2"),
+                action_timing: String::from("BEFORE"),
+                definer: String::from("root@localhost"),
+            },
+            TriggerRow {
+                trigger_name: String::from("trigger_1"),
+                event_manipulation: String::from("INSERT"),
+                event_object_table: String::from("table_1"),
+                action_order: 1,
+                action_statement: String::from("SET NEW.attr_1 = NEW.attr_1 + 1"),
+                action_timing: String::from("BEFORE"),
+                definer: String::from("root@localhost"),
+            },
+        ];
+
+        let actual = assemble(&SchemaRows { columns, triggers, ..Default::default() });
+
+        match &actual[0] {
+            Relation::Table(table) => {
+                let names: Vec<&str> = table.triggers.iter().map(|trigger| trigger.name.as_str()).collect();
+                assert_eq!(names, vec!["trigger_1", "trigger_2"]);
+                assert_eq!(table.triggers[0].table_name, "table_1");
+            },
+            _ => panic!("expected a table"),
+        }
+        match &actual[1] {
+            Relation::Table(table) => assert!(table.triggers.is_empty()),
+            _ => panic!("expected a table"),
+        }
+    }
+
+    #[test]
+    fn assemble_test_7() {
+        //events are placed last with their schedule
+        let columns = vec![
+            column!("table_1", "attr_1", 1, "int(11)", true, ""),
+        ];
+        let events = vec![
+            EventRow {
+                event_name: String::from("event_1"),
+                definer: String::from("root@localhost"),
+                event_type: String::from("RECURRING"),
+                execute_at: None,
+                interval_value: Some(String::from("1")),
+                interval_field: Some(String::from("HOUR")),
+                starts: Some(String::from("2023-01-01 00:00:00")),
+                ends: None,
+                status: String::from("DISABLED"),
+                on_completion: String::from("NOT PRESERVE"),
+                event_definition: String::from("DELETE FROM table_1"),
+                event_comment: String::new(),
+            },
+        ];
+
+        let actual = assemble(&SchemaRows { columns, events, ..Default::default() });
+
+        assert_eq!(actual.len(), 2);
+
+        match &actual[1] {
+            Relation::Event(event) => {
+                assert_eq!(event.name, "event_1");
+                assert_eq!(event.status, EventStatus::Disabled);
+                assert!(!event.preserve);
+                assert_eq!(event.schedule.to_string(), "EVERY '1' HOUR STARTS '2023-01-01 00:00:00'");
+            },
+            _ => panic!("expected an event"),
+        }
+    }
+
+    #[test]
+    fn assemble_test_8() {
+        //every column of a composite primary key is kept in key order
+        let columns = vec![
+            column!("order_items", "product_id", 1, "int(11)", false, ""),
+            column!("order_items", "order_id", 2, "int(11)", false, ""),
+            column!("order_items", "quantity", 3, "int(11)", true, ""),
+        ];
+        let indexes = vec![
+            index!("order_items", "PRIMARY", "order_id", false),
+            index!("order_items", "PRIMARY", "product_id", false),
+        ];
+
+        let actual = assemble(&SchemaRows { columns, indexes, ..Default::default() });
+
+        match &actual[0] {
+            Relation::Table(table) => {
+                assert_eq!(table.primary_key, vec![1, 0]);
+                assert_eq!(table.primary_key_names(), vec!["order_id", "product_id"]);
+                assert!(table.create().ends_with("PRIMARY KEY(order_id,product_id))"));
+            },
+            _ => panic!("expected a table"),
+        }
+    }
+
+    #[test]
+    fn assemble_test_9() {
+        //the columns of a composite foreign key become a single constraint on its first column
+        let columns = vec![
+            column!("order_items", "order_id", 1, "int(11)", false, ""),
+            column!("order_items", "product_id", 2, "int(11)", false, ""),
+            column!("shipments", "id", 1, "int(11)", false, ""),
+            column!("shipments", "item_product_id", 2, "int(11)", true, ""),
+            column!("shipments", "item_order_id", 3, "int(11)", true, ""),
+        ];
+        let foreign_keys = vec![
+            ForeignKeyRow {
+                table_name: String::from("shipments"),
+                column_name: String::from("item_product_id"),
+                referenced_table_name: String::from("order_items"),
+                referenced_column_name: String::from("product_id"),
+                constraint_name: String::from("shipments_item_fk"),
+                ordinal_position: 2,
+            },
+            ForeignKeyRow {
+                table_name: String::from("shipments"),
+                column_name: String::from("item_order_id"),
+                referenced_table_name: String::from("order_items"),
+                referenced_column_name: String::from("order_id"),
+                constraint_name: String::from("shipments_item_fk"),
+                ordinal_position: 1,
+            },
+        ];
+        let indexes = vec![
+            index!("order_items", "PRIMARY", "order_id", false),
+            index!("order_items", "PRIMARY", "product_id", false),
+            index!("shipments", "PRIMARY", "id", false),
+        ];
+
+        let actual = assemble(&SchemaRows { columns, foreign_keys, indexes, ..Default::default() });
+
+        match &actual[1] {
+            Relation::Table(table) => {
+                assert_eq!(table.name, "shipments");
+                assert_eq!(
+                    table.attributes.iter()
+                        .map(|attribute| attribute.constraint.iter().filter(|constraint| matches!(constraint, Constraint::ForeignKey { .. })).count())
+                        .collect::<Vec<usize>>(),
+                    vec![0, 0, 1]
+                );
+
+                let create = table.create();
+
+                assert_eq!(create.matches("FOREIGN KEY").count(), 1);
+                assert!(create.contains("CONSTRAINT shipments_item_fk FOREIGN KEY(item_order_id,item_product_id) REFERENCES order_items(order_id,product_id)"));
+            },
+            _ => panic!("expected a table"),
+        }
+    }
+
+    #[test]
+    fn create_view_algorithm_test_1() {
+        //MySQL doesn't expose the algorithm in information_schema.VIEWS, only in SHOW CREATE VIEW
+        assert_eq!(
+            create_view_algorithm("CREATE ALGORITHM=MERGE DEFINER=`root`@`localhost` SQL SECURITY DEFINER VIEW `adults` AS select `patients`.`id` AS `id` from `patients` where (`patients`.`age` >= 18)"),
+            Some(String::from("MERGE"))
+        );
+        assert_eq!(
+            create_view_algorithm("CREATE OR REPLACE algorithm = temptable VIEW adults AS SELECT id FROM patients"),
+            Some(String::from("TEMPTABLE"))
+        );
+        assert_eq!(create_view_algorithm("CREATE VIEW adults AS SELECT id FROM patients WHERE algorithm = 1"), None);
+    }
+}
@@ -1,14 +1,23 @@
 use mysql::Error;
+use serde::{Deserialize, Serialize};
 
 use crate::backend::data_base::DataBase;
 
-use self::{table::Table, view::View};
+use self::{table::Table, view::View, routine::Routine, event::Event};
 
-use super::sql::{SQL, DDL, QDL};
+use super::sql::{DDL, QDL, SQL};
 
 pub mod table;
 pub mod view;
+pub mod routine;
+pub mod trigger;
+pub mod event;
 pub mod paths;
+pub mod information_schema;
+pub mod diff;
+pub mod schema_file;
+pub mod erd;
+pub mod impact;
 
 /// A trait representing methods for generating SQL statements for relations.
 pub trait RelationMethods {
@@ -22,80 +31,67 @@ pub trait RelationMethods {
 
 /// An enumeration representing a relation.
 ///
-/// A relation can either be a `Table` or a `View`.
-#[derive(Clone, Debug)]
+/// A relation can either be a `Table`, a `View`, a stored `Routine` or a scheduled `Event`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum Relation{
     Table(Table),
     View(View),
+    Routine(Routine),
+    Event(Event),
 }
 
 impl Relation {
     /// Returns a vector of `Relation`s from the database.
     ///
+    /// The relations are loaded in bulk from `INFORMATION_SCHEMA` rather than one query per relation.
+    ///
     /// # Errors
     ///
     /// This function will return an error if there is a problem accessing the database or executing the required queries.
     pub fn get_relations() -> Result<Vec<Relation>, Error> {
         match DataBase::from_env() {
-            Ok(db) => {
-                let rows: Vec<Relation> = db.execute(
-                    &SQL::new("SHOW FULL TABLES").unwrap(),
-                    |row| {
-                        match row {
-                            Ok(row) => {
-                                let name: String = row.get(0).unwrap();
-                                let relation_type: String = row.get(1).unwrap();
-
-                                return Some((name, relation_type));
-                            },
-                            Err(_err) => {
-                                return None;
-                            }
-                        };
-                    }
-                ).unwrap()
-                .iter()
-                .filter(|val| {
-                    match val {
-                        Some(_str) => true,
-                        None => false,
-                    }
-                })
-                .map(|val| {
-                    match val {
-                        Some(name_type_pair) => name_type_pair.clone(),
-                        None => panic!(),
-                    }
-                })
-                .map(|row| {
-                    let row : (&str, &str) = (&row.0, &row.1);
-                    match row {
-                        (name, "BASE TABLE") => {
-                            Relation::Table(Table::from_db(name).unwrap())
-                        },
-                        (name, "VIEW") => {
-                            Relation::View(View::from_db(name).unwrap())
-                        },
-                        _ => {
-                            panic!("Invalid row")
-                        }
-                    }
-                })
-                .collect();
-
-                return Ok(rows)
-            },
+            Ok(db) => information_schema::load(&db),
             Err(err) => {
                 panic!("{:?}", err);
             }
         }
     }
     
+    /// Returns the `Relation` defined by a `CREATE TABLE` or `CREATE VIEW` statement.
+    ///
+    /// Returns `None` for any other statement, including the creation of routines, triggers & events.
+    pub fn from_sql(sql: &SQL) -> Option<Relation> {
+        match sql {
+            SQL::Create(ddl) => {
+                if let Some(table) = Table::from_ddl(ddl) {
+                    return Some(Relation::Table(table))
+                }
+
+                View::from_ddl(ddl).map(Relation::View)
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns the tables & views defined by the `CREATE` statements of a sql file.
+    ///
+    /// Any other statement is ignored.
+    pub fn from_file(file_path: &str) -> Result<Vec<Relation>, std::io::Error> {
+        Ok(
+            SQL::from_file(file_path)?
+                .iter()
+                .filter_map(Relation::from_sql)
+                .collect()
+        )
+    }
+
     /// Returns the name of the relation as a `String`.
     pub fn name(&self) -> String {
         match self {
             Relation::Table(table) => table.name.clone(),
-            Relation::View(view) => view.name.clone()
+            Relation::View(view) => view.name.clone(),
+            Relation::Routine(routine) => routine.name.clone(),
+            Relation::Event(event) => event.name.clone(),
         }
     }
 }
@@ -105,18 +101,32 @@ impl RelationMethods for Relation{
         match self{
             Relation::Table(table) => table.select(),
             Relation::View(view) => view.select(),
+            Relation::Routine(routine) => routine.select(),
+            Relation::Event(event) => event.select(),
         }
     }
     fn drop(&self) -> DDL {
         match self {
             Relation::Table(table) => table.drop(),
             Relation::View(view) => view.drop(),
+            Relation::Routine(routine) => routine.drop(),
+            Relation::Event(event) => event.drop(),
         }
     }
     fn create(&self) -> DDL {
         match self {
             Relation::Table(table) => table.create(),
             Relation::View(view) => view.create(),
+            Relation::Routine(routine) => routine.create(),
+            Relation::Event(event) => event.create(),
         }
     }
-}
\ No newline at end of file
+}
+
+/// Returns the `DEFINER` clause value of an account in the form of `user@host` (e.g. `` `root`@`localhost` ``).
+pub(crate) fn definer_sql(definer: &str) -> String {
+    match definer.rsplit_once('@') {
+        Some((user, host)) => format!("`{}`@`{}`", user, host),
+        None => format!("`{}`", definer),
+    }
+}
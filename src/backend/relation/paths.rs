@@ -1,11 +1,11 @@
 use std::collections::{HashMap, LinkedList};
 
 use lazy_static::lazy_static;
-use log::info;
-use petgraph::{Graph, adj::NodeIndex, visit::NodeIndexable, Incoming, Outgoing, Directed};
+use log::{info, warn};
+use petgraph::{Graph, adj::NodeIndex, algo::tarjan_scc, visit::NodeIndexable, Incoming, Directed};
 use regex::Regex;
 
-use super::{Relation, view::View, table::Table};
+use super::{Relation, view::View, table::{Table, Constraint}, routine::RoutineType};
 
 /// DependencyTree is a forest graph of relation nodes and the dependency relationship between relation nodes
 pub type DependencyTree = Graph<NodeIndex, NodeIndex, Directed>;
@@ -33,7 +33,8 @@ pub type DependencyTree = Graph<NodeIndex, NodeIndex, Directed>;
 ///                     constraint: HashSet::new()
 ///                 }
 ///             ],
-///             primary_key: Some(0),
+///             primary_key: vec![0],
+///             triggers: vec![],
 ///         }
 ///     ),
 ///     Relation::Table(
@@ -46,14 +47,16 @@ pub type DependencyTree = Graph<NodeIndex, NodeIndex, Directed>;
 ///                     constraint: HashSet::from([
 ///                             Constraint::ForeignKey{
 ///                                 table_name: String::from("table_1"),
-///                                 attribute_name: String::from("attr_1")
+///                                 attribute_name: String::from("attr_1"),
+///                                 constraint_name: None
 ///                             }
 ///                         ]
 ///                     )
 ///                 }
 ///                 foreign_relation!["table_1"]
 ///             ],
-///             primary_key: Some(0),
+///             primary_key: vec![0],
+///             triggers: vec![],
 ///         }
 ///     ),
 /// ];
@@ -68,7 +71,7 @@ pub type DependencyTree = Graph<NodeIndex, NodeIndex, Directed>;
 /// 
 /// assert_eq_graph!(actual, expected, relations);
 /// ```
-pub fn get_dependency_tree(relations: &Vec<Relation>) -> DependencyTree {
+pub fn get_dependency_tree(relations: &[Relation]) -> DependencyTree {
     let name_to_index: HashMap<String, usize> = name_to_index_hashmap(relations);
     let mut index_to_node_index: HashMap<usize, NodeIndex> = HashMap::new();
 
@@ -88,7 +91,9 @@ pub fn get_dependency_tree(relations: &Vec<Relation>) -> DependencyTree {
             );
             match r {
                 Relation::Table(table) => add_table_edges(table, &mut edges, index, &name_to_index),
-                Relation::View(view) => add_view_edges(view, &name_to_index, &mut edges, index),
+                Relation::View(view) => add_view_edges(view, relations, &name_to_index, &mut edges, index),
+                Relation::Routine(routine) => add_body_edges(&routine.body, relations, &name_to_index, &mut edges, index),
+                Relation::Event(event) => add_body_edges(&event.body, relations, &name_to_index, &mut edges, index),
             }
         });
 
@@ -107,7 +112,7 @@ pub fn get_dependency_tree(relations: &Vec<Relation>) -> DependencyTree {
     dependency_tree
 }
 
-fn name_to_index_hashmap(relations: &Vec<Relation>) -> HashMap<String, usize> {
+fn name_to_index_hashmap(relations: &[Relation]) -> HashMap<String, usize> {
     let name_to_index: HashMap<String, usize> = {
         let mut name_to_index: HashMap<String, usize> = HashMap::new();
 
@@ -120,90 +125,59 @@ fn name_to_index_hashmap(relations: &Vec<Relation>) -> HashMap<String, usize> {
     name_to_index
 }
 
-fn add_view_edges(view: &View, name_to_index: &HashMap<String, usize>, edges: &mut Vec<(usize, usize)>, index: usize) {
-    let query = &{
-        let mut query = (&*view.query).clone();
+fn add_view_edges(view: &View, relations: &[Relation], name_to_index: &HashMap<String, usize>, edges: &mut Vec<(usize, usize)>, index: usize) {
+    info!("ADD VIEW - {}", *view.query);
 
-        lazy_static!{
-            static ref INNER_JOIN : Regex = Regex::new("[Ii][Nn][Nn][Ee][r] [Jj][Oo][Ii][Nn]").unwrap();
-        };
-
-        if INNER_JOIN.is_match(&query) {
-            query = INNER_JOIN.replace_all(&query, ", ").to_string();
-        }
-        
-        lazy_static!{
-            static ref OUTER_JOIN : Regex = Regex::new("[Oo][Uu][Tt][Ee][Rr] [Jj][Oo][Ii][Nn]").unwrap();
-        };
-
-        if OUTER_JOIN.is_match(&query) {
-            query = OUTER_JOIN.replace_all(&query, ", ").to_string();
-        }
-        
-        lazy_static!{
-            static ref JOIN : Regex = Regex::new("[Jj][Oo][Ii][Nn]").unwrap();
-        };
-
-        if JOIN.is_match(&query) {
-            query = JOIN.replace_all(&query, ", ").to_string();
-        }
-
-        lazy_static!{
-            static ref QUOTATION_MARK : Regex = Regex::new("`").unwrap();
-        };
-
-        if QUOTATION_MARK.is_match(&query) {
-            query = QUOTATION_MARK.replace_all(&query, "").to_string();
-        }
-        query
-    };
-    
-    info!("ADD VIEW - {}", query);
+    view.get_dependencies()
+        .iter()
+        .filter_map(|dependency| name_to_index.get(dependency))
+        .filter(|val| **val != index)
+        .for_each(|val| {
+            edges.push(
+                (
+                    *val,
+                    index
+                )
+            );
+        });
 
-    let dependencies = {
-        lazy_static!{
-            static ref DEPENDENCIES_REGEX : Regex = Regex::new("[Ff][Rr][Oo][Mm](?:[\\n\\t]+| )([a-zA-Z][a-zA-Z0-9_]+(?:, ?[a-zA-Z][a-zA-Z0-9_]+)*)").unwrap();
-        };
-        
-        if !DEPENDENCIES_REGEX.is_match(query) {
-            return ;
-        }
+    //unlike stored programs, views can only be created once the functions they call exist
+    view.get_function_calls()
+        .iter()
+        .filter_map(|function| name_to_index.get(function))
+        .filter(|val| matches!(&relations[**val], Relation::Routine(routine) if routine.routine_type == RoutineType::Function))
+        .for_each(|val| {
+            if !edges.contains(&(*val, index)) {
+                edges.push(
+                    (
+                        *val,
+                        index
+                    )
+                );
+            }
+        });
+}
 
-        DEPENDENCIES_REGEX.captures(query).unwrap()
+fn add_body_edges(body: &str, relations: &[Relation], name_to_index: &HashMap<String, usize>, edges: &mut Vec<(usize, usize)>, index: usize) {
+    lazy_static!{
+        static ref IDENTIFIER_REGEX : Regex = Regex::new("[a-zA-Z_][a-zA-Z0-9_]*").unwrap();
     };
 
-    dependencies.iter()
-        .enumerate()
-        .filter_map(|(i, c)| {
-            if i % 2 == 1 {
-                return Some(c)
+    info!("ADD BODY - {}", relations[index].name());
+
+    //stored programs only depend on tables & views. Calls to routines are resolved at execution time
+    IDENTIFIER_REGEX.find_iter(body)
+        .filter_map(|identifier| name_to_index.get(identifier.as_str()))
+        .filter(|val| matches!(relations[**val], Relation::Table(_) | Relation::View(_)))
+        .for_each(|val| {
+            if !edges.contains(&(*val, index)) {
+                edges.push(
+                    (
+                        *val,
+                        index
+                    )
+                );
             }
-            return None
-        })
-        .filter(|capture| match capture {
-            Some(_) => true,
-            None => false,
-        })
-        .map(|capture| capture.unwrap().as_str())
-        .for_each(|capture| {
-            
-            lazy_static!{
-                static ref DEPENDENCIES_REGEX : Regex = Regex::new("([a-zA-Z0-9_]+)[^,]").unwrap();
-            };
-
-            DEPENDENCIES_REGEX.captures_iter(capture)
-                .for_each(|capture| {
-                    let capture = capture.get(0).unwrap().as_str();
-
-                    if let Some(val) = name_to_index.get(capture) {
-                        edges.push(
-                            (
-                                *val,
-                                index
-                            )
-                        );
-                    }
-                });
         });
 }
 
@@ -212,12 +186,16 @@ fn add_table_edges(table: &Table, edges: &mut Vec<(usize, usize)>, index: usize,
         foreign_keys.iter()
             .for_each(
                 |(table_name, _attribute_name)| {
-                    edges.push(
-                        (
-                            *name_to_index.get(table_name).unwrap(),
-                            index
-                        )
-                    );
+                    //tables outside of the relations (e.g. on another database) can't be ordered
+                    match name_to_index.get(table_name) {
+                        Some(val) => edges.push(
+                            (
+                                *val,
+                                index
+                            )
+                        ),
+                        None => warn!("{} references {}, which is not one of the relations", table.name, table_name),
+                    }
                 }
             );
     }
@@ -244,7 +222,8 @@ fn add_table_edges(table: &Table, edges: &mut Vec<(usize, usize)>, index: usize,
 ///                     constraint: HashSet::new()
 ///                 }
 ///             ],
-///             primary_key: Some(0),
+///             primary_key: vec![0],
+///             triggers: vec![],
 ///         }
 ///     ),
 ///     Relation::Table(
@@ -257,14 +236,16 @@ fn add_table_edges(table: &Table, edges: &mut Vec<(usize, usize)>, index: usize,
 ///                     constraint: HashSet::from([
 ///                             Constraint::ForeignKey{
 ///                                 table_name: String::from("table_1"),
-///                                 attribute_name: String::from("attr_1")
+///                                 attribute_name: String::from("attr_1"),
+///                                 constraint_name: None
 ///                             }
 ///                         ]
 ///                     )
 ///                 }
 ///                 foreign_relation!["table_1"]
 ///             ],
-///             primary_key: Some(0),
+///             primary_key: vec![0],
+///             triggers: vec![],
 ///         }
 ///     ),
 /// ];
@@ -285,9 +266,8 @@ pub fn get_generation_path(relations: &Vec<Relation>, dependency_tree: &Dependen
         if visited[i] {
             continue 'node_loop;
         }
-        //println!("explore:{i}");
 
-        if let Some(mut path) = add_dependency(relations, &dependency_tree, &mut visited, i) {
+        if let Some(mut path) = add_dependency(relations, dependency_tree, &mut visited, i) {
             order.append(&mut path);
         }
     };
@@ -312,7 +292,7 @@ fn add_dependency(relations: &Vec<Relation>, dependency_tree: &DependencyTree, v
 
     let node_index = dependency_tree.from_index(node);
 
-
+    //dependencies are added before the node. A visited dependency is either already added or in a cycle with the node
     dependency_tree.neighbors_directed(node_index, Incoming)
         .for_each(
             |pre_node| {
@@ -332,25 +312,112 @@ fn add_dependency(relations: &Vec<Relation>, dependency_tree: &DependencyTree, v
     
     order.push_back(node);
 
-    dependency_tree.neighbors_directed(node_index, Outgoing)
-        .for_each(
-            |post_node| {
-                let dependents = add_dependency(
-                    relations,
-                    dependency_tree,
-                    visited,
-                    *dependency_tree.node_weight(post_node).unwrap() as usize
-                );
-                if let Some(mut dependents) = dependents {
-                    order.append(
-                        &mut dependents
-                    );
-                }
-            }
-        );
-    
-    
-    return Some(order);
+    Some(order)
+}
+
+/// Returns the groups of relations that depend on each other, including tables referencing themselves.
+///
+/// Each group is sorted by index & the groups are sorted by their first index.
+///
+/// # Arguments
+///
+/// * `dependency_tree` - A dependency tree of relations.
+pub fn get_cycles(dependency_tree: &DependencyTree) -> Vec<Vec<usize>> {
+    let mut cycles: Vec<Vec<usize>> = tarjan_scc(dependency_tree)
+        .into_iter()
+        .filter(|component| component.len() > 1 || dependency_tree.contains_edge(component[0], component[0]))
+        .map(|component| {
+            let mut cycle: Vec<usize> = component.iter()
+                .map(|node| dependency_tree[*node] as usize)
+                .collect();
+
+            cycle.sort();
+
+            cycle
+        })
+        .collect();
+
+    cycles.sort();
+
+    cycles
+}
+
+/// A foreign key that can't be declared when its table is created
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeferredForeignKey{
+    /// The index of the table declaring the foreign key.
+    pub table: usize,
+    /// The name of the attribute the foreign key is declared on.
+    pub attribute: String,
+    /// The foreign key constraint of the attribute.
+    pub foreign_key: Constraint,
+}
+
+/// Returns the foreign keys referencing a table of the same cycle, including foreign keys of a table referencing itself.
+///
+/// Tables of a cycle can't all be created with their foreign keys, & their rows can't be inserted in an order satisfying them.
+/// So the tables are created without the deferred foreign keys, which are added with `ALTER TABLE ... ADD CONSTRAINT` once the rows are inserted.
+///
+/// # Arguments
+///
+/// * `relations` - A reference to a vector of `Relation`s.
+/// * `dependency_tree` - A dependency tree of `relations` parameter.
+pub fn get_deferred_foreign_keys(relations: &[Relation], dependency_tree: &DependencyTree) -> Vec<DeferredForeignKey> {
+    get_cycles(dependency_tree)
+        .iter()
+        .flat_map(|cycle| {
+            let names: Vec<String> = cycle.iter()
+                .map(|index| relations[*index].name())
+                .collect();
+
+            cycle.iter()
+                .filter_map(|index| match &relations[*index] {
+                    Relation::Table(table) => Some((*index, table)),
+                    _ => None,
+                })
+                .flat_map(|(index, table)| {
+                    table.attributes.iter()
+                        .flat_map(|attribute| attribute.constraint.iter().map(move |constraint| (attribute, constraint)))
+                        .filter(|(_, constraint)| matches!(constraint, Constraint::ForeignKey { table_name, .. } if names.contains(table_name)))
+                        .map(|(attribute, constraint)| DeferredForeignKey {
+                            table: index,
+                            attribute: attribute.name.clone(),
+                            foreign_key: constraint.clone(),
+                        })
+                        .collect::<Vec<DeferredForeignKey>>()
+                })
+                .collect::<Vec<DeferredForeignKey>>()
+        })
+        .collect()
+}
+
+/// Returns the indexes of the tables & every table they reference, directly or through other tables, sorted by index.
+///
+/// # Arguments
+///
+/// * `relations` - A reference to a vector of `Relation`s.
+/// * `dependency_tree` - A dependency tree of `relations` parameter.
+/// * `tables` - The indexes of the tables.
+pub fn get_referenced_tables(relations: &[Relation], dependency_tree: &DependencyTree, tables: &[usize]) -> Vec<usize> {
+    let mut included: Vec<bool> = vec![false; relations.len()];
+    let mut stack: Vec<usize> = tables.to_vec();
+
+    while let Some(index) = stack.pop() {
+        if included[index] {
+            continue;
+        }
+        included[index] = true;
+
+        //only foreign keys are followed, as tables don't depend on any other relation
+        dependency_tree.neighbors_directed(dependency_tree.from_index(index), Incoming)
+            .map(|dependency| dependency_tree[dependency] as usize)
+            .filter(|dependency| matches!(relations[*dependency], Relation::Table(_)))
+            .for_each(|dependency| stack.push(dependency));
+    }
+
+    (0..relations.len())
+        .filter(|index| included[*index])
+        .collect()
 }
 
 #[cfg(test)]
@@ -365,12 +432,13 @@ mod tests{
             Relation,
             table::{Table, Attribute, AttributeType, Constraint},
             paths::{get_dependency_tree, DependencyTree},
-            view::View
+            view::View,
+            routine::{Routine, RoutineType, Characteristics, DataAccess, SqlSecurity}
         },
         sql::SQL
     };
 
-    use super::get_generation_path;
+    use super::{get_generation_path, get_cycles, get_deferred_foreign_keys, get_referenced_tables};
 
     //assert_eq_graph checks if two graphs are the same
     #[allow(unused_macros)]
@@ -436,7 +504,9 @@ mod tests{
                     [
                         Constraint::ForeignKey{
                             table_name: String::from($table_name),
-                            attribute_name: String::from("attr_1")
+                            attribute_name: String::from("attr_1"),
+                            constraint_name: None,
+                            other_columns: Vec::new()
                         }
                     ]
                 )
@@ -453,7 +523,8 @@ mod tests{
                     attributes: vec![
                         foreign_relation![]
                     ],
-                    primary_key: Some(0),
+                    primary_key: vec![0],
+                    triggers: vec![],
                 }
             ),
             Relation::Table(
@@ -462,7 +533,8 @@ mod tests{
                     attributes: vec![
                         foreign_relation!["table_1"]
                     ],
-                    primary_key: None,
+                    primary_key: Vec::new(),
+                    triggers: vec![],
                 }
             ),
         ];
@@ -487,7 +559,8 @@ mod tests{
                     attributes: vec![
                         foreign_relation![]
                     ],
-                    primary_key: Some(0),
+                    primary_key: vec![0],
+                    triggers: vec![],
                 }
             ),
             Relation::Table(
@@ -496,7 +569,8 @@ mod tests{
                     attributes: vec![
                         foreign_relation![]
                     ],
-                    primary_key: None,
+                    primary_key: Vec::new(),
+                    triggers: vec![],
                 }
             ),
         ];
@@ -519,7 +593,8 @@ mod tests{
                     attributes: vec![
                         foreign_relation![]
                     ],
-                    primary_key: Some(0),
+                    primary_key: vec![0],
+                    triggers: vec![],
                 }
             ),
             Relation::Table(
@@ -528,7 +603,8 @@ mod tests{
                     attributes: vec![
                         foreign_relation![]
                     ],
-                    primary_key: Some(1),
+                    primary_key: vec![1],
+                    triggers: vec![],
                 }
             ),
             Relation::Table(
@@ -538,7 +614,8 @@ mod tests{
                         foreign_relation!["table_1"],
                         foreign_relation!["table_2"]
                     ],
-                    primary_key: None,
+                    primary_key: Vec::new(),
+                    triggers: vec![],
                 }
             ),
         ];
@@ -564,14 +641,12 @@ mod tests{
                     attributes: vec![
                         foreign_relation![]
                     ],
-                    primary_key: Some(0),
+                    primary_key: vec![0],
+                    triggers: vec![],
                 }
             ),
             Relation::View(
-                View{
-                    name: String::from("view_1"),
-                    query: SQL::new("SELECT * FROM table_1").unwrap().qdl().unwrap().clone()
-                }
+                View::new("view_1", SQL::new("SELECT * FROM table_1").unwrap().qdl().unwrap().clone())
             )
         ];
 
@@ -595,7 +670,8 @@ mod tests{
                     attributes: vec![
                         foreign_relation![]
                     ],
-                    primary_key: Some(0),
+                    primary_key: vec![0],
+                    triggers: vec![],
                 }
             ),
             Relation::Table(
@@ -604,14 +680,12 @@ mod tests{
                     attributes: vec![
                         foreign_relation![]
                     ],
-                    primary_key: None,
+                    primary_key: Vec::new(),
+                    triggers: vec![],
                 }
             ),
             Relation::View(
-                View{
-                    name: String::from("view_1"),
-                    query: SQL::new("SELECT * FROM table_1, table_2").unwrap().qdl().unwrap().clone()
-                }
+                View::new("view_1", SQL::new("SELECT * FROM table_1, table_2").unwrap().qdl().unwrap().clone())
             )
 
         ];
@@ -637,7 +711,8 @@ mod tests{
                     attributes: vec![
                         foreign_relation![]
                     ],
-                    primary_key: Some(0),
+                    primary_key: vec![0],
+                    triggers: vec![],
                 }
             ),
             Relation::Table(
@@ -646,7 +721,8 @@ mod tests{
                     attributes: vec![
                         foreign_relation![]
                     ],
-                    primary_key: Some(0),
+                    primary_key: vec![0],
+                    triggers: vec![],
                 }
             ),
             Relation::Table(
@@ -655,14 +731,12 @@ mod tests{
                     attributes: vec![
                         foreign_relation![]
                     ],
-                    primary_key: Some(0),
+                    primary_key: vec![0],
+                    triggers: vec![],
                 }
             ),
             Relation::View(
-                View{
-                    name: String::from("view_1"),
-                    query: SQL::new("SELECT * FROM table_1, table_2, table_3").unwrap().qdl().unwrap().clone()
-                }
+                View::new("view_1", SQL::new("SELECT * FROM table_1, table_2, table_3").unwrap().qdl().unwrap().clone())
             )
 
         ];
@@ -680,6 +754,69 @@ mod tests{
         assert_eq_graph!(actual, expected, relations);
     }
 
+    #[test]
+    fn dependency_test_7() {
+        let relations: Vec<Relation> = vec![
+            Relation::Table(
+                Table{
+                    name: String::from("table_1"),
+                    attributes: vec![
+                        foreign_relation![]
+                    ],
+                    primary_key: vec![0],
+                    triggers: vec![],
+                }
+            ),
+            Relation::View(
+                View::new("view_1", SQL::new("SELECT * FROM table_1").unwrap().qdl().unwrap().clone())
+            ),
+            Relation::Routine(
+                Routine{
+                    name: String::from("routine_1"),
+                    routine_type: RoutineType::Procedure,
+                    parameters: vec![],
+                    return_type: None,
+                    body: String::from("BEGIN SELECT * FROM `view_1`; DELETE FROM table_1; CALL routine_2(); END"),
+                    definer: String::from("root@localhost"),
+                    characteristics: Characteristics{
+                        deterministic: false,
+                        data_access: DataAccess::ModifiesSqlData,
+                        security: SqlSecurity::Definer,
+                        comment: String::new(),
+                    },
+                }
+            ),
+            Relation::Routine(
+                Routine{
+                    name: String::from("routine_2"),
+                    routine_type: RoutineType::Procedure,
+                    parameters: vec![],
+                    return_type: None,
+                    body: String::from("BEGIN CALL routine_1(); END"),
+                    definer: String::from("root@localhost"),
+                    characteristics: Characteristics{
+                        deterministic: false,
+                        data_access: DataAccess::ContainsSql,
+                        security: SqlSecurity::Definer,
+                        comment: String::new(),
+                    },
+                }
+            ),
+        ];
+
+        let actual = get_dependency_tree(&relations);
+
+        let mut expected: DependencyTree = Graph::new();
+        let v1 = expected.add_node(0);
+        let v2 = expected.add_node(1);
+        let v3 = expected.add_node(2);
+        expected.add_node(3);
+
+        expected.extend_with_edges(&[(v1, v2), (v2, v3), (v1, v3)]);
+
+        assert_eq_graph!(actual, expected, relations);
+    }
+
     //assert_path checks the validity of a path by brute force checking every relation. Making sure that all dependency relations are in front of a given relation that being checked
     #[allow(unused_macros)]
     macro_rules! assert_path {
@@ -737,7 +874,8 @@ mod tests{
                     attributes: vec![
                         foreign_relation![]
                     ],
-                    primary_key: Some(0),
+                    primary_key: vec![0],
+                    triggers: vec![],
                 }
             ),
         ];
@@ -758,7 +896,8 @@ mod tests{
                     attributes: vec![
                         foreign_relation![]
                     ],
-                    primary_key: Some(0),
+                    primary_key: vec![0],
+                    triggers: vec![],
                 }
             ),
             Relation::Table(
@@ -767,7 +906,8 @@ mod tests{
                     attributes: vec![
                         foreign_relation!["table_1"]
                     ],
-                    primary_key: Some(0),
+                    primary_key: vec![0],
+                    triggers: vec![],
                 }
             ),
         ];
@@ -788,7 +928,8 @@ mod tests{
                     attributes: vec![
                         foreign_relation!["table_2"]
                     ],
-                    primary_key: Some(0),
+                    primary_key: vec![0],
+                    triggers: vec![],
                 }
             ),
             Relation::Table(
@@ -797,7 +938,8 @@ mod tests{
                     attributes: vec![
                         foreign_relation![]
                     ],
-                    primary_key: Some(0),
+                    primary_key: vec![0],
+                    triggers: vec![],
                 }
             ),
         ];
@@ -818,7 +960,8 @@ mod tests{
                     attributes: vec![
                         foreign_relation![]
                     ],
-                    primary_key: Some(0),
+                    primary_key: vec![0],
+                    triggers: vec![],
                 }
             ),
             Relation::Table(
@@ -827,7 +970,8 @@ mod tests{
                     attributes: vec![
                         foreign_relation!["table_1"]
                     ],
-                    primary_key: Some(0),
+                    primary_key: vec![0],
+                    triggers: vec![],
                 }
             ),
             Relation::Table(
@@ -836,7 +980,8 @@ mod tests{
                     attributes: vec![
                         foreign_relation!["table_2"]
                     ],
-                    primary_key: Some(0),
+                    primary_key: vec![0],
+                    triggers: vec![],
                 }
             ),
         ];
@@ -857,7 +1002,8 @@ mod tests{
                     attributes: vec![
                         foreign_relation![]
                     ],
-                    primary_key: Some(0),
+                    primary_key: vec![0],
+                    triggers: vec![],
                 }
             ),
             Relation::Table(
@@ -866,7 +1012,8 @@ mod tests{
                     attributes: vec![
                         foreign_relation!["table_1"]
                     ],
-                    primary_key: Some(0),
+                    primary_key: vec![0],
+                    triggers: vec![],
                 }
             ),
             Relation::Table(
@@ -875,7 +1022,8 @@ mod tests{
                     attributes: vec![
                         foreign_relation![]
                     ],
-                    primary_key: Some(0),
+                    primary_key: vec![0],
+                    triggers: vec![],
                 }
             ),
         ];
@@ -896,7 +1044,8 @@ mod tests{
                     attributes: vec![
                         foreign_relation!["table_3"]
                     ],
-                    primary_key: Some(0),
+                    primary_key: vec![0],
+                    triggers: vec![],
                 }
             ),
             Relation::Table(
@@ -905,7 +1054,8 @@ mod tests{
                     attributes: vec![
                         foreign_relation![]
                     ],
-                    primary_key: Some(0),
+                    primary_key: vec![0],
+                    triggers: vec![],
                 }
             ),
             Relation::Table(
@@ -914,7 +1064,8 @@ mod tests{
                     attributes: vec![
                         foreign_relation!["table_2"]
                     ],
-                    primary_key: Some(0),
+                    primary_key: vec![0],
+                    triggers: vec![],
                 }
             ),
         ];
@@ -935,7 +1086,8 @@ mod tests{
                     attributes: vec![
                         foreign_relation![]
                     ],
-                    primary_key: Some(0),
+                    primary_key: vec![0],
+                    triggers: vec![],
                 }
             ),
             Relation::Table(
@@ -944,7 +1096,8 @@ mod tests{
                     attributes: vec![
                         foreign_relation!["table_1"]
                     ],
-                    primary_key: Some(0),
+                    primary_key: vec![0],
+                    triggers: vec![],
                 }
             ),//0,1,2
             Relation::Table(
@@ -953,7 +1106,8 @@ mod tests{
                     attributes: vec![
                         foreign_relation!["table_2"]
                     ],
-                    primary_key: Some(0),
+                    primary_key: vec![0],
+                    triggers: vec![],
                 }
             ),
             Relation::Table(
@@ -962,7 +1116,8 @@ mod tests{
                     attributes: vec![
                         foreign_relation!["table_2"]
                     ],
-                    primary_key: Some(0),
+                    primary_key: vec![0],
+                    triggers: vec![],
                 }
             ),
         ];
@@ -974,4 +1129,242 @@ mod tests{
         assert_path!(relations, dependency_tree, actual);
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn generation_path_test_8(){
+        //a dependency of the relation explored first is also a dependency of the relation's dependent
+        let relations: Vec<Relation> = vec![
+            Relation::Table(
+                Table{
+                    name: String::from("table_2"),
+                    attributes: vec![
+                        foreign_relation!["table_1"]
+                    ],
+                    primary_key: vec![0],
+                    triggers: vec![],
+                }
+            ),
+            Relation::Table(
+                Table{
+                    name: String::from("table_1"),
+                    attributes: vec![
+                        foreign_relation![]
+                    ],
+                    primary_key: vec![0],
+                    triggers: vec![],
+                }
+            ),
+            Relation::Table(
+                Table{
+                    name: String::from("table_3"),
+                    attributes: vec![
+                        foreign_relation!["table_1"],
+                        Attribute{
+                            name: String::from("attr_3"),
+                            data_type: AttributeType::Text,
+                            constraint: HashSet::from([
+                                Constraint::ForeignKey{
+                                    table_name: String::from("table_2"),
+                                    attribute_name: String::from("attr_1"),
+                                    constraint_name: None,
+                                    other_columns: Vec::new()
+                                }
+                            ])
+                        }
+                    ],
+                    primary_key: vec![0],
+                    triggers: vec![],
+                }
+            ),
+        ];
+
+        let dependency_tree = get_dependency_tree(&relations);
+
+        let actual = get_generation_path(&relations, &dependency_tree);
+
+        assert_path!(relations, dependency_tree, actual);
+    }
+
+    #[test]
+    fn generation_path_test_9(){
+        //views are created after the functions they call
+        let relations: Vec<Relation> = vec![
+            Relation::View(
+                View::new("view_1", SQL::new("SELECT attr_1, function_1(attr_1) AS attr_2, NOW() AS attr_3 FROM table_1").unwrap().qdl().unwrap().clone())
+            ),
+            Relation::Routine(
+                Routine{
+                    name: String::from("function_1"),
+                    routine_type: RoutineType::Function,
+                    parameters: vec![],
+                    return_type: Some(String::from("int(11)")),
+                    body: String::from("RETURN 1"),
+                    definer: String::from("root@localhost"),
+                    characteristics: Characteristics{
+                        deterministic: true,
+                        data_access: DataAccess::NoSql,
+                        security: SqlSecurity::Definer,
+                        comment: String::new(),
+                    },
+                }
+            ),
+            Relation::Table(
+                Table{
+                    name: String::from("table_1"),
+                    attributes: vec![
+                        foreign_relation![]
+                    ],
+                    primary_key: vec![0],
+                    triggers: vec![],
+                }
+            ),
+        ];
+
+        let dependency_tree = get_dependency_tree(&relations);
+
+        let actual = get_generation_path(&relations, &dependency_tree);
+
+        assert_path!(relations, dependency_tree, actual);
+
+        let position = |relation: usize| actual.iter().position(|index| *index == relation).unwrap();
+
+        assert!(position(1) < position(0));
+        assert!(position(2) < position(0));
+    }
+
+    #[test]
+    fn cycle_test_1(){
+        let relations: Vec<Relation> = vec![
+            Relation::Table(
+                Table{
+                    name: String::from("table_1"),
+                    attributes: vec![
+                        foreign_relation!["table_2"]
+                    ],
+                    primary_key: vec![0],
+                    triggers: vec![],
+                }
+            ),
+            Relation::Table(
+                Table{
+                    name: String::from("table_2"),
+                    attributes: vec![
+                        foreign_relation!["table_1"]
+                    ],
+                    primary_key: vec![0],
+                    triggers: vec![],
+                }
+            ),
+            Relation::Table(
+                Table{
+                    name: String::from("table_3"),
+                    attributes: vec![
+                        foreign_relation!["table_3"]
+                    ],
+                    primary_key: vec![0],
+                    triggers: vec![],
+                }
+            ),
+            Relation::Table(
+                Table{
+                    name: String::from("table_4"),
+                    attributes: vec![
+                        foreign_relation!["table_1"]
+                    ],
+                    primary_key: vec![0],
+                    triggers: vec![],
+                }
+            ),
+            Relation::Table(
+                Table{
+                    name: String::from("table_5"),
+                    attributes: vec![
+                        foreign_relation!["table_on_another_database"]
+                    ],
+                    primary_key: vec![0],
+                    triggers: vec![],
+                }
+            ),
+        ];
+
+        let dependency_tree = get_dependency_tree(&relations);
+
+        assert_eq!(get_cycles(&dependency_tree), vec![vec![0, 1], vec![2]]);
+
+        let actual = get_generation_path(&relations, &dependency_tree);
+
+        let mut sorted = actual.clone();
+        sorted.sort();
+        assert_eq!(sorted, vec![0, 1, 2, 3, 4]);
+
+        //table_4 is created after the cycle it depends on
+        let position = |index: usize| actual.iter().position(|val| *val == index).unwrap();
+        assert!(position(0) < position(3));
+        assert!(position(1) < position(3));
+
+        let deferred: Vec<(usize, String)> = get_deferred_foreign_keys(&relations, &dependency_tree)
+            .into_iter()
+            .map(|foreign_key| (foreign_key.table, foreign_key.attribute))
+            .collect();
+
+        assert_eq!(
+            deferred,
+            vec![
+                (0, String::from("attr_2")),
+                (1, String::from("attr_2")),
+                (2, String::from("attr_2")),
+            ]
+        );
+    }
+
+    #[test]
+    fn referenced_tables_test_1(){
+        let relations: Vec<Relation> = vec![
+            Relation::Table(
+                Table{
+                    name: String::from("table_1"),
+                    attributes: vec![
+                        foreign_relation!["table_2"]
+                    ],
+                    primary_key: vec![0],
+                    triggers: vec![],
+                }
+            ),
+            Relation::Table(
+                Table{
+                    name: String::from("table_2"),
+                    attributes: vec![
+                        foreign_relation!["table_1"]
+                    ],
+                    primary_key: vec![0],
+                    triggers: vec![],
+                }
+            ),
+            Relation::Table(
+                Table{
+                    name: String::from("table_3"),
+                    attributes: vec![
+                        foreign_relation![]
+                    ],
+                    primary_key: vec![0],
+                    triggers: vec![],
+                }
+            ),
+            Relation::Table(
+                Table{
+                    name: String::from("table_4"),
+                    attributes: vec![
+                        foreign_relation!["table_1"]
+                    ],
+                    primary_key: vec![0],
+                    triggers: vec![],
+                }
+            ),
+        ];
+
+        let dependency_tree = get_dependency_tree(&relations);
+
+        assert_eq!(get_referenced_tables(&relations, &dependency_tree, &[3]), vec![0, 1, 3]);
+        assert_eq!(get_referenced_tables(&relations, &dependency_tree, &[2]), vec![2]);
+        assert_eq!(get_referenced_tables(&relations, &dependency_tree, &[]), Vec::<usize>::new());
+    }
+}
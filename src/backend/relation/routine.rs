@@ -0,0 +1,310 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backend::sql::{QDL, DDL};
+
+use super::{RelationMethods, definer_sql};
+
+/// A struct representing a stored procedure or a stored function
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Routine{
+    /// The name of the routine.
+    pub name: String,
+    /// Whether the routine is a procedure or a function.
+    pub routine_type: RoutineType,
+    /// The parameters of the routine, in declaration order.
+    pub parameters: Vec<Parameter>,
+    /// The return type of the routine. Only functions have a return type.
+    pub return_type: Option<String>,
+    /// The body of the routine (e.g. `BEGIN ... END`).
+    pub body: String,
+    /// The account that defined the routine (e.g. `root@localhost`).
+    pub definer: String,
+    /// The characteristics of the routine.
+    pub characteristics: Characteristics,
+}
+
+impl Routine {
+    /// Returns the parameter list & return type of the routine (e.g. `(IN id int) RETURNS int`).
+    pub fn signature(&self) -> String {
+        let parameters: Vec<String> = self.parameters
+            .iter()
+            .map(|parameter| parameter.to_string())
+            .collect();
+
+        match (&self.routine_type, &self.return_type) {
+            (RoutineType::Function, Some(return_type)) => format!("({}) RETURNS {}", parameters.join(", "), return_type),
+            _ => format!("({})", parameters.join(", ")),
+        }
+    }
+}
+
+impl RelationMethods for Routine {
+    /// Returns a `QDL` selecting the routine's definition from `information_schema.ROUTINES`.
+    fn select(&self) -> QDL {
+        QDL(format!("SELECT * FROM information_schema.ROUTINES WHERE ROUTINE_SCHEMA = DATABASE() AND ROUTINE_NAME = '{}'", self.name))
+    }
+    /// Returns a `DDL` representing a `DROP` statement for the routine.
+    fn drop(&self) -> DDL {
+        DDL(format!("DROP {} {}", self.routine_type, self.name))
+    }
+    /// Returns a `DDL` representing a `CREATE` statement for the routine.
+    fn create(&self) -> DDL {
+        DDL(
+            format!(
+                "CREATE DEFINER={} {} {}{} {} {}",
+                definer_sql(&self.definer),
+                self.routine_type,
+                self.name,
+                self.signature(),
+                self.characteristics,
+                self.body
+            )
+        )
+    }
+}
+
+/// RoutineType defines the kinds of stored routines
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum RoutineType{
+    Procedure,
+    Function,
+}
+
+impl RoutineType {
+    /// Returns the `RoutineType` corresponding to `information_schema.ROUTINES.ROUTINE_TYPE`.
+    pub fn from(raw_str: &str) -> Option<RoutineType> {
+        match raw_str.to_ascii_uppercase().as_str() {
+            "PROCEDURE" => Some(RoutineType::Procedure),
+            "FUNCTION" => Some(RoutineType::Function),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for RoutineType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RoutineType::Procedure => write!(f, "PROCEDURE"),
+            RoutineType::Function => write!(f, "FUNCTION"),
+        }
+    }
+}
+
+/// A parameter of a stored routine
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Parameter{
+    /// The name of the parameter.
+    pub name: String,
+    /// The direction of the parameter. Function parameters have no mode.
+    pub mode: Option<ParameterMode>,
+    /// The data type of the parameter (e.g. `varchar(255)`).
+    pub data_type: String,
+}
+
+impl fmt::Display for Parameter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.mode {
+            Some(mode) => write!(f, "{} {} {}", mode, self.name, self.data_type),
+            None => write!(f, "{} {}", self.name, self.data_type),
+        }
+    }
+}
+
+/// ParameterMode defines the direction of a procedure parameter
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum ParameterMode{
+    In,
+    Out,
+    InOut,
+}
+
+impl ParameterMode {
+    /// Returns the `ParameterMode` corresponding to `information_schema.PARAMETERS.PARAMETER_MODE`.
+    pub fn from(raw_str: &str) -> Option<ParameterMode> {
+        match raw_str.to_ascii_uppercase().as_str() {
+            "IN" => Some(ParameterMode::In),
+            "OUT" => Some(ParameterMode::Out),
+            "INOUT" => Some(ParameterMode::InOut),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ParameterMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParameterMode::In => write!(f, "IN"),
+            ParameterMode::Out => write!(f, "OUT"),
+            ParameterMode::InOut => write!(f, "INOUT"),
+        }
+    }
+}
+
+/// Characteristics defines the optional clauses of a stored routine
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Characteristics{
+    /// Whether the routine always produces the same result for the same input.
+    pub deterministic: bool,
+    /// The kind of data access the routine performs.
+    pub data_access: DataAccess,
+    /// The security context the routine is executed with.
+    pub security: SqlSecurity,
+    /// The comment of the routine.
+    pub comment: String,
+}
+
+impl fmt::Display for Characteristics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let deterministic = match self.deterministic {
+            true => "DETERMINISTIC",
+            false => "NOT DETERMINISTIC",
+        };
+
+        match self.comment.len() {
+            0 => write!(f, "{} {} SQL SECURITY {}", deterministic, self.data_access, self.security),
+            _ => write!(
+                f,
+                "{} {} SQL SECURITY {} COMMENT '{}'",
+                deterministic,
+                self.data_access,
+                self.security,
+                self.comment.replace('\\', "\\\\").replace('\'', "''")
+            ),
+        }
+    }
+}
+
+/// DataAccess defines the nature of the data used by a stored routine
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum DataAccess{
+    ContainsSql,
+    NoSql,
+    ReadsSqlData,
+    ModifiesSqlData,
+}
+
+impl DataAccess {
+    /// Returns the `DataAccess` corresponding to `information_schema.ROUTINES.SQL_DATA_ACCESS`.
+    pub fn from(raw_str: &str) -> Option<DataAccess> {
+        match raw_str.to_ascii_uppercase().as_str() {
+            "CONTAINS SQL" => Some(DataAccess::ContainsSql),
+            "NO SQL" => Some(DataAccess::NoSql),
+            "READS SQL DATA" => Some(DataAccess::ReadsSqlData),
+            "MODIFIES SQL DATA" => Some(DataAccess::ModifiesSqlData),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for DataAccess {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataAccess::ContainsSql => write!(f, "CONTAINS SQL"),
+            DataAccess::NoSql => write!(f, "NO SQL"),
+            DataAccess::ReadsSqlData => write!(f, "READS SQL DATA"),
+            DataAccess::ModifiesSqlData => write!(f, "MODIFIES SQL DATA"),
+        }
+    }
+}
+
+/// SqlSecurity defines whose privileges are used when a stored object is executed
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum SqlSecurity{
+    Definer,
+    Invoker,
+}
+
+impl SqlSecurity {
+    /// Returns the `SqlSecurity` corresponding to an `information_schema` `SECURITY_TYPE` column.
+    pub fn from(raw_str: &str) -> Option<SqlSecurity> {
+        match raw_str.to_ascii_uppercase().as_str() {
+            "DEFINER" => Some(SqlSecurity::Definer),
+            "INVOKER" => Some(SqlSecurity::Invoker),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SqlSecurity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SqlSecurity::Definer => write!(f, "DEFINER"),
+            SqlSecurity::Invoker => write!(f, "INVOKER"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(unused_imports)]
+    use crate::backend::relation::RelationMethods;
+
+    use super::{Routine, RoutineType, Parameter, ParameterMode, Characteristics, DataAccess, SqlSecurity};
+
+    #[test]
+    fn create_test_1() {
+        let routine = Routine{
+            name: String::from("add_patient"),
+            routine_type: RoutineType::Procedure,
+            parameters: vec![
+                Parameter{
+                    name: String::from("first_name"),
+                    mode: Some(ParameterMode::In),
+                    data_type: String::from("varchar(255)"),
+                },
+                Parameter{
+                    name: String::from("patient_id"),
+                    mode: Some(ParameterMode::Out),
+                    data_type: String::from("int"),
+                },
+            ],
+            return_type: None,
+            body: String::from("BEGIN INSERT INTO patients(first_name) VALUES (first_name); SET patient_id = LAST_INSERT_ID(); END"),
+            definer: String::from("root@localhost"),
+            characteristics: Characteristics{
+                deterministic: false,
+                data_access: DataAccess::ModifiesSqlData,
+                security: SqlSecurity::Definer,
+                comment: String::new(),
+            },
+        };
+
+        assert_eq!(
+            *routine.create(),
+            "CREATE DEFINER=`root`@`localhost` PROCEDURE add_patient(IN first_name varchar(255), OUT patient_id int) NOT DETERMINISTIC MODIFIES SQL DATA SQL SECURITY DEFINER BEGIN INSERT INTO patients(first_name) VALUES (first_name); SET patient_id = LAST_INSERT_ID(); END"
+        );
+        assert_eq!(*routine.drop(), "DROP PROCEDURE add_patient");
+    }
+
+    #[test]
+    fn create_test_2() {
+        let routine = Routine{
+            name: String::from("patient_count"),
+            routine_type: RoutineType::Function,
+            parameters: vec![
+                Parameter{
+                    name: String::from("gender"),
+                    mode: None,
+                    data_type: String::from("char(1)"),
+                },
+            ],
+            return_type: Some(String::from("int")),
+            body: String::from("RETURN (SELECT COUNT(*) FROM patients WHERE patients.gender = gender)"),
+            definer: String::from("admin@%"),
+            characteristics: Characteristics{
+                deterministic: true,
+                data_access: DataAccess::ReadsSqlData,
+                security: SqlSecurity::Invoker,
+                comment: String::from("patient's count"),
+            },
+        };
+
+        assert_eq!(
+            *routine.create(),
+            "CREATE DEFINER=`admin`@`%` FUNCTION patient_count(gender char(1)) RETURNS int DETERMINISTIC READS SQL DATA SQL SECURITY INVOKER COMMENT 'patient''s count' RETURN (SELECT COUNT(*) FROM patients WHERE patients.gender = gender)"
+        );
+        assert_eq!(*routine.drop(), "DROP FUNCTION patient_count");
+    }
+}
@@ -0,0 +1,220 @@
+use std::{fmt, fs, path::Path};
+
+use ron::ser::PrettyConfig;
+
+use super::Relation;
+
+/// Enum representing the errors that can occur when exporting or importing a schema file
+#[derive(Debug)]
+pub enum Error {
+    /// Indicates that there was an error while reading or writing the file
+    File(std::io::Error),
+    /// Indicates that the file is not a json, ron or sql file
+    UnsupportedFormat(String),
+    /// Indicates that there was an error while serializing or deserializing json
+    Json(serde_json::Error),
+    /// Indicates that there was an error while serializing ron
+    Ron(ron::Error),
+    /// Indicates that there was an error while deserializing ron
+    RonDeserialization(ron::error::SpannedError),
+}
+
+/// Format defines the serialization formats a schema can be exported to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format{
+    Json,
+    Ron,
+}
+
+impl Format {
+    /// Returns the `Format` corresponding to its name or file extension.
+    pub fn from(raw_str: &str) -> Option<Format> {
+        match raw_str.to_ascii_lowercase().as_str() {
+            "json" => Some(Format::Json),
+            "ron" => Some(Format::Ron),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Format::Json => write!(f, "json"),
+            Format::Ron => write!(f, "ron"),
+        }
+    }
+}
+
+/// Writes the relations to a file in the given format.
+///
+/// Constraints are written in declaration order & relations in the given order, so exporting the same schema twice produces the same file.
+///
+/// # Arguments
+///
+/// * `relations` - The relations to export
+/// * `file_path` - The path of the file to write
+/// * `format` - The serialization format of the file
+pub fn export(relations: &[Relation], file_path: &str, format: Format) -> Result<(), Error> {
+    let content = match format {
+        Format::Json => serde_json::to_string_pretty(relations).map_err(Error::Json)?,
+        Format::Ron => ron::ser::to_string_pretty(relations, PrettyConfig::default()).map_err(Error::Ron)?,
+    };
+
+    fs::write(file_path, content).map_err(Error::File)
+}
+
+/// Returns the relations defined by a schema file.
+///
+/// `.json` & `.ron` files must contain a serialized `Vec<Relation>`. For `.sql` files, the tables & views defined by `CREATE` statements are returned.
+pub fn import(file_path: &str) -> Result<Vec<Relation>, Error> {
+    let extension = Path::new(file_path)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+
+    if extension == "sql" {
+        return Relation::from_file(file_path).map_err(Error::File);
+    }
+
+    let format = Format::from(&extension).ok_or_else(|| Error::UnsupportedFormat(extension.clone()))?;
+    let content = fs::read_to_string(file_path).map_err(Error::File)?;
+
+    match format {
+        Format::Json => serde_json::from_str(&content).map_err(Error::Json),
+        Format::Ron => ron::from_str(&content).map_err(Error::RonDeserialization),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(unused_imports)]
+    use std::collections::HashSet;
+
+    use crate::{
+        backend::{relation::{Relation, RelationMethods, diff::diff, table::{Table, Attribute, AttributeType, Constraint}, view::View}, sql::QDL},
+        test_tools::file_env::FileEnv
+    };
+
+    use super::{export, import, Format};
+
+    #[allow(dead_code)]
+    fn relations() -> Vec<Relation> {
+        vec![
+            Relation::Table(
+                Table{
+                    name: String::from("table_2"),
+                    attributes: vec![
+                        Attribute{
+                            name: String::from("attr_1"),
+                            data_type: AttributeType::Int(11),
+                            constraint: HashSet::new()
+                        },
+                    ],
+                    primary_key: vec![0],
+                    triggers: vec![],
+                }
+            ),
+            Relation::Table(
+                Table{
+                    name: String::from("table_1"),
+                    attributes: vec![
+                        Attribute{
+                            name: String::from("attr_1"),
+                            data_type: AttributeType::Int(11),
+                            constraint: HashSet::from([Constraint::AutoIncrement, Constraint::NotNull, Constraint::Unique])
+                        },
+                        Attribute{
+                            name: String::from("attr_2"),
+                            data_type: AttributeType::Decimal(10, 2),
+                            constraint: HashSet::from([
+                                Constraint::ForeignKey{
+                                    table_name: String::from("table_2"),
+                                    attribute_name: String::from("attr_1"),
+                                    constraint_name: Some(String::from("table_1_ibfk_1")),
+                                    other_columns: Vec::new()
+                                }
+                            ])
+                        },
+                    ],
+                    primary_key: vec![0],
+                    triggers: vec![],
+                }
+            ),
+            Relation::View(View::new("view_1", QDL(String::from("SELECT * FROM table_1")))),
+        ]
+    }
+
+    #[test]
+    fn export_test_1() {
+        let file_path = "schema_file_export_test_1.json";
+        let _file = FileEnv::new(file_path, "");
+
+        export(&relations(), file_path, Format::Json).unwrap();
+
+        let content = std::fs::read_to_string(file_path).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&content).unwrap();
+
+        assert_eq!(
+            json[1]["Table"]["attributes"][0]["constraint"],
+            serde_json::json!(["NotNull", "Unique", "AutoIncrement"])
+        );
+        assert_eq!(json[2]["View"]["query"], "SELECT * FROM table_1");
+    }
+
+    #[test]
+    fn import_test_1() {
+        for format in [Format::Json, Format::Ron] {
+            let file_path = format!("schema_file_import_test_1.{}", format);
+            let _file = FileEnv::new(&file_path, "");
+
+            export(&relations(), &file_path, format).unwrap();
+            let actual = import(&file_path).unwrap();
+
+            assert_eq!(
+                actual.iter().map(|relation| relation.name()).collect::<Vec<String>>(),
+                vec!["table_2", "table_1", "view_1"]
+            );
+            assert!(diff(&relations(), &actual).is_empty());
+        }
+    }
+
+    #[test]
+    fn import_test_2() {
+        let file_path = "schema_file_import_test_2.yaml";
+        let _file = FileEnv::new(file_path, "");
+
+        assert!(import(file_path).is_err());
+    }
+
+    #[test]
+    fn import_test_3() {
+        //files exported before composite primary keys store a single optional index
+        let attributes = r#"[{"name":"attr_1","data_type":"Text","constraint":[]}]"#;
+
+        for (primary_key, expected) in [("0", vec![0]), ("null", Vec::new())] {
+            let file_path = "schema_file_import_test_3.json";
+            let _file = FileEnv::new(
+                file_path,
+                &format!(r#"[{{"Table":{{"name":"table_1","attributes":{},"primary_key":{},"triggers":[]}}}}]"#, attributes, primary_key)
+            );
+
+            match &import(file_path).unwrap()[0] {
+                Relation::Table(table) => assert_eq!(table.primary_key, expected),
+                _ => panic!("expected a table"),
+            }
+        }
+
+        let file_path = "schema_file_import_test_3.ron";
+        let _file = FileEnv::new(
+            file_path,
+            r#"[Table((name:"table_1",attributes:[(name:"attr_1",data_type:Text,constraint:[])],primary_key:Some(0),triggers:[]))]"#
+        );
+
+        match &import(file_path).unwrap()[0] {
+            Relation::Table(table) => assert_eq!(table.primary_key, vec![0]),
+            _ => panic!("expected a table"),
+        }
+    }
+}
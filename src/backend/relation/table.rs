@@ -4,20 +4,25 @@ use core::hash::Hash;
 use log::info;
 use mysql::{Row};
 use regex::Regex;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
 
 use crate::backend::{data_base::DataBase, sql::{SQL, QML, DDL, QDL}};
 
-use super::RelationMethods;
+use super::{RelationMethods, trigger::Trigger};
 
 /// A struct representing a table in a relational database
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Table{
     /// The name of the table.
     pub name: String,
     /// A vector of `Attribute`s representing the columns of the table.
     pub attributes: Vec<Attribute>,
-    /// The index of the primary key attribute in the `attributes` vector, if one exists.
-    pub primary_key: Option<usize>,
+    /// The indexes of the primary key attributes in the `attributes` vector, in key order. Empty if the table has no primary key.
+    #[serde(deserialize_with = "deserialize_primary_key")]
+    pub primary_key: Vec<usize>,
+    /// The triggers defined on the table, in the order they are executed.
+    pub triggers: Vec<Trigger>,
 }
 
 impl Table {
@@ -38,8 +43,7 @@ impl Table {
         match DataBase::from_env() {
             Ok(db) => {
                         
-                let mut primary_key : Option<usize> = None;
-                let mut col_num: usize = 0;
+                let mut primary_key : Vec<usize> = Vec::new();
                 
                 //println!("{}", table_name);
                 let attr : Vec<(Option<Attribute>, bool)> = db.execute(
@@ -64,23 +68,27 @@ impl Table {
                     |val| {
                         match val {
                             (None, _val) => None,
-                            (Some(val), true) => {
-                                primary_key = Some(col_num.clone());
-                                Some(val.clone())
-                            },
-                            (Some(val), false) => {
-                                col_num+=1;
-                                Some(val.clone())
-                            },
+                            (Some(val), is_primary_key) => Some((val.clone(), *is_primary_key)),
                         }
                     }
+                )
+                .enumerate()
+                .map(
+                    |(col_num, (val, is_primary_key))| {
+                        if is_primary_key {
+                            primary_key.push(col_num);
+                        }
+
+                        val
+                    }
                 ).collect();
 
                 Some(
                     Table{
                         name: table_name.to_string(),
                         attributes: attr,
-                        primary_key: primary_key
+                        primary_key: primary_key,
+                        triggers: Vec::new(),
                     }
                 )
             },
@@ -90,6 +98,121 @@ impl Table {
         }
     }
 
+    /// Returns the `Table` defined by a `CREATE TABLE` statement, or `None` if the statement is not a `CREATE TABLE` statement.
+    ///
+    /// Statements generated by `Table::create` & `SHOW CREATE TABLE` are supported. Columns of unsupported types are kept as `AttributeType::Unknown`, while indexes & table options are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `ddl` - A `CREATE TABLE` statement
+    pub fn from_ddl(ddl: &DDL) -> Option<Table> {
+        lazy_static!{
+            static ref CREATE_TABLE : Regex = Regex::new(r"(?is)^CREATE\s+TABLE\s+(?:IF\s+NOT\s+EXISTS\s+)?`?([a-zA-Z0-9_]+)`?\s*\((.*)\)").unwrap();
+        };
+        lazy_static!{
+            static ref PRIMARY_KEY : Regex = Regex::new(r"(?i)^PRIMARY\s+KEY\s*\(([^)]*)\)").unwrap();
+        };
+        lazy_static!{
+            static ref FOREIGN_KEY : Regex = Regex::new(r"(?i)^(?:CONSTRAINT\s+([a-zA-Z0-9_]+)\s+)?FOREIGN\s+KEY\s*\(([^)]*)\)\s*REFERENCES\s+([a-zA-Z0-9_]+)\s*\(([^)]*)\)").unwrap();
+        };
+        lazy_static!{
+            static ref UNIQUE_KEY : Regex = Regex::new(r"(?i)^UNIQUE(?:\s+(?:KEY|INDEX))?(?:\s+[a-zA-Z0-9_]+)?\s*\(\s*([a-zA-Z0-9_]+)\s*\)$").unwrap();
+        };
+        lazy_static!{
+            static ref INDEX : Regex = Regex::new(r"(?i)^(?:KEY|INDEX|UNIQUE|FULLTEXT|SPATIAL|CONSTRAINT|CHECK)\b").unwrap();
+        };
+        lazy_static!{
+            static ref COLUMN : Regex = Regex::new(r"(?is)^([a-zA-Z0-9_]+)\s+([a-zA-Z]+(?:\s*\([^)]*\))?)(.*)$").unwrap();
+        };
+
+        let captures = CREATE_TABLE.captures(ddl)?;
+
+        let name = captures.get(1)?.as_str().to_string();
+        let definitions = split_definitions(&captures.get(2)?.as_str().replace('`', ""));
+
+        let mut attributes: Vec<Attribute> = Vec::new();
+        let mut primary_key: Vec<String> = Vec::new();
+
+        for definition in &definitions {
+            if let Some(captures) = PRIMARY_KEY.captures(definition) {
+                primary_key = captures[1].split(',')
+                    .map(|column| column.trim().to_string())
+                    .collect();
+            }
+            else if let Some(captures) = FOREIGN_KEY.captures(definition) {
+                let columns: Vec<&str> = captures[2].split(',').map(str::trim).collect();
+                let referenced: Vec<&str> = captures[4].split(',').map(str::trim).collect();
+
+                if columns.len() != referenced.len() {
+                    continue;
+                }
+
+                if let Some(attribute) = attributes.iter_mut().find(|attribute| attribute.name == columns[0]) {
+                    attribute.constraint.insert(
+                        Constraint::ForeignKey {
+                            table_name: captures[3].to_string(),
+                            attribute_name: referenced[0].to_string(),
+                            constraint_name: captures.get(1).map(|constraint_name| constraint_name.as_str().to_string()),
+                            other_columns: columns[1..].iter()
+                                .zip(&referenced[1..])
+                                .map(|(column, referenced)| (column.to_string(), referenced.to_string()))
+                                .collect()
+                        }
+                    );
+                }
+            }
+            else if let Some(captures) = UNIQUE_KEY.captures(definition) {
+                if let Some(attribute) = attributes.iter_mut().find(|attribute| attribute.name == captures[1]) {
+                    attribute.constraint.insert(Constraint::Unique);
+                }
+            }
+            else if INDEX.is_match(definition) {
+                continue;
+            }
+            else if let Some(captures) = COLUMN.captures(definition) {
+                let data_type = AttributeType::from(&captures[2].to_ascii_uppercase().replace(' ', ""))
+                    .unwrap_or_else(|| AttributeType::Unknown(captures[2].to_string()));
+
+                let options = captures[3].to_ascii_uppercase();
+                let mut constraint: HashSet<Constraint> = HashSet::new();
+
+                if options.contains("NOT NULL") {
+                    constraint.insert(Constraint::NotNull);
+                }
+                if options.contains("UNIQUE") {
+                    constraint.insert(Constraint::Unique);
+                }
+                if options.contains("AUTO_INCREMENT") {
+                    constraint.insert(Constraint::AutoIncrement);
+                }
+                if options.contains("PRIMARY KEY") {
+                    primary_key = vec![captures[1].to_string()];
+                }
+
+                attributes.push(
+                    Attribute {
+                        name: captures[1].to_string(),
+                        data_type,
+                        constraint
+                    }
+                );
+            }
+        }
+
+        let primary_key = primary_key.iter()
+            .filter_map(|primary_key| attributes.iter().position(|attribute| &attribute.name == primary_key))
+            .collect();
+
+        Some(
+            Table {
+                name,
+                attributes,
+                primary_key,
+                triggers: Vec::new(),
+            }
+        )
+    }
+
     /// Returns a vector of foreign key tuples for the table.
     ///
     /// The tuples contain the name of the table and the name of the attribute that the foreign key references.
@@ -113,7 +236,7 @@ impl Table {
             })
             .map(|a| {//turn a into foreign key
                 for constraint in &a.constraint{
-                    if let Constraint::ForeignKey { table_name, attribute_name  } = constraint {
+                    if let Constraint::ForeignKey { table_name, attribute_name, .. } = constraint {
                         return (table_name.clone(), attribute_name.clone())
                     }
                 }
@@ -128,6 +251,21 @@ impl Table {
         Some(foreign_key)
     }
 
+    /// Returns a copy of the table without the foreign keys of the given attributes.
+    ///
+    /// # Arguments
+    ///
+    /// * `attributes` - The names of the attributes whose foreign keys are removed
+    pub fn without_foreign_keys(&self, attributes: &[String]) -> Table {
+        let mut table = self.clone();
+
+        table.attributes.iter_mut()
+            .filter(|attribute| attributes.contains(&attribute.name))
+            .for_each(|attribute| attribute.constraint.retain(|constraint| !matches!(constraint, Constraint::ForeignKey { .. })));
+
+        table
+    }
+
     /// Returns a `QML` representing an `INSERT` statement for the table with the given values.
     ///
     /// # Arguments
@@ -167,7 +305,8 @@ impl Table {
     ///             constraint: HashSet::new()
     ///         },
     ///     ],
-    ///     primary_key: None,
+    ///     primary_key: Vec::new(),
+    ///     triggers: vec![],
     /// };
     /// 
     /// let mut values = HashMap::new();
@@ -213,7 +352,8 @@ impl Table {
     ///             constraint: HashSet::new()
     ///         },
     ///     ],
-    ///     primary_key: None,
+    ///     primary_key: Vec::new(),
+    ///     triggers: vec![],
     /// };
     /// 
     /// let mut values = HashMap::new();
@@ -257,7 +397,8 @@ impl Table {
     ///             constraint: HashSet::new()
     ///         },
     ///     ],
-    ///     primary_key: None,
+    ///     primary_key: Vec::new(),
+    ///     triggers: vec![],
     /// };
     /// 
     /// let mut values = HashMap::new();
@@ -316,6 +457,132 @@ impl Table {
         Some(QML(format!("INSERT INTO {}({}) VALUES ({})", &self.name, &columns[1..m1], &values[1..m2])))
     }
 
+    /// Returns the names of the primary key attributes, in key order.
+    pub fn primary_key_names(&self) -> Vec<&str> {
+        self.primary_key.iter()
+            .map(|index| self.attributes[*index].name.as_str())
+            .collect()
+    }
+
+    /// Returns a `SELECT` statement reading a page of rows ordered by the primary key, starting after the row with the primary key values `after`.
+    ///
+    /// Composite primary keys are compared as a tuple (e.g. `WHERE (a,b) > (1,2) ORDER BY a,b`), so rows sharing the first key column aren't skipped.
+    /// Returns `None` if the table has no primary key.
+    ///
+    /// # Arguments
+    ///
+    /// * `after` - The primary key values of the last row of the previous page as SQL literals, in key order, or `None` for the first page
+    /// * `limit` - The maximum number of rows of the page
+    pub fn select_page(&self, after: Option<&[String]>, limit: usize) -> Option<QDL> {
+        let primary_key = self.primary_key_names();
+
+        if primary_key.is_empty() {
+            return None;
+        }
+
+        let tuple = |values: &[&str]| match values {
+            [value] => value.to_string(),
+            values => format!("({})", values.join(",")),
+        };
+        let order = primary_key.join(",");
+
+        Some(
+            match after {
+                Some(after) => {
+                    let after: Vec<&str> = after.iter().map(String::as_str).collect();
+
+                    QDL(format!("SELECT * FROM {} WHERE {} > {} ORDER BY {} LIMIT {}", self.name, tuple(&primary_key), tuple(&after), order, limit))
+                },
+                None => QDL(format!("SELECT * FROM {} ORDER BY {} LIMIT {}", self.name, order, limit)),
+            }
+        )
+    }
+
+    /// Returns multi-row `INSERT` statements inserting the given rows in order.
+    ///
+    /// Rows are added to a statement until it would be longer than `max_size` bytes, so a statement is only longer than `max_size` if it inserts a single row.
+    /// The columns are the attributes with a value in any row, & rows without a value for one of them use the column's default value.
+    ///
+    /// # Arguments
+    ///
+    /// * `rows` - The values of each row, keyed by attribute name
+    /// * `max_size` - The maximum length of a statement in bytes (e.g. the server's `max_allowed_packet`)
+    pub fn insert_batches(&self, rows: &[HashMap<String, String>], max_size: usize) -> Vec<QML> {
+        let columns: Vec<&String> = self.attributes.iter()
+            .map(|attr| &attr.name)
+            .filter(|name| rows.iter().any(|row| row.contains_key(*name)))
+            .collect();
+
+        if columns.is_empty() {
+            return Vec::new();
+        }
+
+        let prefix = format!(
+            "INSERT INTO {}({}) VALUES ",
+            self.name,
+            columns.iter().map(|column| column.as_str()).collect::<Vec<&str>>().join(",")
+        );
+
+        let mut batches: Vec<QML> = Vec::new();
+        let mut batch = prefix.clone();
+
+        rows.iter()
+            .map(|row| {
+                let values: Vec<&str> = columns.iter()
+                    .map(|column| row.get(*column).map_or("DEFAULT", |value| value.as_str()))
+                    .collect();
+
+                format!("({})", values.join(","))
+            })
+            .for_each(|values| {
+                if batch.len() > prefix.len() && batch.len() + 1 + values.len() > max_size {
+                    batches.push(QML(std::mem::replace(&mut batch, prefix.clone())));
+                }
+
+                if batch.len() > prefix.len() {
+                    batch.push(',');
+                }
+                batch.push_str(&values);
+            });
+
+        if batch.len() > prefix.len() {
+            batches.push(QML(batch));
+        }
+
+        batches
+    }
+
+}
+
+/// Splits the definitions of a `CREATE TABLE` statement on the commas that are not nested in parentheses or strings.
+fn split_definitions(definitions: &str) -> Vec<String> {
+    let mut split: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut depth: usize = 0;
+    let mut quote: Option<char> = None;
+
+    for ch in definitions.chars() {
+        match (quote, ch) {
+            (Some(end), _) if ch == end => quote = None,
+            (Some(_), _) => {},
+            (None, '\'') | (None, '"') => quote = Some(ch),
+            (None, '(') => depth += 1,
+            (None, ')') => depth = depth.saturating_sub(1),
+            (None, ',') if depth == 0 => {
+                split.push(current.trim().to_string());
+                current.clear();
+                continue;
+            },
+            _ => {},
+        }
+        current.push(ch);
+    }
+
+    if !current.trim().is_empty() {
+        split.push(current.trim().to_string());
+    }
+
+    split
 }
 
 impl Display for Table {
@@ -329,13 +596,9 @@ impl Display for Table {
         
         let attr = attr.join(",");
 
-        match self.primary_key {
-            Some(index) => {
-                let primary_key = &self.attributes[index].name;
-
-                write!(f, "CREATE TABLE {} ({}, PRIMARY KEY({}))", self.name, attr, primary_key)
-            },
-            None => write!(f, "CREATE TABLE {} ({})", self.name, attr),
+        match self.primary_key.is_empty() {
+            false => write!(f, "CREATE TABLE {} ({}, PRIMARY KEY({}))", self.name, attr, self.primary_key_names().join(",")),
+            true => write!(f, "CREATE TABLE {} ({})", self.name, attr),
         }
     }
 }
@@ -353,13 +616,14 @@ impl RelationMethods for Table {
 }
 
 /// An attribute in a table of a relational database
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Attribute{
     /// The name of the attribute.
     pub name: String,
     /// The data type of the attribute.
     pub data_type: AttributeType,
     /// A vector of Constraints on the attribute.
+    #[serde(serialize_with = "serialize_constraints")]
     pub constraint: HashSet<Constraint>
 }
 
@@ -370,10 +634,7 @@ impl Attribute {
 
         info!("name:{}\tdata_type:{}", name, data_type);
 
-        let data_type = match AttributeType::from(&data_type.to_ascii_uppercase()) {
-            Some(val) => val,
-            None => return None,
-        };
+        let data_type = AttributeType::from_raw(&data_type);
 
         Some(
             Attribute {
@@ -416,7 +677,9 @@ impl Attribute {
 
                                 Constraint::ForeignKey{
                                     table_name: captures.get(1).unwrap().as_str().to_string(),
-                                    attribute_name: captures.get(2).unwrap().as_str().to_string()
+                                    attribute_name: captures.get(2).unwrap().as_str().to_string(),
+                                    constraint_name: None,
+                                    other_columns: Vec::new()
                                 }
                             }).unwrap();
 
@@ -445,8 +708,8 @@ impl fmt::Display for Attribute {
         let constraint_str: String = {
             let mut constraints_vec: Vec<String> = Vec::new();
             for c in &self.constraint {
-                if let Constraint::ForeignKey { .. } = c {
-                    foreign_key = Some(format!("FOREIGN KEY({}) REFERENCES {}", self.name, c.to_string()));
+                if let Some(clause) = c.foreign_key_clause(&self.name) {
+                    foreign_key = Some(clause);
                     continue;
                 }
 
@@ -468,7 +731,7 @@ impl fmt::Display for Attribute {
 }
 
 /// Constraint defines the restrictions of an attribute
-#[derive(Clone, Hash, Eq, Debug)]
+#[derive(Clone, Hash, Eq, Debug, Deserialize, Serialize)]
 pub enum Constraint{
     /// The attribute must not contain a null value.
     NotNull,
@@ -479,7 +742,12 @@ pub enum Constraint{
         /// The name of the table that the foreign key attribute references.
         table_name: String,
         /// The name of the attribute that the foreign key references.
-        attribute_name: String
+        attribute_name: String,
+        /// The name of the foreign key constraint, if known.
+        constraint_name: Option<String>,
+        /// The remaining (attribute, referenced attribute) pairs of a composite foreign key in key order, empty for a single column foreign key.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        other_columns: Vec<(String, String)>
     },
     /// The attribute is an auto-incrementing integer.
     AutoIncrement,
@@ -497,19 +765,78 @@ impl PartialEq for Constraint {
     }
 }
 
+impl Constraint {
+    /// Returns the `FOREIGN KEY` clause of a foreign key declared on `attribute`, or `None` if the constraint isn't a foreign key.
+    pub fn foreign_key_clause(&self, attribute: &str) -> Option<String> {
+        match self {
+            Constraint::ForeignKey { constraint_name, other_columns, .. } => {
+                let columns: Vec<&str> = std::iter::once(attribute)
+                    .chain(other_columns.iter().map(|(column, _)| column.as_str()))
+                    .collect();
+
+                Some(
+                    match constraint_name {
+                        Some(constraint_name) => format!("CONSTRAINT {} FOREIGN KEY({}) REFERENCES {}", constraint_name, columns.join(","), self),
+                        None => format!("FOREIGN KEY({}) REFERENCES {}", columns.join(","), self),
+                    }
+                )
+            },
+            _ => None,
+        }
+    }
+}
+
 impl fmt::Display for Constraint{
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Constraint::NotNull => write!(f, "Not Null"),
             Constraint::Unique => write!(f, "Unique"),
-            Constraint::ForeignKey{table_name: table,attribute_name: attr} => write!(f, "{}({})", table, attr),
+            Constraint::ForeignKey{table_name: table,attribute_name: attr, other_columns, ..} => {
+                let referenced: Vec<&str> = std::iter::once(attr.as_str())
+                    .chain(other_columns.iter().map(|(_, referenced)| referenced.as_str()))
+                    .collect();
+
+                write!(f, "{}({})", table, referenced.join(","))
+            },
             Constraint::AutoIncrement => write!(f, "Auto_increment"),
         }
     }
 }
 
+/// Deserializes the primary key of a table, accepting the single optional index written by older versions.
+fn deserialize_primary_key<'de, D>(deserializer: D) -> Result<Vec<usize>, D::Error> where D: serde::Deserializer<'de> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum PrimaryKey {
+        Columns(Vec<usize>),
+        Column(Option<usize>),
+    }
+
+    Ok(
+        match PrimaryKey::deserialize(deserializer)? {
+            PrimaryKey::Columns(columns) => columns,
+            PrimaryKey::Column(column) => column.into_iter().collect(),
+        }
+    )
+}
+
+/// Serializes the constraints of an attribute as a sequence in declaration order, so the output does not depend on the order of the `HashSet`.
+fn serialize_constraints<S>(constraints: &HashSet<Constraint>, serializer: S) -> Result<S::Ok, S::Error> where S: serde::Serializer {
+    let rank = |constraint: &Constraint| match constraint {
+        Constraint::NotNull => 0,
+        Constraint::Unique => 1,
+        Constraint::ForeignKey { .. } => 2,
+        Constraint::AutoIncrement => 3,
+    };
+
+    let mut constraints: Vec<&Constraint> = constraints.iter().collect();
+    constraints.sort_by_key(|constraint| rank(constraint));
+
+    serializer.collect_seq(constraints)
+}
+
 /// AttributeType defines every type of MySQL datatype
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum AttributeType{
     //string data types
     Char(u8),
@@ -544,7 +871,11 @@ pub enum AttributeType{
     DateTime,
     TimeStamp,
     Time,
-    Year
+    Year,
+
+    //other
+    /// A data type without a variant (e.g. `geometry` or `json`), as written by the database
+    Unknown(String)
 }
 
 macro_rules! regex_check {
@@ -594,7 +925,7 @@ macro_rules! regex_check {
 
 impl AttributeType {
     /// Returns the `AttributeType` variant corresponding to the given string.
-    fn from(raw_str: &str) -> Option<AttributeType> {
+    pub(crate) fn from(raw_str: &str) -> Option<AttributeType> {
         regex_check!(r"CHAR\((\d+)\)", raw_str, Char, u8);
         regex_check!(r"VARCHAR\((\d+)\)", raw_str, VarChar, u16);
         regex_check!(r"BINARY\((\d+)\)", raw_str, Binary, u8);
@@ -629,6 +960,12 @@ impl AttributeType {
 
         return None
     }
+
+    /// Returns the `AttributeType` variant corresponding to the given string, or `AttributeType::Unknown` holding the string if no variant matches.
+    pub(crate) fn from_raw(raw_str: &str) -> AttributeType {
+        AttributeType::from(&raw_str.to_ascii_uppercase())
+            .unwrap_or_else(|| AttributeType::Unknown(raw_str.to_string()))
+    }
 }
 
 impl fmt::Display for AttributeType{
@@ -668,6 +1005,9 @@ impl fmt::Display for AttributeType{
             AttributeType::TimeStamp => write!(f, "timeStamp"),
             AttributeType::Time => write!(f, "time"),
             AttributeType::Year => write!(f, "year"),
+
+            //other
+            AttributeType::Unknown(val) => write!(f, "{}", val),
         }
     }
 }
@@ -677,7 +1017,7 @@ mod tests {
     #![allow(unused_imports)]
     use std::collections::{HashSet, HashMap};
 
-    use crate::{backend::{sql::QML, relation::RelationMethods}, ui::menu::Tab};
+    use crate::{backend::{sql::{QML, DDL, QDL}, relation::RelationMethods}, ui::menu::Tab};
 
     use super::{Table, Attribute, AttributeType, Constraint};
 
@@ -698,7 +1038,8 @@ mod tests {
                     )
                 }
             ],
-            primary_key: Some(0),
+            primary_key: vec![0],
+            triggers: vec![],
         };
 
         assert_eq!(*table.create(), "CREATE TABLE table_1 (attr_1 text Unique Not Null, PRIMARY KEY(attr_1))")
@@ -715,12 +1056,93 @@ mod tests {
                     constraint: HashSet::new()
                 }
             ],
-            primary_key: Some(0),
+            primary_key: vec![0],
+            triggers: vec![],
         };
 
         assert_eq!(*table.create(), "CREATE TABLE table_1 (attr_1 text, PRIMARY KEY(attr_1))")
     }
 
+    #[test]
+    fn from_ddl_test_1() {
+        let ddl = DDL(String::from("CREATE TABLE `table_2` (\n  `attr_1` int(11) NOT NULL AUTO_INCREMENT,\n  `attr_2` varchar(255) DEFAULT NULL,\n  `attr_3` int(11) DEFAULT NULL,\n  PRIMARY KEY (`attr_1`),\n  UNIQUE KEY `attr_2` (`attr_2`),\n  KEY `attr_3` (`attr_3`),\n  CONSTRAINT `table_2_ibfk_1` FOREIGN KEY (`attr_3`) REFERENCES `table_1` (`attr_1`)\n) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4"));
+
+        let table = Table::from_ddl(&ddl).unwrap();
+
+        assert_eq!(table.name, "table_2");
+        assert_eq!(table.primary_key, vec![0]);
+        assert_eq!(
+            table.attributes.iter().map(|attr| attr.name.as_str()).collect::<Vec<&str>>(),
+            vec!["attr_1", "attr_2", "attr_3"]
+        );
+        assert_eq!(table.attributes[0].constraint, HashSet::from([Constraint::NotNull, Constraint::AutoIncrement]));
+        assert_eq!(table.attributes[1].data_type.to_string(), "varchar(255)");
+        assert_eq!(table.attributes[1].constraint, HashSet::from([Constraint::Unique]));
+        assert!(
+            table.attributes[2].constraint.iter().any(|constraint| matches!(
+                constraint,
+                Constraint::ForeignKey{table_name, attribute_name, constraint_name: Some(constraint_name), ..}
+                    if table_name == "table_1" && attribute_name == "attr_1" && constraint_name == "table_2_ibfk_1"
+            ))
+        );
+    }
+
+    #[test]
+    fn from_ddl_test_2() {
+        let table = Table{
+            name: String::from("table_1"),
+            attributes: vec![
+                Attribute{
+                    name: String::from("attr_1"),
+                    data_type: AttributeType::Int(11),
+                    constraint: HashSet::from([Constraint::NotNull])
+                },
+                Attribute{
+                    name: String::from("attr_2"),
+                    data_type: AttributeType::Text,
+                    constraint: HashSet::new()
+                },
+            ],
+            primary_key: vec![0],
+            triggers: vec![],
+        };
+
+        assert_eq!(Table::from_ddl(&table.create()).unwrap().create(), table.create());
+        assert!(Table::from_ddl(&DDL(String::from("CREATE VIEW view_1 AS SELECT * FROM table_1"))).is_none());
+    }
+
+    #[test]
+    fn from_ddl_test_3() {
+        //composite primary keys & columns of unsupported types are kept
+        let ddl = DDL(String::from("CREATE TABLE `order_items` (\n  `order_id` int(11) NOT NULL,\n  `product_id` int(11) NOT NULL,\n  `location` geometry DEFAULT NULL,\n  PRIMARY KEY (`product_id`,`order_id`)\n) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4"));
+
+        let table = Table::from_ddl(&ddl).unwrap();
+
+        assert_eq!(table.primary_key, vec![1, 0]);
+        assert_eq!(table.attributes.len(), 3);
+        assert_eq!(table.attributes[2].data_type.to_string(), "geometry");
+        assert_eq!(Table::from_ddl(&table.create()).unwrap().create(), table.create());
+    }
+
+    #[test]
+    fn from_ddl_test_4() {
+        //composite foreign keys are kept as a single constraint on their first column
+        let ddl = DDL(String::from("CREATE TABLE `shipments` (\n  `id` int(11) NOT NULL,\n  `item_order_id` int(11) DEFAULT NULL,\n  `item_product_id` int(11) DEFAULT NULL,\n  PRIMARY KEY (`id`),\n  KEY `shipments_item_fk` (`item_order_id`,`item_product_id`),\n  CONSTRAINT `shipments_item_fk` FOREIGN KEY (`item_order_id`, `item_product_id`) REFERENCES `order_items` (`order_id`, `product_id`)\n) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4"));
+
+        let table = Table::from_ddl(&ddl).unwrap();
+
+        assert!(
+            table.attributes[1].constraint.iter().any(|constraint| matches!(
+                constraint,
+                Constraint::ForeignKey{table_name, attribute_name, other_columns, ..}
+                    if table_name == "order_items" && attribute_name == "order_id" && other_columns == &vec![(String::from("item_product_id"), String::from("product_id"))]
+            ))
+        );
+        assert!(table.attributes[2].constraint.is_empty());
+        assert!(table.create().contains("CONSTRAINT shipments_item_fk FOREIGN KEY(item_order_id,item_product_id) REFERENCES order_items(order_id,product_id)"));
+        assert_eq!(Table::from_ddl(&table.create()).unwrap().create(), table.create());
+    }
+
     //do more tests
 
     //table insert statement
@@ -755,7 +1177,8 @@ mod tests {
                     constraint: HashSet::new()
                 },
             ],
-            primary_key: None,
+            primary_key: Vec::new(),
+            triggers: vec![],
         };
 
         let mut values = HashMap::new();
@@ -771,6 +1194,58 @@ mod tests {
         assert_eq!(actual, Some(QML(String::from("INSERT INTO table_1(PersonID,LastName,FirstName,Address,City) VALUES (23,'Doe','John','1st Street','Night City')"))));
     }
 
+    #[test]
+    fn insert_batches_test_1() {
+        let table = Table{
+            name: String::from("table_1"),
+            attributes: vec![
+                Attribute{
+                    name: String::from("PersonID"),
+                    data_type: AttributeType::Int(16),
+                    constraint: HashSet::new()
+                },
+                Attribute{
+                    name: String::from("LastName"),
+                    data_type: AttributeType::VarChar(255),
+                    constraint: HashSet::new()
+                },
+            ],
+            primary_key: Vec::new(),
+            triggers: vec![],
+        };
+
+        let rows: Vec<HashMap<String, String>> = vec![
+            HashMap::from([(String::from("PersonID"), String::from("1")), (String::from("LastName"), String::from("'Doe'"))]),
+            HashMap::from([(String::from("PersonID"), String::from("2"))]),
+            HashMap::from([(String::from("PersonID"), String::from("3")), (String::from("LastName"), String::from("'Roe'"))]),
+        ];
+
+        assert_eq!(
+            table.insert_batches(&rows, 1024),
+            vec![QML(String::from("INSERT INTO table_1(PersonID,LastName) VALUES (1,'Doe'),(2,DEFAULT),(3,'Roe')"))]
+        );
+
+        //"INSERT INTO table_1(PersonID,LastName) VALUES " is 46 bytes long & each row is 9 to 11 bytes long
+        assert_eq!(
+            table.insert_batches(&rows, 70),
+            vec![
+                QML(String::from("INSERT INTO table_1(PersonID,LastName) VALUES (1,'Doe'),(2,DEFAULT)")),
+                QML(String::from("INSERT INTO table_1(PersonID,LastName) VALUES (3,'Roe')")),
+            ]
+        );
+
+        assert_eq!(
+            table.insert_batches(&rows, 1),
+            vec![
+                QML(String::from("INSERT INTO table_1(PersonID,LastName) VALUES (1,'Doe')")),
+                QML(String::from("INSERT INTO table_1(PersonID,LastName) VALUES (2,DEFAULT)")),
+                QML(String::from("INSERT INTO table_1(PersonID,LastName) VALUES (3,'Roe')")),
+            ]
+        );
+
+        assert!(table.insert_batches(&[], 1024).is_empty());
+    }
+
     #[test]
     fn insert_test_2(){
         let table = Table{
@@ -802,7 +1277,8 @@ mod tests {
                     constraint: HashSet::new()
                 },
             ],
-            primary_key: None,
+            primary_key: Vec::new(),
+            triggers: vec![],
         };
 
         let mut values = HashMap::new();
@@ -847,7 +1323,8 @@ mod tests {
                     constraint: HashSet::new()
                 },
             ],
-            primary_key: None,
+            primary_key: Vec::new(),
+            triggers: vec![],
         };
 
         let values = HashMap::new();
@@ -856,4 +1333,40 @@ mod tests {
 
         assert_eq!(actual, None);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn select_page_test_1() {
+        let mut table = Table{
+            name: String::from("table_1"),
+            attributes: vec![
+                Attribute{
+                    name: String::from("PersonID"),
+                    data_type: AttributeType::Int(16),
+                    constraint: HashSet::new()
+                },
+                Attribute{
+                    name: String::from("LastName"),
+                    data_type: AttributeType::VarChar(255),
+                    constraint: HashSet::new()
+                },
+            ],
+            primary_key: vec![0],
+            triggers: vec![],
+        };
+
+        assert_eq!(table.select_page(None, 1000), Some(QDL(String::from("SELECT * FROM table_1 ORDER BY PersonID LIMIT 1000"))));
+        assert_eq!(table.select_page(Some(&[String::from("'23'")]), 1000), Some(QDL(String::from("SELECT * FROM table_1 WHERE PersonID > '23' ORDER BY PersonID LIMIT 1000"))));
+
+        table.primary_key = vec![1, 0];
+
+        assert_eq!(table.select_page(None, 1000), Some(QDL(String::from("SELECT * FROM table_1 ORDER BY LastName,PersonID LIMIT 1000"))));
+        assert_eq!(
+            table.select_page(Some(&[String::from("'Doe'"), String::from("23")]), 1000),
+            Some(QDL(String::from("SELECT * FROM table_1 WHERE (LastName,PersonID) > ('Doe',23) ORDER BY LastName,PersonID LIMIT 1000")))
+        );
+
+        table.primary_key = Vec::new();
+
+        assert_eq!(table.select_page(None, 1000), None);
+    }
+}
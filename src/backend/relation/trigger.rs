@@ -0,0 +1,140 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backend::sql::{QDL, DDL};
+
+use super::{RelationMethods, definer_sql};
+
+/// A struct representing a trigger defined on a table
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Trigger{
+    /// The name of the trigger.
+    pub name: String,
+    /// The name of the table the trigger is defined on.
+    pub table_name: String,
+    /// Whether the trigger is activated before or after the event.
+    pub timing: TriggerTiming,
+    /// The row operation that activates the trigger.
+    pub event: TriggerEvent,
+    /// The position of the trigger among the triggers with the same timing & event, starting at 1.
+    pub order: u64,
+    /// The statement executed when the trigger activates (e.g. `BEGIN ... END`).
+    pub body: String,
+    /// The account that defined the trigger (e.g. `root@localhost`).
+    pub definer: String,
+}
+
+impl RelationMethods for Trigger {
+    /// Returns a `QDL` selecting the trigger's definition from `information_schema.TRIGGERS`.
+    fn select(&self) -> QDL {
+        QDL(format!("SELECT * FROM information_schema.TRIGGERS WHERE TRIGGER_SCHEMA = DATABASE() AND TRIGGER_NAME = '{}'", self.name))
+    }
+    /// Returns a `DDL` representing a `DROP` statement for the trigger.
+    fn drop(&self) -> DDL {
+        DDL(format!("DROP TRIGGER {}", self.name))
+    }
+    /// Returns a `DDL` representing a `CREATE` statement for the trigger.
+    ///
+    /// Triggers with the same timing & event are ordered by creation, so they must be created in `order`.
+    fn create(&self) -> DDL {
+        DDL(
+            format!(
+                "CREATE DEFINER={} TRIGGER {} {} {} ON {} FOR EACH ROW {}",
+                definer_sql(&self.definer),
+                self.name,
+                self.timing,
+                self.event,
+                self.table_name,
+                self.body
+            )
+        )
+    }
+}
+
+/// TriggerTiming defines when a trigger is activated relative to its event
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum TriggerTiming{
+    Before,
+    After,
+}
+
+impl TriggerTiming {
+    /// Returns the `TriggerTiming` corresponding to `information_schema.TRIGGERS.ACTION_TIMING`.
+    pub fn from(raw_str: &str) -> Option<TriggerTiming> {
+        match raw_str.to_ascii_uppercase().as_str() {
+            "BEFORE" => Some(TriggerTiming::Before),
+            "AFTER" => Some(TriggerTiming::After),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for TriggerTiming {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TriggerTiming::Before => write!(f, "BEFORE"),
+            TriggerTiming::After => write!(f, "AFTER"),
+        }
+    }
+}
+
+/// TriggerEvent defines the row operations that activate a trigger
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum TriggerEvent{
+    Insert,
+    Update,
+    Delete,
+}
+
+impl TriggerEvent {
+    /// Returns the `TriggerEvent` corresponding to `information_schema.TRIGGERS.EVENT_MANIPULATION`.
+    pub fn from(raw_str: &str) -> Option<TriggerEvent> {
+        match raw_str.to_ascii_uppercase().as_str() {
+            "INSERT" => Some(TriggerEvent::Insert),
+            "UPDATE" => Some(TriggerEvent::Update),
+            "DELETE" => Some(TriggerEvent::Delete),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for TriggerEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TriggerEvent::Insert => write!(f, "INSERT"),
+            TriggerEvent::Update => write!(f, "UPDATE"),
+            TriggerEvent::Delete => write!(f, "DELETE"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(unused_imports)]
+    use crate::backend::{relation::RelationMethods, sql::SQL};
+
+    use super::{Trigger, TriggerTiming, TriggerEvent};
+
+    #[test]
+    fn create_test_1() {
+        let trigger = Trigger{
+            name: String::from("patients_audit"),
+            table_name: String::from("patients"),
+            timing: TriggerTiming::After,
+            event: TriggerEvent::Update,
+            order: 1,
+            body: String::from("BEGIN INSERT INTO audit(patient_id) VALUES (NEW.id); END"),
+            definer: String::from("root@localhost"),
+        };
+
+        assert_eq!(
+            *trigger.create(),
+            "CREATE DEFINER=`root`@`localhost` TRIGGER patients_audit AFTER UPDATE ON patients FOR EACH ROW BEGIN INSERT INTO audit(patient_id) VALUES (NEW.id); END"
+        );
+        assert_eq!(*trigger.drop(), "DROP TRIGGER patients_audit");
+
+        assert!(!SQL::from(trigger.create()).is_preparable());
+        assert!(!SQL::from(trigger.drop()).is_preparable());
+    }
+}
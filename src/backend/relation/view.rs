@@ -1,17 +1,30 @@
-use log::info;
+use std::{fmt, ops::ControlFlow};
+
 use regex::Regex;
 use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use sqlparser::{ast::{Visit, Visitor, Query, ObjectName, Expr}, dialect::MySqlDialect, parser::Parser};
 
-use crate::backend::{ sql::{QDL, SQL, DDL}, data_base::DataBase};
+use crate::backend::{ sql::{QDL, DDL}, data_base::DataBase};
 
-use super::RelationMethods;
+use super::{RelationMethods, definer_sql, information_schema, routine::SqlSecurity};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct View{
     /// The name of the view.
     pub name: String,
     /// The query defining the view.
-    pub query: QDL
+    pub query: QDL,
+    /// The algorithm used to process the view.
+    pub algorithm: ViewAlgorithm,
+    /// The account that defined the view (e.g. `root@localhost`). Empty if the current user is used.
+    pub definer: String,
+    /// The security context the view is executed with.
+    pub security: SqlSecurity,
+    /// The check applied to rows inserted or updated through the view.
+    pub check_option: CheckOption,
+    /// The names of the columns of the view. Empty if the names are derived from the query.
+    pub columns: Vec<String>,
 }
 
 impl View {
@@ -19,45 +32,173 @@ impl View {
     pub fn from_db(name: &str) -> Option<View> {
         let db = DataBase::from_env().unwrap();
 
-        let tmp : Vec<String> = db.execute(
-            &SQL::new(&format!(r"SHOW CREATE TABLE {}", name)).unwrap(),
-            |row| {
-                let tmp_str: String = row.unwrap().get(1).unwrap();
-
-                lazy_static! {
-                    static ref VIEW_REGEX : Regex = Regex::new("[sS][eE][lL][eE][cC][tT] .+").unwrap();
-                };
-
-                VIEW_REGEX.captures(&tmp_str)
-                    .unwrap()
-                    .get(0)
-                    .unwrap()
-                    .as_str()
-                    .to_string()
+        match information_schema::load_view(&db, name) {
+            Ok(view) => view,
+            Err(err) => {
+                log::error!("Failed to load view({}) - Err:{:?}", name, err);
+                None
             }
-        ).unwrap();
-
-        match tmp.get(0) {
-            Some(query) => {
-                //info!("View::from_db - {:?}", query);
-                Some(
-                    View {
-                        name: name.to_string(),
-                        query: SQL::new(query).unwrap().qdl().unwrap().clone()
-                    }
-                )
-            },
-            None => None
         }
+    }
 
+    /// Returns the `View` defined by a `CREATE VIEW` statement, or `None` if the statement is not a `CREATE VIEW` statement.
+    ///
+    /// # Arguments
+    ///
+    /// * `ddl` - A `CREATE VIEW` statement
+    pub fn from_ddl(ddl: &DDL) -> Option<View> {
+        lazy_static!{
+            static ref CREATE_VIEW : Regex = Regex::new(r"(?is)^CREATE\s+(?:OR\s+REPLACE\s+)?(?:ALGORITHM\s*=\s*([a-zA-Z]+)\s+)?(?:DEFINER\s*=\s*(\S+)\s+)?(?:SQL\s+SECURITY\s+([a-zA-Z]+)\s+)?VIEW\s+`?([a-zA-Z0-9_]+)`?\s*(?:\(([^)]*)\)\s*)?AS\s+(.*?)(?:\s+WITH\s+(?:(CASCADED|LOCAL)\s+)?(CHECK)\s+OPTION)?$").unwrap();
+        };
+
+        let captures = CREATE_VIEW.captures(ddl)?;
+
+        Some(
+            View {
+                name: captures.get(4)?.as_str().to_string(),
+                query: QDL(captures.get(6)?.as_str().to_string()),
+                algorithm: captures.get(1)
+                    .and_then(|algorithm| ViewAlgorithm::from(algorithm.as_str()))
+                    .unwrap_or(ViewAlgorithm::Undefined),
+                definer: captures.get(2)
+                    .map(|definer| definer.as_str().replace('`', ""))
+                    .unwrap_or_default(),
+                security: captures.get(3)
+                    .and_then(|security| SqlSecurity::from(security.as_str()))
+                    .unwrap_or(SqlSecurity::Definer),
+                check_option: match (captures.get(7), captures.get(8)) {
+                    (Some(check_option), _) => CheckOption::from(check_option.as_str()).unwrap_or(CheckOption::Cascaded),
+                    (None, Some(_)) => CheckOption::Cascaded,
+                    (None, None) => CheckOption::None,
+                },
+                columns: captures.get(5)
+                    .map(|columns| {
+                        columns.as_str()
+                            .split(',')
+                            .map(|column| column.trim().replace('`', ""))
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            }
+        )
     }
 
     /// Returns a new `View` with the given name and query.
     pub fn new(name: &str, query: QDL) -> View {
         View{
             name: name.to_string(),
-            query: query
+            query: query,
+            algorithm: ViewAlgorithm::Undefined,
+            definer: String::new(),
+            security: SqlSecurity::Definer,
+            check_option: CheckOption::None,
+            columns: Vec::new(),
+        }
+    }
+
+    /// Returns the names of the tables & views the query of the view selects from, in order of first reference & without duplicates.
+    ///
+    /// The query is parsed, so relations referenced by subqueries, `EXISTS` conditions, unions & common table expressions are included,
+    /// while aliases & the names of common table expressions are not. Schema-qualified names (e.g. `` `db`.`table_1` ``) are returned without their schema.
+    ///
+    /// If the query can't be parsed, every identifier of the query is returned, so no dependency is missed.
+    pub fn get_dependencies(&self) -> Vec<String> {
+        let visitor = match self.visit() {
+            Some(visitor) => visitor,
+            None => {
+                lazy_static!{
+                    static ref IDENTIFIER_REGEX : Regex = Regex::new("[a-zA-Z_][a-zA-Z0-9_$]*").unwrap();
+                };
+
+                let mut identifiers: Vec<String> = Vec::new();
+
+                IDENTIFIER_REGEX.find_iter(&self.query)
+                    .map(|identifier| identifier.as_str().to_string())
+                    .for_each(|identifier| {
+                        if !identifiers.contains(&identifier) {
+                            identifiers.push(identifier);
+                        }
+                    });
+
+                return identifiers;
+            },
+        };
+
+        visitor.relations.into_iter()
+            .filter(|relation| !visitor.common_table_expressions.contains(relation))
+            .collect()
+    }
+
+    /// Returns the names of the functions called by the query of the view, including built-in functions, in order of first call & without duplicates.
+    ///
+    /// If the query can't be parsed, no function is returned, as `get_dependencies` already returns every identifier of the query.
+    pub fn get_function_calls(&self) -> Vec<String> {
+        self.visit()
+            .map(|visitor| visitor.functions)
+            .unwrap_or_default()
+    }
+
+    /// Parses the query of the view & collects the relations it references, or returns `None` if the query can't be parsed.
+    fn visit(&self) -> Option<DependencyVisitor> {
+        let statements = match Parser::parse_sql(&MySqlDialect {}, &self.query) {
+            Ok(statements) => statements,
+            Err(err) => {
+                log::warn!("Failed to parse the query of view({}) - Err:{:?}", self.name, err);
+                return None;
+            },
+        };
+
+        let mut visitor = DependencyVisitor::default();
+
+        let _result = statements.visit(&mut visitor);
+
+        Some(visitor)
+    }
+}
+
+/// Collects the relations referenced by a parsed query
+#[derive(Default)]
+struct DependencyVisitor{
+    /// The names of the referenced relations, without duplicates.
+    relations: Vec<String>,
+    /// The names of the common table expressions defined by `WITH` clauses.
+    common_table_expressions: Vec<String>,
+    /// The names of the called functions, without duplicates.
+    functions: Vec<String>,
+}
+
+impl Visitor for DependencyVisitor {
+    type Break = ();
+
+    fn pre_visit_query(&mut self, query: &Query) -> ControlFlow<Self::Break> {
+        if let Some(with) = &query.with {
+            with.cte_tables.iter()
+                .for_each(|cte| self.common_table_expressions.push(cte.alias.name.value.clone()));
         }
+
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_relation(&mut self, relation: &ObjectName) -> ControlFlow<Self::Break> {
+        if let Some(name) = relation.0.last() {
+            if !self.relations.contains(&name.value) {
+                self.relations.push(name.value.clone());
+            }
+        }
+
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_expr(&mut self, expr: &Expr) -> ControlFlow<Self::Break> {
+        if let Expr::Function(function) = expr {
+            if let Some(name) = function.name.0.last() {
+                if !self.functions.contains(&name.value) {
+                    self.functions.push(name.value.clone());
+                }
+            }
+        }
+
+        ControlFlow::Continue(())
     }
 }
 
@@ -72,6 +213,199 @@ impl RelationMethods for View{
     }
     /// Returns a `DDL` representing a `CREATE` statement for the view.
     fn create(&self) -> DDL {
-        SQL::new(&format!("CREATE VIEW {} AS {}", self.name, *self.query)).unwrap().ddl().unwrap().clone()
+        let definer = match self.definer.len() {
+            0 => String::new(),
+            _ => format!("DEFINER={} ", definer_sql(&self.definer)),
+        };
+
+        let columns = match self.columns.len() {
+            0 => String::new(),
+            _ => format!(" ({})", self.columns.join(", ")),
+        };
+
+        let check_option = match self.check_option {
+            CheckOption::None => String::new(),
+            _ => format!(" WITH {} CHECK OPTION", self.check_option),
+        };
+
+        DDL(
+            format!(
+                "CREATE ALGORITHM={} {}SQL SECURITY {} VIEW {}{} AS {}{}",
+                self.algorithm,
+                definer,
+                self.security,
+                self.name,
+                columns,
+                *self.query,
+                check_option
+            )
+        )
     }
-}
\ No newline at end of file
+}
+
+/// ViewAlgorithm defines how MySQL processes a view
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum ViewAlgorithm{
+    Undefined,
+    Merge,
+    TempTable,
+}
+
+impl ViewAlgorithm {
+    /// Returns the `ViewAlgorithm` corresponding to the `ALGORITHM` of a view.
+    pub fn from(raw_str: &str) -> Option<ViewAlgorithm> {
+        match raw_str.to_ascii_uppercase().as_str() {
+            "UNDEFINED" => Some(ViewAlgorithm::Undefined),
+            "MERGE" => Some(ViewAlgorithm::Merge),
+            "TEMPTABLE" => Some(ViewAlgorithm::TempTable),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ViewAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ViewAlgorithm::Undefined => write!(f, "UNDEFINED"),
+            ViewAlgorithm::Merge => write!(f, "MERGE"),
+            ViewAlgorithm::TempTable => write!(f, "TEMPTABLE"),
+        }
+    }
+}
+
+/// CheckOption defines the `WITH CHECK OPTION` clause of an updatable view
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum CheckOption{
+    None,
+    Local,
+    Cascaded,
+}
+
+impl CheckOption {
+    /// Returns the `CheckOption` corresponding to `information_schema.VIEWS.CHECK_OPTION`.
+    pub fn from(raw_str: &str) -> Option<CheckOption> {
+        match raw_str.to_ascii_uppercase().as_str() {
+            "NONE" => Some(CheckOption::None),
+            "LOCAL" => Some(CheckOption::Local),
+            "CASCADED" => Some(CheckOption::Cascaded),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for CheckOption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckOption::None => write!(f, "NONE"),
+            CheckOption::Local => write!(f, "LOCAL"),
+            CheckOption::Cascaded => write!(f, "CASCADED"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(unused_imports)]
+    use crate::backend::{relation::{RelationMethods, routine::SqlSecurity}, sql::{SQL, QDL}};
+
+    use super::{View, ViewAlgorithm, CheckOption};
+
+    #[test]
+    fn create_test_1() {
+        let view = View::new("view_1", QDL(String::from("SELECT * FROM table_1")));
+
+        assert_eq!(
+            *view.create(),
+            "CREATE ALGORITHM=UNDEFINED SQL SECURITY DEFINER VIEW view_1 AS SELECT * FROM table_1"
+        );
+    }
+
+    #[test]
+    fn create_test_2() {
+        let view = View{
+            name: String::from("recent_patients"),
+            query: QDL(String::from("WITH recent AS (SELECT id, name FROM patients WHERE created > NOW() - INTERVAL 1 DAY) SELECT * FROM recent")),
+            algorithm: ViewAlgorithm::TempTable,
+            definer: String::from("root@localhost"),
+            security: SqlSecurity::Invoker,
+            check_option: CheckOption::Cascaded,
+            columns: vec![String::from("patient_id"), String::from("patient_name")],
+        };
+
+        assert_eq!(
+            *view.create(),
+            "CREATE ALGORITHM=TEMPTABLE DEFINER=`root`@`localhost` SQL SECURITY INVOKER VIEW recent_patients (patient_id, patient_name) AS WITH recent AS (SELECT id, name FROM patients WHERE created > NOW() - INTERVAL 1 DAY) SELECT * FROM recent WITH CASCADED CHECK OPTION"
+        );
+        assert_eq!(SQL::from(view.create()), SQL::Create(view.create()));
+        assert!(SQL::new(&view.query).unwrap().qdl().is_some());
+    }
+
+    #[test]
+    fn from_ddl_test_1() {
+        let view = View{
+            name: String::from("recent_patients"),
+            query: QDL(String::from("SELECT id, name FROM patients")),
+            algorithm: ViewAlgorithm::Merge,
+            definer: String::from("root@localhost"),
+            security: SqlSecurity::Invoker,
+            check_option: CheckOption::Local,
+            columns: vec![String::from("patient_id"), String::from("patient_name")],
+        };
+
+        let actual = View::from_ddl(&view.create()).unwrap();
+
+        assert_eq!(actual.create(), view.create());
+        assert_eq!(actual.columns, view.columns);
+        assert_eq!(actual.check_option, CheckOption::Local);
+
+        let actual = View::from_ddl(&View::new("view_1", QDL(String::from("SELECT * FROM table_1"))).create()).unwrap();
+
+        assert_eq!(actual.name, "view_1");
+        assert_eq!(actual.query, QDL(String::from("SELECT * FROM table_1")));
+        assert_eq!(actual.check_option, CheckOption::None);
+        assert!(actual.columns.is_empty());
+    }
+
+    #[test]
+    fn get_dependencies_test_1() {
+        let dependencies = |query: &str| View::new("view_1", QDL(String::from(query))).get_dependencies();
+
+        assert_eq!(dependencies("SELECT * FROM table_1 JOINED_ALIAS"), vec!["table_1"]);
+        assert_eq!(
+            dependencies("SELECT t.attr_1 FROM table_1 t INNER JOIN table_2 AS u ON t.attr_1 = u.attr_1 LEFT OUTER JOIN view_2 ON TRUE"),
+            vec!["table_1", "table_2", "view_2"]
+        );
+        assert_eq!(
+            dependencies("SELECT * FROM table_1 WHERE EXISTS (SELECT 1 FROM table_2 WHERE table_2.attr_1 = table_1.attr_1) AND attr_2 IN (SELECT attr_2 FROM table_3)"),
+            vec!["table_1", "table_2", "table_3"]
+        );
+        assert_eq!(
+            dependencies("SELECT attr_1 FROM table_1 UNION SELECT attr_1 FROM table_2 UNION ALL SELECT attr_1 FROM table_1"),
+            vec!["table_1", "table_2"]
+        );
+        assert_eq!(
+            dependencies("WITH recent AS (SELECT id, name FROM patients WHERE created > NOW() - INTERVAL 1 DAY) SELECT * FROM recent"),
+            vec!["patients"]
+        );
+        assert_eq!(
+            dependencies("select `db`.`table_1`.`attr_1` AS `attr_1` from (`db`.`table_1` join `db`.`table_2` on((`db`.`table_1`.`attr_1` = `db`.`table_2`.`attr_1`)))"),
+            vec!["table_1", "table_2"]
+        );
+    }
+
+    #[test]
+    fn get_dependencies_test_2() {
+        //queries that can't be parsed fall back to every identifier
+        let view = View::new("view_1", QDL(String::from("SELECT FROM FROM table_1")));
+
+        assert_eq!(view.get_dependencies(), vec!["SELECT", "FROM", "table_1"]);
+    }
+
+    #[test]
+    fn get_function_calls_test_1() {
+        let view = View::new("view_1", QDL(String::from("SELECT attr_1, function_1(attr_1) FROM table_1 WHERE attr_2 > (SELECT MAX(attr_2) FROM table_2 WHERE function_1(attr_3))")));
+
+        assert_eq!(view.get_function_calls(), vec!["function_1", "MAX"]);
+        assert_eq!(view.get_dependencies(), vec!["table_1", "table_2"]);
+    }
+}
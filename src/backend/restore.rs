@@ -0,0 +1,585 @@
+use std::{fmt, collections::HashSet};
+
+use lazy_static::lazy_static;
+use petgraph::{Incoming, Outgoing, visit::NodeIndexable};
+use regex::Regex;
+
+use super::{sql::{SQL, QDL, QML}, relation::{Relation, RelationMethods, table::Constraint, trigger::Trigger, paths::{get_dependency_tree, get_generation_path, get_cycles}}};
+
+/// Enum representing the progress of a layered restore
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Progress {
+    /// Indicates that the relations of the database were dropped
+    Cleared,
+    /// Indicates that the tables of a layer started being restored
+    Layer{layer: usize, layers: usize},
+    /// Indicates that a table was restored
+    Table{name: String, restored: usize, tables: usize},
+    /// Indicates that the views, routines, triggers, events & deferred foreign keys started being restored
+    Remaining{statements: usize},
+    /// Indicates that the restore finished
+    Done,
+    /// Indicates that the restore failed
+    Failed(String),
+}
+
+impl fmt::Display for Progress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Progress::Cleared => write!(f, "dropped relations"),
+            Progress::Layer { layer, layers } => write!(f, "restoring layer {}/{}", layer + 1, layers),
+            Progress::Table { name, restored, tables } => write!(f, "restored {} ({}/{} tables)", name, restored, tables),
+            Progress::Remaining { statements } => write!(f, "restoring {} remaining statement(s)", statements),
+            Progress::Done => write!(f, "restored"),
+            Progress::Failed(err) => write!(f, "failed to restore: {}", err),
+        }
+    }
+}
+
+/// The statements restoring a single table
+#[derive(Clone, Debug)]
+pub struct TableRestore{
+    /// The name of the table.
+    pub name: String,
+    /// The `CREATE TABLE` statement of the table, followed by its `INSERT` statements.
+    pub cmds: Vec<SQL>,
+}
+
+/// RestorePlan struct defines the statements of a snapshot grouped into layers of tables that can be restored concurrently
+#[derive(Clone, Debug)]
+pub struct RestorePlan{
+    /// The tables of each layer. A table only references tables of previous layers, or tables of its own cycle whose foreign keys are added afterwards.
+    pub layers: Vec<Vec<TableRestore>>,
+    /// The statements executed in order once every table is restored (e.g. views, routines, triggers, events & deferred foreign keys).
+    pub remaining: Vec<SQL>,
+}
+
+impl RestorePlan {
+    /// Returns the plan restoring the statements of a snapshot.
+    ///
+    /// Each `CREATE TABLE` statement is grouped with the `INSERT` statements of its table. Tables are layered by their foreign keys using the dependency tree,
+    /// ignoring foreign keys between tables of a cycle. Every other statement is kept in `remaining`, in its original order.
+    pub fn from(cmds: Vec<SQL>) -> RestorePlan {
+        let mut tables: Vec<Relation> = Vec::new();
+        let mut table_cmds: Vec<Vec<SQL>> = Vec::new();
+        let mut remaining: Vec<SQL> = Vec::new();
+
+        cmds.into_iter()
+            .for_each(|cmd| {
+                if let Some(Relation::Table(table)) = Relation::from_sql(&cmd) {
+                    tables.push(Relation::Table(table));
+                    table_cmds.push(vec![cmd]);
+                    return;
+                }
+
+                let table = statement_table(&cmd)
+                    .filter(|_| matches!(cmd, SQL::Insert(_)))
+                    .and_then(|name| tables.iter().position(|table| table.name() == name));
+
+                match table {
+                    Some(index) => table_cmds[index].push(cmd),
+                    None => remaining.push(cmd),
+                }
+            });
+
+        let dependency_tree = get_dependency_tree(&tables);
+        let cycles = get_cycles(&dependency_tree);
+        let cycle = |index: usize| cycles.iter().position(|cycle| cycle.contains(&index));
+
+        //a table is restored one layer after its deepest dependency
+        let mut layer_of: Vec<usize> = vec![0; tables.len()];
+
+        get_generation_path(&tables, &dependency_tree)
+            .iter()
+            .for_each(|index| {
+                layer_of[*index] = dependency_tree.neighbors_directed(dependency_tree.from_index(*index), Incoming)
+                    .map(|dependency| dependency_tree[dependency] as usize)
+                    .filter(|dependency| cycle(*dependency).is_none() || cycle(*dependency) != cycle(*index))
+                    .map(|dependency| layer_of[dependency] + 1)
+                    .max()
+                    .unwrap_or(0);
+            });
+
+        let mut layers: Vec<Vec<TableRestore>> = vec![Vec::new(); layer_of.iter().max().map_or(0, |layer| layer + 1)];
+
+        tables.iter()
+            .zip(table_cmds)
+            .enumerate()
+            .for_each(|(index, (table, cmds))| layers[layer_of[index]].push(TableRestore { name: table.name(), cmds }));
+
+        RestorePlan { layers, remaining }
+    }
+
+    /// Returns the number of tables restored by the plan.
+    pub fn tables(&self) -> usize {
+        self.layers.iter()
+            .map(|layer| layer.len())
+            .sum()
+    }
+}
+
+/// Returns the name of the table an `INSERT`, `DELETE`, `ALTER TABLE` or `CREATE TRIGGER` statement applies to.
+fn statement_table(cmd: &SQL) -> Option<&str> {
+    lazy_static!{
+        static ref INSERT_INTO : Regex = Regex::new("^(?i)INSERT\\s+(?:IGNORE\\s+)?INTO\\s+`?([a-zA-Z0-9_$]+)`?").unwrap();
+    };
+    lazy_static!{
+        static ref DELETE_FROM : Regex = Regex::new("^(?i)DELETE\\s+FROM\\s+`?([a-zA-Z0-9_$]+)`?").unwrap();
+    };
+    lazy_static!{
+        static ref ALTER_TABLE : Regex = Regex::new("^(?i)ALTER\\s+TABLE\\s+`?([a-zA-Z0-9_$]+)`?").unwrap();
+    };
+    lazy_static!{
+        static ref CREATE_TRIGGER : Regex = Regex::new("^(?i)CREATE\\s+(?:DEFINER\\s*=\\s*\\S+\\s+)?TRIGGER\\s+\\S+\\s+\\w+\\s+\\w+\\s+ON\\s+`?([a-zA-Z0-9_$]+)`?").unwrap();
+    };
+
+    let (regex, cmd): (&Regex, &str) = match cmd {
+        SQL::Insert(qml) => (&INSERT_INTO, qml),
+        SQL::Delete(qml) => (&DELETE_FROM, qml),
+        SQL::Alter(ddl) => (&ALTER_TABLE, ddl),
+        SQL::Create(ddl) => (&CREATE_TRIGGER, ddl),
+        _ => return None,
+    };
+
+    regex.captures(cmd)
+        .and_then(|captures| captures.get(1))
+        .map(|name| name.as_str())
+}
+
+/// Returns the statements of a snapshot restoring a single table, or `None` if the snapshot doesn't hold the table.
+///
+/// With `data_only`, only the rows of the table are replaced: its `INSERT` statements follow a `DELETE FROM` statement.
+/// Otherwise the table is recreated by its `CREATE TABLE` statement, followed by its rows, the foreign keys added to it once rows are restored & its triggers.
+///
+/// # Arguments
+///
+/// * `cmds` - The statements of the snapshot
+/// * `table` - The name of the table
+/// * `data_only` - Whether only the rows of the table are restored
+pub fn table_statements(cmds: Vec<SQL>, table: &str, data_only: bool) -> Option<Vec<SQL>> {
+    // whether the snapshot holds the definition of the table or, for data only snapshots, clears its rows
+    let mut created = false;
+    let mut cleared = false;
+
+    let mut statements: Vec<SQL> = cmds.into_iter()
+        .filter(|cmd| {
+            if let Some(Relation::Table(created_table)) = Relation::from_sql(cmd) {
+                created = created || created_table.name == table;
+                return !data_only && created_table.name == table;
+            }
+
+            match cmd {
+                SQL::Insert(_) => statement_table(cmd) == Some(table),
+                SQL::Delete(_) => {
+                    cleared = cleared || statement_table(cmd) == Some(table);
+                    false
+                },
+                SQL::Alter(_) | SQL::Create(_) => !data_only && statement_table(cmd) == Some(table),
+                _ => false,
+            }
+        })
+        .collect();
+
+    match (data_only, created, cleared) {
+        (true, true, _) | (true, _, true) => {
+            statements.insert(0, SQL::Delete(QML(format!("DELETE FROM {}", table))));
+            Some(statements)
+        },
+        (false, true, _) => Some(statements),
+        _ => None,
+    }
+}
+
+/// Returns the statements dropping & recreating the triggers of the tables whose rows a partial snapshot replaces, in the order they are executed.
+///
+/// The triggers are read from `relations` (e.g. the relations of the database) & are dropped in reverse order before the rows are replaced, then recreated in order,
+/// so they don't fire on restored rows. Tables created by the snapshot, or whose triggers it creates, are skipped as the snapshot recreates their triggers.
+///
+/// # Arguments
+///
+/// * `cmds` - The statements of the partial snapshot
+/// * `relations` - The relations defining the triggers
+pub fn trigger_statements(cmds: &[SQL], relations: &[Relation]) -> (Vec<SQL>, Vec<SQL>) {
+    let mut created: HashSet<String> = HashSet::new();
+    let mut replaced: HashSet<&str> = HashSet::new();
+
+    cmds.iter()
+        .for_each(|cmd| match (Relation::from_sql(cmd), cmd) {
+            (Some(Relation::Table(table)), _) => {
+                created.insert(table.name);
+            },
+            (_, SQL::Create(_)) => {
+                created.extend(statement_table(cmd).map(str::to_string));
+            },
+            (_, SQL::Insert(_) | SQL::Delete(_)) => {
+                replaced.extend(statement_table(cmd));
+            },
+            _ => {},
+        });
+
+    let triggers: Vec<&Trigger> = relations.iter()
+        .filter_map(|relation| match relation {
+            Relation::Table(table) if replaced.contains(table.name.as_str()) && !created.contains(&table.name) => Some(table),
+            _ => None,
+        })
+        .flat_map(|table| table.triggers.iter())
+        .collect();
+
+    (
+        triggers.iter().rev().map(|trigger| SQL::from(trigger.drop())).collect(),
+        triggers.iter().map(|trigger| SQL::from(trigger.create())).collect(),
+    )
+}
+
+/// Returns the queries counting the rows referencing missing rows after a partial snapshot is restored, labelled by their foreign key (e.g. `visits.patient_id -> patients(id)`).
+///
+/// Partial restores run with foreign key checks disabled, so the foreign keys of the restored tables & of the tables referencing them, found through the dependency tree, are checked
+/// with a `LEFT JOIN` on the referenced table. Rows with a null foreign key column don't reference any row & aren't counted.
+///
+/// # Arguments
+///
+/// * `cmds` - The statements of the partial snapshot
+/// * `relations` - The relations of the database
+pub fn orphan_checks(cmds: &[SQL], relations: &[Relation]) -> Vec<(String, SQL)> {
+    //the tables created by the snapshot or whose rows it replaces
+    let tables: Vec<String> = cmds.iter()
+        .filter_map(|cmd| match (Relation::from_sql(cmd), cmd) {
+            (Some(Relation::Table(table)), _) => Some(table.name),
+            (_, SQL::Insert(_) | SQL::Delete(_)) => statement_table(cmd).map(str::to_string),
+            _ => None,
+        })
+        .collect();
+
+    let tables = &tables;
+    let dependency_tree = get_dependency_tree(relations);
+
+    let restored: Vec<usize> = relations.iter()
+        .enumerate()
+        .filter(|(_index, relation)| matches!(relation, Relation::Table(table) if tables.contains(&table.name)))
+        .map(|(index, _relation)| index)
+        .collect();
+
+    //the restored tables & the tables referencing them
+    let mut referencing: Vec<usize> = restored.iter()
+        .flat_map(|index| {
+            std::iter::once(*index)
+                .chain(
+                    dependency_tree.neighbors_directed(dependency_tree.from_index(*index), Outgoing)
+                        .map(|dependent| dependency_tree[dependent] as usize)
+                )
+        })
+        .collect();
+    referencing.sort();
+    referencing.dedup();
+
+    referencing.iter()
+        .filter_map(|index| match &relations[*index] {
+            Relation::Table(table) => Some((restored.contains(index), table)),
+            _ => None,
+        })
+        .flat_map(|(table_restored, table)| {
+            table.attributes.iter()
+                .flat_map(move |attribute| attribute.constraint.iter().map(move |constraint| (attribute, constraint)))
+                .filter_map(move |(attribute, constraint)| match constraint {
+                    Constraint::ForeignKey { table_name, attribute_name, other_columns, .. } if table_restored || tables.contains(table_name) => {
+                        let columns: Vec<(&str, &str)> = std::iter::once((attribute.name.as_str(), attribute_name.as_str()))
+                            .chain(other_columns.iter().map(|(column, referenced)| (column.as_str(), referenced.as_str())))
+                            .collect();
+
+                        let join = columns.iter()
+                            .map(|(column, referenced)| format!("child.{} = parent.{}", column, referenced))
+                            .collect::<Vec<String>>()
+                            .join(" AND ");
+                        let not_null = columns.iter()
+                            .map(|(column, _referenced)| format!(" AND child.{} IS NOT NULL", column))
+                            .collect::<String>();
+
+                        Some((
+                            format!("{}.{} -> {}", table.name, attribute.name, constraint),
+                            SQL::Select(QDL(format!(
+                                "SELECT COUNT(*) FROM {} AS child LEFT JOIN {} AS parent ON {} WHERE parent.{} IS NULL{}",
+                                table.name, table_name, join, attribute_name, not_null
+                            )))
+                        ))
+                    },
+                    _ => None,
+                })
+        })
+        .collect()
+}
+
+/// Returns the statements with consecutive `INSERT` statements into the same columns of a table merged into multi-row statements.
+///
+/// Like `Table::insert_batches`, rows are merged until a statement would be longer than `max_size` bytes, so snapshots with a statement per row are restored in batches too.
+/// Every other statement, including `INSERT ... SELECT` & `INSERT ... ON DUPLICATE KEY UPDATE` statements, is kept as is.
+///
+/// # Arguments
+///
+/// * `cmds` - The statements to batch
+/// * `max_size` - The maximum length of a merged statement in bytes
+pub fn batch_inserts(cmds: Vec<SQL>, max_size: usize) -> Vec<SQL> {
+    lazy_static!{
+        static ref INSERT_VALUES : Regex = Regex::new("^(?is)(INSERT\\s+INTO\\s+`?[a-zA-Z0-9_$]+`?\\s*\\([^)]*\\)\\s*VALUES\\s*)(\\(.*\\))$").unwrap();
+    };
+    lazy_static!{
+        static ref ON_DUPLICATE_KEY : Regex = Regex::new("(?i)\\)\\s*ON\\s+DUPLICATE\\s+KEY\\s+UPDATE").unwrap();
+    };
+
+    let mut batched: Vec<SQL> = Vec::with_capacity(cmds.len());
+    //the prefix (e.g. `INSERT INTO table_1(attr_1) VALUES `) & rows of the statement being merged
+    let mut batch: Option<(String, String)> = None;
+
+    let flush = |batch: &mut Option<(String, String)>, batched: &mut Vec<SQL>| {
+        if let Some((prefix, rows)) = batch.take() {
+            batched.push(SQL::Insert(QML(format!("{}{}", prefix, rows))));
+        }
+    };
+
+    cmds.into_iter()
+        .for_each(|cmd| {
+            let captures = match &cmd {
+                SQL::Insert(qml) if !ON_DUPLICATE_KEY.is_match(qml) => INSERT_VALUES.captures(qml),
+                _ => None,
+            };
+
+            let (prefix, rows) = match captures {
+                Some(captures) => (captures.get(1).unwrap().as_str(), captures.get(2).unwrap().as_str()),
+                None => {
+                    flush(&mut batch, &mut batched);
+                    batched.push(cmd);
+                    return;
+                },
+            };
+
+            match &mut batch {
+                Some((batch_prefix, batch_rows)) if batch_prefix == prefix && batch_prefix.len() + batch_rows.len() + 1 + rows.len() <= max_size => {
+                    batch_rows.push(',');
+                    batch_rows.push_str(rows);
+                },
+                _ => {
+                    flush(&mut batch, &mut batched);
+                    batch = Some((prefix.to_string(), rows.to_string()));
+                },
+            }
+        });
+
+    flush(&mut batch, &mut batched);
+
+    batched
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(unused_imports)]
+    use crate::backend::sql::SQL;
+
+    use crate::backend::relation::{Relation, trigger::{Trigger, TriggerTiming, TriggerEvent}};
+
+    use super::{RestorePlan, batch_inserts, table_statements, trigger_statements, orphan_checks};
+
+    #[test]
+    fn plan_test_1() {
+        let cmds: Vec<SQL> = vec![
+            "CREATE TABLE patients (id int(11), PRIMARY KEY(id))",
+            "CREATE TABLE doctors (id int(11), PRIMARY KEY(id))",
+            "CREATE TABLE visits (id int(11),patient_id int(11),doctor_id int(11), PRIMARY KEY(id), FOREIGN KEY (patient_id) REFERENCES patients(id), FOREIGN KEY (doctor_id) REFERENCES doctors(id))",
+            "CREATE TABLE notes (id int(11),visit_id int(11), PRIMARY KEY(id), FOREIGN KEY (visit_id) REFERENCES visits(id))",
+            "CREATE ALGORITHM=UNDEFINED SQL SECURITY DEFINER VIEW patient_visits AS SELECT * FROM visits",
+            "INSERT INTO patients(id) VALUES (1)",
+            "INSERT INTO doctors(id) VALUES (1)",
+            "INSERT INTO visits(id,patient_id,doctor_id) VALUES (1,1,1)",
+            "INSERT INTO patients(id) VALUES (2)",
+            "ALTER TABLE notes ADD FOREIGN KEY(id) REFERENCES notes(id)",
+        ].iter()
+            .map(|cmd| SQL::new(cmd).unwrap())
+            .collect();
+
+        let plan = RestorePlan::from(cmds);
+
+        let layers: Vec<Vec<(String, usize)>> = plan.layers.iter()
+            .map(|layer| layer.iter().map(|table| (table.name.clone(), table.cmds.len())).collect())
+            .collect();
+
+        assert_eq!(
+            layers,
+            vec![
+                vec![(String::from("patients"), 3), (String::from("doctors"), 2)],
+                vec![(String::from("visits"), 2)],
+                vec![(String::from("notes"), 1)],
+            ]
+        );
+        assert_eq!(plan.tables(), 4);
+        assert_eq!(
+            plan.remaining.iter().map(|cmd| cmd.to_string()).collect::<Vec<String>>(),
+            vec![
+                "CREATE ALGORITHM=UNDEFINED SQL SECURITY DEFINER VIEW patient_visits AS SELECT * FROM visits",
+                "ALTER TABLE notes ADD FOREIGN KEY(id) REFERENCES notes(id)",
+            ]
+        );
+    }
+
+    #[test]
+    fn batch_inserts_test_1() {
+        let cmds: Vec<SQL> = vec![
+            "CREATE TABLE patients (id int(11),name varchar(255), PRIMARY KEY(id))",
+            "INSERT INTO patients(id,name) VALUES (1,'Doe')",
+            "INSERT INTO patients(id,name) VALUES (2,'Roe'),(3,'Poe')",
+            "INSERT INTO patients(id) VALUES (4)",
+            "INSERT INTO patients(id) VALUES (5)",
+            "INSERT INTO patients(id,name) VALUES (6,'(Moe)') ON DUPLICATE KEY UPDATE name=VALUES(name)",
+            "INSERT INTO patients(id,name) VALUES (7,'Zoe')",
+            "INSERT INTO patients(id,name) VALUES (8,'Joe')",
+        ].iter()
+            .map(|cmd| SQL::new(cmd).unwrap())
+            .collect();
+
+        let batched = |max_size: usize| -> Vec<String> {
+            batch_inserts(cmds.clone(), max_size).iter().map(|cmd| cmd.to_string()).collect()
+        };
+
+        assert_eq!(
+            batched(1024),
+            vec![
+                "CREATE TABLE patients (id int(11),name varchar(255), PRIMARY KEY(id))",
+                "INSERT INTO patients(id,name) VALUES (1,'Doe'),(2,'Roe'),(3,'Poe')",
+                "INSERT INTO patients(id) VALUES (4),(5)",
+                "INSERT INTO patients(id,name) VALUES (6,'(Moe)') ON DUPLICATE KEY UPDATE name=VALUES(name)",
+                "INSERT INTO patients(id,name) VALUES (7,'Zoe'),(8,'Joe')",
+            ]
+        );
+
+        //"INSERT INTO patients(id,name) VALUES " is 37 bytes long
+        assert_eq!(batched(60)[1..3], ["INSERT INTO patients(id,name) VALUES (1,'Doe')", "INSERT INTO patients(id,name) VALUES (2,'Roe'),(3,'Poe')"]);
+    }
+
+    #[test]
+    fn table_statements_test_1() {
+        let cmds: Vec<SQL> = vec![
+            "CREATE TABLE patients (id int(11), PRIMARY KEY(id))",
+            "CREATE TABLE visits (id int(11),patient_id int(11), PRIMARY KEY(id))",
+            "CREATE DEFINER=`root`@`localhost` TRIGGER visits_check BEFORE INSERT ON visits FOR EACH ROW SET NEW.id = NEW.id",
+            "INSERT INTO patients(id) VALUES (1)",
+            "INSERT INTO visits(id,patient_id) VALUES (1,1)",
+            "ALTER TABLE visits ADD FOREIGN KEY(patient_id) REFERENCES patients(id)",
+        ].iter()
+            .map(|cmd| SQL::new(cmd).unwrap())
+            .collect();
+
+        let statements = |table: &str, data_only: bool| -> Option<Vec<String>> {
+            table_statements(cmds.clone(), table, data_only)
+                .map(|statements| statements.iter().map(|cmd| cmd.to_string()).collect())
+        };
+
+        assert_eq!(
+            statements("visits", false),
+            Some(vec![
+                String::from("CREATE TABLE visits (id int(11),patient_id int(11), PRIMARY KEY(id))"),
+                String::from("CREATE DEFINER=`root`@`localhost` TRIGGER visits_check BEFORE INSERT ON visits FOR EACH ROW SET NEW.id = NEW.id"),
+                String::from("INSERT INTO visits(id,patient_id) VALUES (1,1)"),
+                String::from("ALTER TABLE visits ADD FOREIGN KEY(patient_id) REFERENCES patients(id)"),
+            ])
+        );
+        assert_eq!(
+            statements("patients", true),
+            Some(vec![String::from("DELETE FROM patients"), String::from("INSERT INTO patients(id) VALUES (1)")])
+        );
+        assert_eq!(statements("doctors", false), None);
+
+        let data_only: Vec<SQL> = vec!["DELETE FROM patients", "INSERT INTO patients(id) VALUES (1)"].iter()
+            .map(|cmd| SQL::new(cmd).unwrap())
+            .collect();
+
+        assert_eq!(table_statements(data_only.clone(), "patients", false), None);
+        assert_eq!(table_statements(data_only, "patients", true).map(|statements| statements.len()), Some(2));
+    }
+
+    #[test]
+    fn trigger_statements_test_1() {
+        let trigger = |name: &str, table_name: &str, order: u64| Trigger {
+            name: name.to_string(),
+            table_name: table_name.to_string(),
+            timing: TriggerTiming::After,
+            event: TriggerEvent::Insert,
+            order,
+            body: String::from("SET @count = @count + 1"),
+            definer: String::from("root@localhost"),
+        };
+
+        let relations: Vec<Relation> = [
+            ("patients", vec![trigger("patients_log_1", "patients", 1), trigger("patients_log_2", "patients", 2)]),
+            ("visits", vec![trigger("visits_log", "visits", 1)]),
+            ("doctors", vec![trigger("doctors_log", "doctors", 1)]),
+        ].into_iter()
+            .map(|(name, triggers)| {
+                match Relation::from_sql(&SQL::new(&format!("CREATE TABLE {} (id int(11), PRIMARY KEY(id))", name)).unwrap()) {
+                    Some(Relation::Table(mut table)) => {
+                        table.triggers = triggers;
+                        Relation::Table(table)
+                    },
+                    _ => panic!("expected a table"),
+                }
+            })
+            .collect();
+
+        //the rows of patients are replaced, visits is recreated by the snapshot & doctors is untouched
+        let cmds: Vec<SQL> = vec![
+            "DELETE FROM patients",
+            "INSERT INTO patients(id) VALUES (1)",
+            "CREATE TABLE visits (id int(11), PRIMARY KEY(id))",
+            "INSERT INTO visits(id) VALUES (1)",
+        ].iter()
+            .map(|cmd| SQL::new(cmd).unwrap())
+            .collect();
+
+        let (drop_triggers, create_triggers) = trigger_statements(&cmds, &relations);
+
+        assert_eq!(
+            drop_triggers.iter().map(|cmd| cmd.to_string()).collect::<Vec<String>>(),
+            vec!["DROP TRIGGER patients_log_2", "DROP TRIGGER patients_log_1"]
+        );
+        assert_eq!(
+            create_triggers.iter().map(|cmd| cmd.to_string()).collect::<Vec<String>>(),
+            vec![
+                "CREATE DEFINER=`root`@`localhost` TRIGGER patients_log_1 AFTER INSERT ON patients FOR EACH ROW SET @count = @count + 1",
+                "CREATE DEFINER=`root`@`localhost` TRIGGER patients_log_2 AFTER INSERT ON patients FOR EACH ROW SET @count = @count + 1",
+            ]
+        );
+    }
+
+    #[test]
+    fn orphan_checks_test_1() {
+        let relations: Vec<Relation> = vec![
+            "CREATE TABLE patients (id int(11), PRIMARY KEY(id))",
+            "CREATE TABLE doctors (id int(11), PRIMARY KEY(id))",
+            "CREATE TABLE visits (id int(11),patient_id int(11), PRIMARY KEY(id), FOREIGN KEY (patient_id) REFERENCES patients(id))",
+            "CREATE TABLE notes (id int(11),visit_id int(11), PRIMARY KEY(id), FOREIGN KEY (visit_id) REFERENCES visits(id))",
+        ].iter()
+            .filter_map(|cmd| Relation::from_sql(&SQL::new(cmd).unwrap()))
+            .collect();
+
+        let checks = |cmds: Vec<&str>| -> Vec<(String, String)> {
+            let cmds: Vec<SQL> = cmds.iter()
+                .map(|cmd| SQL::new(cmd).unwrap())
+                .collect();
+
+            orphan_checks(&cmds, &relations).into_iter()
+                .map(|(foreign_key, query)| (foreign_key, query.to_string()))
+                .collect()
+        };
+
+        //replacing the rows of patients can orphan visits
+        assert_eq!(
+            checks(vec!["DELETE FROM patients", "INSERT INTO patients(id) VALUES (1)"]),
+            vec![(
+                String::from("visits.patient_id -> patients(id)"),
+                String::from("SELECT COUNT(*) FROM visits AS child LEFT JOIN patients AS parent ON child.patient_id = parent.id WHERE parent.id IS NULL AND child.patient_id IS NOT NULL")
+            )]
+        );
+        //replacing the rows of visits can orphan its own rows & notes
+        assert_eq!(
+            checks(vec!["DELETE FROM visits", "INSERT INTO visits(id,patient_id) VALUES (1,1)"]).into_iter().map(|(foreign_key, _query)| foreign_key).collect::<Vec<String>>(),
+            vec![String::from("visits.patient_id -> patients(id)"), String::from("notes.visit_id -> visits(id)")]
+        );
+        assert!(checks(vec!["DELETE FROM doctors"]).is_empty());
+    }
+}
@@ -0,0 +1,209 @@
+use std::{fmt, cmp::Reverse, collections::HashSet};
+
+use serde::{Deserialize, Serialize};
+use time::{Duration, OffsetDateTime};
+
+use super::snapshot::SnapShot;
+
+/// Returns the period (e.g. the year & day) a snapshot was taken in
+type Period = fn(&SnapShot) -> (i32, u32);
+
+/// Struct representing the snapshots kept when snapshots are pruned
+///
+/// A snapshot is kept if any rule keeps it, & every snapshot is kept if no rule is set.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Retention{
+    /// The number of most recent snapshots kept
+    #[serde(default)]
+    pub last: Option<usize>,
+    /// The number of most recent days whose newest snapshot is kept
+    #[serde(default)]
+    pub daily: Option<usize>,
+    /// The number of most recent ISO weeks whose newest snapshot is kept
+    #[serde(default)]
+    pub weekly: Option<usize>,
+    /// The number of most recent months whose newest snapshot is kept
+    #[serde(default)]
+    pub monthly: Option<usize>,
+    /// The maximum age in days of the snapshots kept
+    #[serde(default)]
+    pub max_age: Option<u64>,
+}
+
+impl Retention {
+    /// Returns the retention given by the flags of a `retention` command (e.g. `--last 10 --daily 7 --max-age 90`)
+    ///
+    /// Returns an error for unknown flags & for flags without a number.
+    pub fn from_flags(flags: &str) -> Result<Retention, String> {
+        let mut retention = Retention::default();
+        let mut flags = flags.split_whitespace();
+
+        while let Some(flag) = flags.next() {
+            let count = match flags.next().map(|count| count.parse::<usize>()) {
+                Some(Ok(count)) => count,
+                _ => return Err(format!("{} requires a number", flag)),
+            };
+
+            match flag {
+                "--last" => retention.last = Some(count),
+                "--daily" => retention.daily = Some(count),
+                "--weekly" => retention.weekly = Some(count),
+                "--monthly" => retention.monthly = Some(count),
+                "--max-age" => retention.max_age = Some(count as u64),
+                flag => return Err(format!("unknown flag {}", flag)),
+            }
+        }
+
+        Ok(retention)
+    }
+
+    /// Returns true if no rule is set, so every snapshot is kept.
+    pub fn is_empty(&self) -> bool {
+        *self == Retention::default()
+    }
+
+    /// Returns the snapshots removed by the retention, newest first.
+    ///
+    /// Pinned snapshots are always kept & don't count towards the other rules.
+    ///
+    /// # Arguments
+    ///
+    /// * `snapshots` - The snapshots being pruned, in any order
+    /// * `now` - The time the age of the snapshots is measured from
+    pub fn expired<'a>(&self, snapshots: &'a [SnapShot], now: OffsetDateTime) -> Vec<&'a SnapShot> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+
+        let mut snapshots: Vec<&SnapShot> = snapshots.iter()
+            .filter(|snapshot| !snapshot.pinned)
+            .collect();
+        snapshots.sort_by_key(|snapshot| Reverse(snapshot.time_stamp));
+
+        let mut kept: HashSet<&str> = HashSet::new();
+
+        if let Some(last) = self.last {
+            snapshots.iter()
+                .take(last)
+                .for_each(|snapshot| {kept.insert(&snapshot.path);});
+        }
+
+        let generations: [(Option<usize>, Period); 3] = [
+            (self.daily, |snapshot| (snapshot.time_stamp.year(), snapshot.time_stamp.ordinal() as u32)),
+            (self.weekly, |snapshot| {
+                let (year, week, _) = snapshot.time_stamp.to_iso_week_date();
+                (year, week as u32)
+            }),
+            (self.monthly, |snapshot| (snapshot.time_stamp.year(), snapshot.time_stamp.month() as u32)),
+        ];
+
+        //the newest snapshot of each of the most recent days, weeks or months is kept
+        generations.iter()
+            .filter_map(|(count, generation)| count.map(|count| (count, generation)))
+            .for_each(|(count, generation)| {
+                let mut seen: Vec<(i32, u32)> = Vec::new();
+
+                snapshots.iter()
+                    .for_each(|snapshot| {
+                        let period = generation(snapshot);
+
+                        if seen.len() < count && !seen.contains(&period) {
+                            seen.push(period);
+                            kept.insert(&snapshot.path);
+                        }
+                    });
+            });
+
+        if let Some(max_age) = self.max_age {
+            snapshots.iter()
+                .filter(|snapshot| now - snapshot.time_stamp <= Duration::days(max_age as i64))
+                .for_each(|snapshot| {kept.insert(&snapshot.path);});
+        }
+
+        snapshots.into_iter()
+            .filter(|snapshot| !kept.contains(snapshot.path.as_str()))
+            .collect()
+    }
+}
+
+impl fmt::Display for Retention {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rules: Vec<String> = [
+            self.last.map(|count| format!("last {}", count)),
+            self.daily.map(|count| format!("{} daily", count)),
+            self.weekly.map(|count| format!("{} weekly", count)),
+            self.monthly.map(|count| format!("{} monthly", count)),
+            self.max_age.map(|days| format!("{} day(s) old", days)),
+        ].into_iter()
+            .flatten()
+            .collect();
+
+        match rules.is_empty() {
+            true => write!(f, "keep all"),
+            false => write!(f, "keep {}", rules.join(", ")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(unused_imports)]
+    use serde::Serialize;
+    use time::{OffsetDateTime, macros::datetime};
+
+    use crate::backend::snapshot::SnapShot;
+
+    use super::Retention;
+
+    #[test]
+    fn from_flags_test_1() {
+        assert_eq!(Retention::from_flags("").unwrap(), Retention::default());
+        assert_eq!(
+            Retention::from_flags(" --last 3 --monthly 12 --max-age 30").unwrap(),
+            Retention { last: Some(3), monthly: Some(12), max_age: Some(30), ..Retention::default() }
+        );
+        assert_eq!(Retention::from_flags(" --last 3 --max-age 30").unwrap().to_string(), "keep last 3, 30 day(s) old");
+
+        assert!(Retention::from_flags(" --hourly 3").is_err());
+        assert!(Retention::from_flags(" --daily").is_err());
+        assert!(Retention::from_flags(" --daily x").is_err());
+    }
+
+    #[test]
+    fn expired_test_1() {
+        #[derive(Serialize)]
+        struct Entry<'a>{
+            time_stamp: OffsetDateTime,
+            path: &'a str,
+            pinned: bool,
+        }
+
+        //snapshots are read from catalog entries, since `SnapShot::new` requires their files to exist
+        let snapshot = |time_stamp: OffsetDateTime, path: &str, pinned: bool| -> SnapShot {
+            ron::from_str(&ron::to_string(&Entry { time_stamp, path, pinned }).unwrap()).unwrap()
+        };
+
+        let snapshots = vec![
+            snapshot(datetime!(2023-03-10 12:00 UTC), "a", false),
+            snapshot(datetime!(2023-03-10 08:00 UTC), "b", false),
+            snapshot(datetime!(2023-03-09 12:00 UTC), "c", false),
+            snapshot(datetime!(2023-02-20 12:00 UTC), "d", false),
+            snapshot(datetime!(2023-01-05 12:00 UTC), "e", true),
+            snapshot(datetime!(2023-01-01 12:00 UTC), "f", false),
+        ];
+        let now = datetime!(2023-03-10 13:00 UTC);
+
+        let expired = |retention: Retention| -> Vec<String> {
+            retention.expired(&snapshots, now)
+                .iter()
+                .map(|snapshot| snapshot.path.clone())
+                .collect()
+        };
+
+        assert!(expired(Retention::default()).is_empty());
+        assert_eq!(expired(Retention { last: Some(2), ..Retention::default() }), vec!["c", "d", "f"]);
+        assert_eq!(expired(Retention { daily: Some(2), ..Retention::default() }), vec!["b", "d", "f"]);
+        assert_eq!(expired(Retention { monthly: Some(3), ..Retention::default() }), vec!["b", "c"]);
+        assert_eq!(expired(Retention { last: Some(1), max_age: Some(2), ..Retention::default() }), vec!["d", "f"]);
+    }
+}
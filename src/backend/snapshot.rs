@@ -1,8 +1,10 @@
 use std::{
-    collections::HashSet,
+    collections::{HashSet, BTreeMap},
+    fmt,
     hash::{Hash, Hasher},
     {fs::{File, remove_file}},
-    io::prelude::*, env, fs
+    io::prelude::*, env, fs,
+    sync::atomic::AtomicBool
 };
 
 use ron::{error::SpannedError};
@@ -14,7 +16,7 @@ use serde::{
 
 use time::{OffsetDateTime};
 
-use super::{sql::SQL, data_base::DataBase};
+use super::{retention::Retention, data_base::DataBase, dump::{DumpProgress, DumpError}, compression::{self, Compression}};
 
 /// Enum representing various errors that can occur when working with SnapShots
 #[derive(Debug)]
@@ -29,10 +31,39 @@ pub enum Error {
     FileWriteErr(std::io::Error),
     /// Indicates that there was an error while deserializing a value from a string
     DeSerializationErr(SpannedError),
+    /// Indicates that the snapshot was cancelled before it was saved
+    Cancelled,
+    /// Indicates that the snapshot file no longer matches the checksum taken when it was saved
+    ChecksumMismatch{expected: String, actual: String},
     /// Indicates that there was an error with a message
     Err(String)
 }
 
+/// Enum representing the relations & statements held by a snapshot
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum SnapShotKind {
+    /// Every relation & every row, restored by replacing every relation of the database
+    #[default]
+    Full,
+    /// Every relation without rows, restored by replacing every relation of the database
+    SchemaOnly,
+    /// The rows of every table, restored by replacing the rows of the existing tables
+    DataOnly,
+    /// The given tables & the tables they reference with their rows, restored by replacing these tables only
+    Tables(Vec<String>),
+}
+
+impl fmt::Display for SnapShotKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapShotKind::Full => write!(f, "full"),
+            SnapShotKind::SchemaOnly => write!(f, "schema only"),
+            SnapShotKind::DataOnly => write!(f, "data only"),
+            SnapShotKind::Tables(tables) => write!(f, "tables {}", tables.join(",")),
+        }
+    }
+}
+
 /// Struct representing a single snapshot
 #[derive(Deserialize, Serialize, Eq, Clone)]
 pub struct SnapShot{
@@ -40,6 +71,108 @@ pub struct SnapShot{
     pub time_stamp: OffsetDateTime,
     /// The path to the snapshot file
     pub path: String,
+    /// The sha256 checksum of the snapshot file, or `None` for snapshots saved before checksums were stored
+    #[serde(default)]
+    pub checksum: Option<String>,
+    /// The label given to the snapshot (e.g. `before v2 deploy`)
+    #[serde(default)]
+    pub label: Option<String>,
+    /// The description given to the snapshot
+    #[serde(default)]
+    pub description: Option<String>,
+    /// The host & port of the database the snapshot was taken of
+    #[serde(default)]
+    pub host: Option<String>,
+    /// The name of the database the snapshot was taken of
+    #[serde(default)]
+    pub database: Option<String>,
+    /// The version of the server the snapshot was taken on
+    #[serde(default)]
+    pub server_version: Option<String>,
+    /// The size of the snapshot file in bytes
+    #[serde(default)]
+    pub size: Option<u64>,
+    /// The number of rows of each table, keyed by table name
+    #[serde(default)]
+    pub row_counts: Option<BTreeMap<String, usize>>,
+    /// The relations & statements held by the snapshot
+    #[serde(default)]
+    pub kind: SnapShotKind,
+    /// Whether the snapshot is exempt from pruning
+    #[serde(default)]
+    pub pinned: bool,
+    /// Whether the snapshot was taken automatically before a rollback or a destructive statement
+    #[serde(default)]
+    pub automatic: bool,
+}
+
+/// Struct representing the options of a snapshot being taken
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SnapShotOptions{
+    /// Whether writes to every table are blocked with `FLUSH TABLES WITH READ LOCK` while the snapshot is taken
+    pub read_lock: bool,
+    /// The compression of the snapshot file
+    pub compression: Compression,
+    /// The label given to the snapshot
+    pub label: Option<String>,
+    /// The description given to the snapshot
+    pub description: Option<String>,
+    /// The relations & statements held by the snapshot
+    pub kind: SnapShotKind,
+    /// Whether the snapshot is taken automatically before a rollback or a destructive statement
+    pub automatic: bool,
+}
+
+impl SnapShotOptions {
+    /// Returns the options given by the flags of an `add` command (e.g. `--read-lock --zstd` or `--tables table_1,table_2`)
+    ///
+    /// Returns an error for unknown flags & for flags selecting different kinds of snapshot.
+    pub fn from_flags(flags: &str) -> Result<SnapShotOptions, String> {
+        let mut options = SnapShotOptions::default();
+        let mut flags = flags.split_whitespace();
+
+        while let Some(flag) = flags.next() {
+            let kind = match flag {
+                "--read-lock" => {
+                    options.read_lock = true;
+                    continue;
+                },
+                "--gzip" => {
+                    options.compression = Compression::Gzip;
+                    continue;
+                },
+                "--zstd" => {
+                    options.compression = Compression::Zstd;
+                    continue;
+                },
+                "--schema-only" => SnapShotKind::SchemaOnly,
+                "--data-only" => SnapShotKind::DataOnly,
+                "--tables" => {
+                    let tables: Vec<String> = flags.next()
+                        .unwrap_or_default()
+                        .split(',')
+                        .filter(|table| !table.is_empty())
+                        .map(|table| table.to_string())
+                        .collect();
+
+                    if tables.is_empty() {
+                        return Err(String::from("--tables requires a comma separated list of tables"));
+                    }
+
+                    SnapShotKind::Tables(tables)
+                },
+                flag => return Err(format!("unknown flag {}", flag)),
+            };
+
+            if options.kind != SnapShotKind::Full {
+                return Err(String::from("--schema-only, --data-only & --tables can't be combined"));
+            }
+
+            options.kind = kind;
+        }
+
+        Ok(options)
+    }
 }
 
 impl SnapShot {
@@ -63,14 +196,40 @@ impl SnapShot {
                     }
 
                     path
-                }
+                },
+                checksum: None,
+                label: None,
+                description: None,
+                host: None,
+                database: None,
+                server_version: None,
+                size: None,
+                row_counts: None,
+                kind: SnapShotKind::Full,
+                pinned: false,
+                automatic: false,
             }
         )
     }
-}
 
-impl From<DataBase> for SnapShot {
-    fn from(db: DataBase) -> Self {
+    /// Takes a consistent snapshot of the database and saves it in the `snap_shots` directory
+    ///
+    /// The statements are written to the file as the rows are read, & the file only gets its name once every statement is written,
+    /// so a cancelled or failed snapshot leaves no partial file behind. The sha256 checksum of the saved file is kept in the snapshot.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - The database to take the snapshot of
+    /// * `options` - The options of the snapshot (e.g. read lock & compression)
+    /// * `cancel` - Stops the snapshot once set
+    /// * `progress` - Called with the progress of the snapshot
+    ///
+    /// # Returns
+    ///
+    /// * `Result` - Returns `Ok(SnapShot)` if the snapshot was taken & saved successfully, or `Err(Error)` if there was an error
+    pub fn take<F: FnMut(DumpProgress)>(db: &DataBase, options: &SnapShotOptions, cancel: &AtomicBool, mut progress: F) -> Result<SnapShot, Error> {
+        let timestamp = OffsetDateTime::now_utc();
+
         let mut path = env::current_dir().unwrap();
         path.push("snap_shots");
         let metadata = fs::metadata(&path);
@@ -79,20 +238,104 @@ impl From<DataBase> for SnapShot {
             let _result = fs::create_dir_all(path.as_path());
         }
 
-        let timestamp = OffsetDateTime::now_utc();
+        path.push(format!("snap_shot_{}.sql{}", timestamp.unix_timestamp(), options.compression.extension()));
 
-        path.push(format!("snap_shot_{}.sql", timestamp.unix_timestamp()));
+        //snapshots taken within the same second (e.g. a safety snapshot right after `add`) get a suffix instead of replacing each other
+        let mut suffix: usize = 1;
+        while path.exists() {
+            path.set_file_name(format!("snap_shot_{}_{}.sql{}", timestamp.unix_timestamp(), suffix, options.compression.extension()));
+            suffix += 1;
+        }
 
         let file_path = path.as_path().to_str().unwrap();
-        let _result = SQL::save_to_file(file_path, &db.get_snapshot());
+        let partial_path = format!("{}.partial", file_path);
+
+        let file = File::create(&partial_path).map_err(Error::FileOpenErr)?;
+        let mut file = options.compression.writer(file).map_err(Error::FileWriteErr)?;
+
+        let mut row_counts: BTreeMap<String, usize> = BTreeMap::new();
+
+        let result = db.stream_snapshot(
+            options.read_lock,
+            &options.kind,
+            cancel,
+            |cmds| cmds.iter().try_for_each(|cmd| cmd.write_to(&mut file)),
+            |dump_progress| {
+                //every table reports its rows at least once, even when it is empty
+                if let DumpProgress::Rows { name, rows, .. } = &dump_progress {
+                    row_counts.insert(name.clone(), *rows);
+                }
+                progress(dump_progress);
+            }
+        );
+
+        let checksum = match result.and_then(|_| file.finish().map_err(DumpError::from)) {
+            Ok(checksum) => checksum,
+            Err(err) => {
+                let _result = remove_file(&partial_path);
+
+                return Err(
+                    match err {
+                        DumpError::Cancelled => Error::Cancelled,
+                        DumpError::Write(err) => Error::FileWriteErr(err),
+                        err => Error::Err(format!("failed to take snapshot: {}", err)),
+                    }
+                );
+            },
+        };
+
+        fs::rename(&partial_path, file_path).map_err(Error::FileWriteErr)?;
+
+        Ok(
+            SnapShot{
+                time_stamp: timestamp,
+                path: file_path.to_string(),
+                checksum: Some(checksum),
+                label: options.label.clone(),
+                description: options.description.clone(),
+                host: Some(db.host()),
+                database: Some(db.name().to_string()),
+                server_version: db.server_version(),
+                size: fs::metadata(file_path).ok().map(|metadata| metadata.len()),
+                row_counts: match options.kind {
+                    SnapShotKind::SchemaOnly => None,
+                    _ => Some(row_counts),
+                },
+                kind: options.kind.clone(),
+                pinned: false,
+                automatic: options.automatic,
+            }
+        )
+    }
 
-        SnapShot{
-            time_stamp: timestamp,
-            path: file_path.to_string(),
+    /// Checks that the snapshot file still matches the checksum taken when it was saved
+    ///
+    /// Snapshots saved before checksums were stored are not checked.
+    ///
+    /// # Returns
+    ///
+    /// * `Result` - Returns `Err(Error::ChecksumMismatch)` if the file changed, or `Err(Error::FileReadErr)` if it can't be read
+    pub fn verify(&self) -> Result<(), Error> {
+        let expected = match &self.checksum {
+            Some(checksum) => checksum,
+            None => return Ok(()),
+        };
+
+        let actual = compression::checksum(&self.path).map_err(Error::FileReadErr)?;
+
+        match *expected == actual {
+            true => Ok(()),
+            false => Err(Error::ChecksumMismatch { expected: expected.clone(), actual }),
         }
     }
 }
 
+impl From<DataBase> for SnapShot {
+    fn from(db: DataBase) -> Self {
+        SnapShot::take(&db, &SnapShotOptions::default(), &AtomicBool::new(false), |_| {}).unwrap()
+    }
+}
+
 impl Hash for SnapShot {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.path.hash(state);
@@ -112,6 +355,17 @@ pub struct SnapShotsFile{
     pub name: String,
     /// The collection of snapshots in the file
     pub snap_shots: HashSet<SnapShot>,
+    /// The snapshots kept when the snapshots are pruned
+    #[serde(default)]
+    pub retention: Retention,
+    /// Whether a snapshot is taken automatically before a rollback or a destructive statement
+    #[serde(default = "enabled")]
+    pub safety_snapshots: bool,
+}
+
+/// Returns `true`, the default of settings enabled unless turned off
+fn enabled() -> bool {
+    true
 }
 
 
@@ -218,6 +472,110 @@ impl SnapShotsFile{
 
      let _result = self.save();
     }
+
+    /// Takes an automatic snapshot of the database & adds it to the file, if safety snapshots are enabled
+    ///
+    /// Safety snapshots don't prune the file, so the snapshot about to be restored is never removed before it is read.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - The database to take the snapshot of
+    /// * `label` - The label of the snapshot (e.g. `before rollback`)
+    /// * `description` - The description of the snapshot (e.g. the destructive statement)
+    ///
+    /// # Returns
+    ///
+    /// * `Result` - Returns `Ok(None)` if safety snapshots are disabled, or `Err(Error)` if the snapshot failed
+    pub fn safety_snapshot(&mut self, db: &DataBase, label: &str, description: &str) -> Result<Option<SnapShot>, Error> {
+        if !self.safety_snapshots {
+            return Ok(None);
+        }
+
+        let options = SnapShotOptions {
+            label: Some(label.to_string()),
+            description: Some(description.to_string()),
+            automatic: true,
+            ..SnapShotOptions::default()
+        };
+
+        let snapshot = SnapShot::take(db, &options, &AtomicBool::new(false), |_| {})?;
+        self.add_snapshot(snapshot.clone());
+
+        Ok(Some(snapshot))
+    }
+
+    /// Pins or unpins the snapshot stored at a path & saves the file, leaving every other snapshot untouched
+    ///
+    /// The snapshots are read from the file first, so snapshots added through another `SnapShotsFile` are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the snapshot
+    /// * `pinned` - Whether the snapshot is pinned
+    ///
+    /// # Returns
+    ///
+    /// * `Result` - Returns `Ok(false)` if the file has no snapshot at `path`, or `Err(Error)` if the file couldn't be saved
+    pub fn set_pinned(&mut self, path: &str, pinned: bool) -> Result<bool, Error> {
+        if let Ok(file) = SnapShotsFile::open(&self.name) {
+            self.snap_shots = file.snap_shots;
+        }
+
+        let mut snapshot = match self.snap_shots.iter().find(|snapshot| snapshot.path == path) {
+            Some(snapshot) => snapshot.clone(),
+            None => return Ok(false),
+        };
+
+        snapshot.pinned = pinned;
+        self.snap_shots.replace(snapshot);
+
+        self.save().map(|_| true)
+    }
+
+    /// Returns the most recent automatic snapshot of the file, if any
+    pub fn last_automatic(&self) -> Option<&SnapShot> {
+        self.snap_shots.iter()
+            .filter(|snapshot| snapshot.automatic)
+            .max_by_key(|snapshot| snapshot.time_stamp)
+    }
+
+    /// Returns the snapshots the retention of the file removes when pruned, newest first
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The time the age of the snapshots is measured from
+    pub fn expired(&self, now: OffsetDateTime) -> Vec<SnapShot> {
+        let snapshots: Vec<SnapShot> = self.snap_shots.iter().cloned().collect();
+
+        self.retention.expired(&snapshots, now)
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Removes the snapshots expired by the retention of the file along with their files, & saves the file
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The time the age of the snapshots is measured from
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<SnapShot>` - The removed snapshots, newest first
+    pub fn prune(&mut self, now: OffsetDateTime) -> Vec<SnapShot> {
+        let expired = self.expired(now);
+
+        if !expired.is_empty() {
+            let snapshots: Vec<SnapShot> = self.snap_shots.iter()
+                .filter(|snapshot| !expired.contains(snapshot))
+                .cloned()
+                .collect();
+
+            self.replace_snapshots(&snapshots);
+        }
+
+        expired
+    }
 }
 
 impl Default for SnapShotsFile{
@@ -233,6 +591,8 @@ impl Default for SnapShotsFile{
                     let snap_shot_file = Self {
                         name: String::from(PATH),
                         snap_shots: HashSet::new(),
+                        retention: Retention::default(),
+                        safety_snapshots: true,
                     };
                     
                     let _result = snap_shot_file.save();
@@ -246,3 +606,137 @@ impl Default for SnapShotsFile{
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    #![allow(unused_imports)]
+    use serde::Serialize;
+    use time::{OffsetDateTime, macros::datetime};
+
+    use super::{SnapShot, SnapShotOptions, SnapShotKind, SnapShotsFile};
+    use crate::backend::retention::Retention;
+    use crate::backend::compression::Compression;
+    use crate::test_tools::file_env::FileEnv;
+
+    #[derive(Serialize)]
+    struct Entry<'a>{
+        time_stamp: OffsetDateTime,
+        path: &'a str,
+        automatic: bool,
+    }
+
+    //snapshots are read from catalog entries, since `SnapShot::new` requires their files to exist
+    fn snapshot(time_stamp: OffsetDateTime, path: &str, automatic: bool) -> SnapShot {
+        ron::from_str(&ron::to_string(&Entry { time_stamp, path, automatic }).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn deserialize_test_1() {
+        //the entries of catalogs saved before metadata was stored
+        #[derive(Serialize)]
+        struct LegacySnapShot{
+            time_stamp: OffsetDateTime,
+            path: String,
+        }
+
+        let legacy = ron::to_string(&LegacySnapShot{ time_stamp: datetime!(2023-01-02 03:04:05 UTC), path: String::from("snap_shots/snap_shot_1.sql") }).unwrap();
+
+        let snapshot: SnapShot = ron::from_str(&legacy).unwrap();
+
+        assert_eq!(snapshot.time_stamp, datetime!(2023-01-02 03:04:05 UTC));
+        assert_eq!(snapshot.path, "snap_shots/snap_shot_1.sql");
+        assert_eq!(snapshot.checksum, None);
+        assert_eq!(snapshot.label, None);
+        assert_eq!(snapshot.row_counts, None);
+        assert_eq!(snapshot.kind, SnapShotKind::Full);
+        assert!(!snapshot.pinned);
+        assert!(!snapshot.automatic);
+        assert!(snapshot.verify().is_ok());
+    }
+
+    #[test]
+    fn deserialize_test_2() {
+        //catalogs saved before retention & safety snapshots were configurable
+        let file: SnapShotsFile = ron::from_str("(name: \"snap_shots.ron\", snap_shots: [])").unwrap();
+
+        assert_eq!(file.retention, Retention::default());
+        assert!(file.safety_snapshots);
+    }
+
+    #[test]
+    fn from_flags_test_1() {
+        assert_eq!(SnapShotOptions::from_flags("").unwrap(), SnapShotOptions::default());
+
+        let options = SnapShotOptions::from_flags(" --read-lock --zstd").unwrap();
+        assert!(options.read_lock);
+        assert_eq!(options.compression, Compression::Zstd);
+
+        assert!(SnapShotOptions::from_flags(" --bzip2").is_err());
+
+        assert_eq!(SnapShotOptions::from_flags(" --schema-only").unwrap().kind, SnapShotKind::SchemaOnly);
+        assert_eq!(SnapShotOptions::from_flags(" --data-only --gzip").unwrap().kind, SnapShotKind::DataOnly);
+        assert_eq!(
+            SnapShotOptions::from_flags(" --tables patients,visits --zstd").unwrap(),
+            SnapShotOptions {
+                compression: Compression::Zstd,
+                kind: SnapShotKind::Tables(vec![String::from("patients"), String::from("visits")]),
+                ..SnapShotOptions::default()
+            }
+        );
+
+        assert!(SnapShotOptions::from_flags(" --tables").is_err());
+        assert!(SnapShotOptions::from_flags(" --schema-only --data-only").is_err());
+    }
+
+    #[test]
+    fn last_automatic_test_1() {
+        //safety snapshots added through another handle of the catalog (e.g. before a console statement) are found once it is reloaded
+        let file_path = "snapshot_last_automatic_test_1.ron";
+        let _file = FileEnv::new(
+            file_path,
+            &format!(
+                "(name: \"{}\", snap_shots: [{}, {}])",
+                file_path,
+                ron::to_string(&Entry { time_stamp: datetime!(2023-01-01 00:00 UTC), path: "a", automatic: true }).unwrap(),
+                ron::to_string(&Entry { time_stamp: datetime!(2023-01-03 00:00 UTC), path: "b", automatic: false }).unwrap()
+            )
+        );
+
+        let mut catalog = SnapShotsFile::open(file_path).unwrap();
+        assert_eq!(catalog.last_automatic().unwrap().path, "a");
+
+        SnapShotsFile::open(file_path).unwrap().add_snapshot(snapshot(datetime!(2023-01-02 00:00 UTC), "c", true));
+        assert_eq!(catalog.last_automatic().unwrap().path, "a");
+
+        catalog.update();
+        assert_eq!(catalog.last_automatic().unwrap().path, "c");
+    }
+
+    #[test]
+    fn set_pinned_test_1() {
+        //only the pinned snapshot changes, including snapshots added through another handle of the catalog
+        let file_path = "snapshot_set_pinned_test_1.ron";
+        let _file = FileEnv::new(
+            file_path,
+            &format!(
+                "(name: \"{}\", snap_shots: [{}])",
+                file_path,
+                ron::to_string(&Entry { time_stamp: datetime!(2023-01-01 00:00 UTC), path: "a", automatic: false }).unwrap()
+            )
+        );
+
+        let mut catalog = SnapShotsFile::open(file_path).unwrap();
+
+        SnapShotsFile::open(file_path).unwrap().add_snapshot(snapshot(datetime!(2023-01-02 00:00 UTC), "b", true));
+
+        assert!(catalog.set_pinned("a", true).unwrap());
+        assert!(!catalog.set_pinned("c", true).unwrap());
+
+        let catalog = SnapShotsFile::open(file_path).unwrap();
+        let pinned = |path: &str| catalog.snap_shots.iter().find(|snapshot| snapshot.path == path).map(|snapshot| snapshot.pinned);
+
+        assert_eq!(catalog.snap_shots.len(), 2);
+        assert_eq!(pinned("a"), Some(true));
+        assert_eq!(pinned("b"), Some(false));
+    }
+}
@@ -0,0 +1,386 @@
+use std::{fmt, collections::BTreeMap, sync::atomic::AtomicBool};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::{sql::SQL, data_base::DataBase, dump::DumpError, snapshot::SnapShotKind, relation::{Relation, diff::{diff as schema_diff, SchemaDiff}}};
+
+/// The values of a row, keyed by column name. Values are SQL literals (e.g. `'Doe'`, `NULL`).
+pub type RowValues = BTreeMap<String, String>;
+
+/// SnapShotState struct defines the schema & rows held by a snapshot or by the live database
+#[derive(Clone, Debug, Default)]
+pub struct SnapShotState{
+    /// The tables & views defined by the `CREATE` statements.
+    pub relations: Vec<Relation>,
+    /// The rows inserted into each table, keyed by table name.
+    pub rows: BTreeMap<String, Vec<RowValues>>,
+}
+
+impl SnapShotState {
+    /// Returns the state defined by the statements of a snapshot.
+    ///
+    /// Rows are read from `INSERT INTO table(columns) VALUES (...),(...)` statements, & any statement other than `CREATE` & `INSERT` is ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `cmds` - The statements of the snapshot
+    pub fn from_sql(cmds: &[SQL]) -> SnapShotState {
+        lazy_static!{
+            static ref INSERT_VALUES : Regex = Regex::new("^(?is)INSERT\\s+(?:IGNORE\\s+)?INTO\\s+`?([a-zA-Z0-9_$]+)`?\\s*(?:\\(([^)]*)\\))?\\s*VALUES\\s*(\\(.*\\))$").unwrap();
+        };
+
+        let relations: Vec<Relation> = cmds.iter()
+            .filter_map(Relation::from_sql)
+            .collect();
+
+        let mut rows: BTreeMap<String, Vec<RowValues>> = BTreeMap::new();
+
+        cmds.iter()
+            .filter_map(|cmd| match cmd {
+                SQL::Insert(qml) => INSERT_VALUES.captures(qml),
+                _ => None,
+            })
+            .for_each(|captures| {
+                let table = captures.get(1).unwrap().as_str();
+
+                //statements without a column list insert every column of the table in order
+                let columns: Vec<String> = match captures.get(2) {
+                    Some(columns) => columns.as_str()
+                        .split(',')
+                        .map(|column| column.trim().trim_matches('`').to_string())
+                        .collect(),
+                    None => match relations.iter().find(|relation| relation.name() == table) {
+                        Some(Relation::Table(table)) => table.attributes.iter().map(|attr| attr.name.clone()).collect(),
+                        _ => return,
+                    },
+                };
+
+                rows.entry(table.to_string())
+                    .or_default()
+                    .extend(
+                        split_values(captures.get(3).unwrap().as_str())
+                            .into_iter()
+                            .map(|values| columns.iter().cloned().zip(values).collect::<RowValues>())
+                    );
+            });
+
+        SnapShotState { relations, rows }
+    }
+
+    /// Returns the current state of a database, read the same way a snapshot is taken.
+    ///
+    /// Returns Error if there is a failure to connect or to read the database
+    pub fn from_db(db: &DataBase) -> Result<SnapShotState, DumpError> {
+        let mut cmds: Vec<SQL> = Vec::new();
+
+        db.stream_snapshot(
+            false,
+            &SnapShotKind::Full,
+            &AtomicBool::new(false),
+            |batch| {
+                cmds.extend_from_slice(batch);
+                Ok(())
+            },
+            |_| {}
+        )?;
+
+        Ok(SnapShotState::from_sql(&cmds))
+    }
+
+    /// Returns the names of the primary key columns of a table in key order, or `None` if the table has no primary key or isn't defined by the state.
+    fn primary_key(&self, table: &str) -> Option<Vec<&str>> {
+        match self.relations.iter().find(|relation| relation.name() == table) {
+            Some(Relation::Table(table)) if !table.primary_key.is_empty() => Some(table.primary_key_names()),
+            _ => None,
+        }
+    }
+}
+
+/// Splits the rows of the `VALUES` clause of an `INSERT` statement (e.g. `(1,'Doe'),(2,'Roe')`) into the values of each row.
+///
+/// Commas & parentheses inside quoted strings, including backslash escaped quotes, are not split on.
+fn split_values(rows: &str) -> Vec<Vec<String>> {
+    let mut split: Vec<Vec<String>> = Vec::new();
+    let mut row: Vec<String> = Vec::new();
+    let mut value = String::new();
+    let mut depth: usize = 0;
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+
+    for ch in rows.chars() {
+        match (quote, ch) {
+            (Some(_), _) if escaped => escaped = false,
+            (Some(_), '\\') => escaped = true,
+            (Some(end), _) if ch == end => quote = None,
+            (Some(_), _) => {},
+            (None, '\'') | (None, '"') => quote = Some(ch),
+            (None, '(') => {
+                depth += 1;
+                if depth == 1 {
+                    continue;
+                }
+            },
+            (None, ')') => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    row.push(value.trim().to_string());
+                    value.clear();
+                    split.push(std::mem::take(&mut row));
+                    continue;
+                }
+            },
+            (None, ',') if depth == 1 => {
+                row.push(value.trim().to_string());
+                value.clear();
+                continue;
+            },
+            //separators between rows
+            (None, _) if depth == 0 => continue,
+            _ => {},
+        }
+        value.push(ch);
+    }
+
+    split
+}
+
+/// A single difference between the rows of two states
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RowChange{
+    /// A row that only exists in the new state.
+    Inserted{table: String, key: String, values: RowValues},
+    /// A row that only exists in the old state.
+    Deleted{table: String, key: String, values: RowValues},
+    /// A row whose primary key exists in both states, with the old & new value of each changed column.
+    Updated{table: String, key: String, columns: Vec<(String, Option<String>, Option<String>)>},
+}
+
+impl fmt::Display for RowChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let values = |values: &RowValues| -> String {
+            values.iter()
+                .map(|(column, value)| format!("{}={}", column, value))
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+
+        match self {
+            RowChange::Inserted { table, key, values: row } => write!(f, "+ {}({}): {}", table, key, values(row)),
+            RowChange::Deleted { table, key, values: row } => write!(f, "- {}({}): {}", table, key, values(row)),
+            RowChange::Updated { table, key, columns } => write!(
+                f,
+                "~ {}({}): {}",
+                table,
+                key,
+                columns.iter()
+                    .map(|(column, old, new)| format!("{} {} -> {}", column, old.as_deref().unwrap_or("DEFAULT"), new.as_deref().unwrap_or("DEFAULT")))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+/// SnapShotDiff defines the schema & row differences between two states
+#[derive(Clone, Debug, Default)]
+pub struct SnapShotDiff{
+    /// The changes turning the old schema into the new schema.
+    pub schema: SchemaDiff,
+    /// The inserted, deleted & updated rows, ordered by table & key.
+    pub rows: Vec<RowChange>,
+}
+
+impl SnapShotDiff {
+    /// Returns true if both states hold the same schema & rows.
+    pub fn is_empty(&self) -> bool {
+        self.schema.is_empty() && self.rows.is_empty()
+    }
+
+    /// Returns the number of inserted, deleted & updated rows.
+    pub fn counts(&self) -> (usize, usize, usize) {
+        self.rows.iter()
+            .fold((0, 0, 0), |(inserted, deleted, updated), change| match change {
+                RowChange::Inserted { .. } => (inserted + 1, deleted, updated),
+                RowChange::Deleted { .. } => (inserted, deleted + 1, updated),
+                RowChange::Updated { .. } => (inserted, deleted, updated + 1),
+            })
+    }
+}
+
+/// Returns the differences turning the `old` state into the `new` state.
+///
+/// Rows are matched by the primary key of their table, taken from the new schema or else the old schema. Rows of tables without a primary key
+/// are matched by all of their values, so a changed row is reported as a deleted & an inserted row.
+/// Foreign keys between tables referencing each other are added by `ALTER TABLE` statements in snapshots, so they are not compared.
+///
+/// # Arguments
+///
+/// * `old` - The state being compared from (e.g. an older snapshot)
+/// * `new` - The state being compared to (e.g. a newer snapshot or the live database)
+pub fn diff(old: &SnapShotState, new: &SnapShotState) -> SnapShotDiff {
+    let mut tables: Vec<&String> = old.rows.keys()
+        .chain(new.rows.keys())
+        .collect();
+    tables.sort();
+    tables.dedup();
+
+    let rows: Vec<RowChange> = tables.into_iter()
+        .flat_map(|table| {
+            let primary_key = new.primary_key(table).or_else(|| old.primary_key(table));
+
+            let old_rows = keyed_rows(old.rows.get(table), primary_key.as_deref());
+            let new_rows = keyed_rows(new.rows.get(table), primary_key.as_deref());
+
+            let mut changes: Vec<RowChange> = Vec::new();
+
+            old_rows.iter()
+                .for_each(|(id, (key, old_values))| match new_rows.get(id) {
+                    None => changes.push(RowChange::Deleted { table: table.clone(), key: key.clone(), values: old_values.clone() }),
+                    Some((_, new_values)) => {
+                        let mut columns: Vec<&String> = old_values.keys().chain(new_values.keys()).collect();
+                        columns.sort();
+                        columns.dedup();
+
+                        let columns: Vec<(String, Option<String>, Option<String>)> = columns.into_iter()
+                            .filter(|column| old_values.get(*column) != new_values.get(*column))
+                            .map(|column| (column.clone(), old_values.get(column).cloned(), new_values.get(column).cloned()))
+                            .collect();
+
+                        if !columns.is_empty() {
+                            changes.push(RowChange::Updated { table: table.clone(), key: key.clone(), columns });
+                        }
+                    },
+                });
+
+            new_rows.iter()
+                .filter(|(id, _)| !old_rows.contains_key(*id))
+                .for_each(|(_, (key, new_values))| changes.push(RowChange::Inserted { table: table.clone(), key: key.clone(), values: new_values.clone() }));
+
+            changes
+        })
+        .collect();
+
+    SnapShotDiff { schema: schema_diff(&old.relations, &new.relations), rows }
+}
+
+/// Returns the rows of a table keyed by an identifier unique within the table, with the key shown for the row.
+///
+/// Rows are identified by the values of every primary key column (e.g. `order_id=1, product_id=2`), or by all of their values & their occurrence
+/// for tables without a primary key.
+fn keyed_rows(rows: Option<&Vec<RowValues>>, primary_key: Option<&[&str]>) -> BTreeMap<String, (String, RowValues)> {
+    let mut keyed: BTreeMap<String, (String, RowValues)> = BTreeMap::new();
+
+    rows.into_iter()
+        .flatten()
+        .for_each(|row| {
+            let primary_key: Option<Vec<String>> = primary_key.and_then(|primary_key| {
+                primary_key.iter()
+                    .map(|column| row.get(*column).map(|value| format!("{}={}", column, value)))
+                    .collect()
+            });
+
+            let key = match primary_key {
+                Some(primary_key) => primary_key.join(", "),
+                None => row.values().cloned().collect::<Vec<String>>().join(","),
+            };
+
+            let mut id = key.clone();
+            let mut occurrence: usize = 1;
+            while keyed.contains_key(&id) {
+                occurrence += 1;
+                id = format!("{}#{}", key, occurrence);
+            }
+
+            keyed.insert(id, (key, row.clone()));
+        });
+
+    keyed
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(unused_imports)]
+    use std::collections::BTreeMap;
+
+    use crate::backend::sql::SQL;
+
+    use super::{SnapShotState, RowChange, diff, split_values};
+
+    #[test]
+    fn split_values_test_1() {
+        assert_eq!(
+            split_values("(1,'Doe'),(2,'R,o\\'e (2)'), (3,NULL)"),
+            vec![
+                vec![String::from("1"), String::from("'Doe'")],
+                vec![String::from("2"), String::from("'R,o\\'e (2)'")],
+                vec![String::from("3"), String::from("NULL")],
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_test_1() {
+        let state = |cmds: &[&str]| -> SnapShotState {
+            SnapShotState::from_sql(
+                &cmds.iter()
+                    .map(|cmd| SQL::new(cmd).unwrap())
+                    .collect::<Vec<SQL>>()
+            )
+        };
+
+        let old = state(&[
+            "CREATE TABLE patients (id int(11),name varchar(255), PRIMARY KEY(id))",
+            "CREATE TABLE logs (message varchar(255))",
+            "INSERT INTO patients(id,name) VALUES (1,'Doe'),(2,'Roe')",
+            "INSERT INTO logs(message) VALUES ('a'),('a')",
+        ]);
+        let new = state(&[
+            "CREATE TABLE patients (id int(11),name varchar(255),age int(11), PRIMARY KEY(id))",
+            "CREATE TABLE logs (message varchar(255))",
+            "INSERT INTO patients(id,name,age) VALUES (2,'Poe',NULL),(3,'Moe',40)",
+            "INSERT INTO logs(message) VALUES ('a')",
+        ]);
+
+        let changes = diff(&old, &new);
+
+        assert_eq!(changes.schema.changes.iter().map(|change| change.to_string()).collect::<Vec<String>>(), vec!["+ patients.age int(11)"]);
+        assert_eq!(
+            changes.rows.iter().map(|change| change.to_string()).collect::<Vec<String>>(),
+            vec![
+                "- logs('a'): message='a'",
+                "- patients(id=1): id=1, name='Doe'",
+                "~ patients(id=2): age DEFAULT -> NULL, name 'Roe' -> 'Poe'",
+                "+ patients(id=3): age=40, id=3, name='Moe'",
+            ]
+        );
+        assert_eq!(changes.counts(), (1, 2, 1));
+    }
+
+    #[test]
+    fn diff_test_2() {
+        //rows of composite primary keys are matched on every key column, regardless of their order
+        let state = |cmds: &[&str]| -> SnapShotState {
+            SnapShotState::from_sql(
+                &cmds.iter()
+                    .map(|cmd| SQL::new(cmd).unwrap())
+                    .collect::<Vec<SQL>>()
+            )
+        };
+
+        let old = state(&[
+            "CREATE TABLE order_items (order_id int(11),product_id int(11),note varchar(255), PRIMARY KEY(order_id,product_id))",
+            "INSERT INTO order_items(order_id,product_id,note) VALUES (1,1,'a'),(1,2,'b'),(2,1,'c')",
+        ]);
+        let new = state(&[
+            "CREATE TABLE order_items (order_id int(11),product_id int(11),note varchar(255), PRIMARY KEY(order_id,product_id))",
+            "INSERT INTO order_items(order_id,product_id,note) VALUES (2,1,'c'),(1,2,'B'),(1,1,'a')",
+        ]);
+
+        assert_eq!(
+            diff(&old, &new).rows.iter().map(|change| change.to_string()).collect::<Vec<String>>(),
+            vec!["~ order_items(order_id=1, product_id=2): note 'b' -> 'B'"]
+        );
+        assert!(diff(&old, &old).is_empty());
+    }
+}
@@ -1,10 +1,11 @@
-use std::{fmt, ops::{Deref, DerefMut}, fs::File, io::{Read, Write}};
+use std::{fmt, ops::{Deref, DerefMut}, fs::File, io::{Read, Write, BufWriter}};
 
 use mysql::{Error, Row};
 use regex::Regex;
 use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
 
-use super::data_base::{DataBase, DatabaseExecute, DatabaseError};
+use super::{data_base::{DataBase, DatabaseExecute, DatabaseError}, compression};
 
 mod file_insertion;
 
@@ -96,7 +97,8 @@ impl From<&DDL> for SQLLanguage{
 }
 
 /// Represents a data query language (DQL) SQL command.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(transparent)]
 pub struct QDL(pub String);
 impl Deref for QDL {
     type Target = String;
@@ -325,15 +327,15 @@ enum ParseMode{
 }
 
 impl ParseMode {
-    pub fn parse(self, buffer: &mut Vec<char>, cmds: &mut Vec<SQL>, ch: char) -> Self {
+    pub fn parse(self, buffer: &mut Vec<char>, cmds: &mut Vec<SQL>, delimiter: &mut String, ch: char) -> Self {
         match self{
-            ParseMode::Regular => self.regular_parse(buffer, cmds, ch),
+            ParseMode::Regular => self.regular_parse(buffer, cmds, delimiter, ch),
             ParseMode::Comment => self.comment_parse(ch),
             ParseMode::String(_) => self.string_parse(buffer, ch),
         }
     }
 
-    fn regular_parse(self, buffer: &mut Vec<char>, cmds: &mut Vec<SQL>, ch: char) -> Self{
+    fn regular_parse(self, buffer: &mut Vec<char>, cmds: &mut Vec<SQL>, delimiter: &mut String, ch: char) -> Self{
         match ch {
             //new string
             '\"' |
@@ -355,12 +357,10 @@ impl ParseMode {
                     }
                 }
             },
-            //end of line
-            ';' => {
-                match SQL::new(&buffer.drain(..).collect::<String>()) {
-                    Ok(val) => cmds.push(val),
-                    Err(err) => log::error!("Error - {:?}", err),
-                }
+            //end of delimiter statement
+            '\n' if ParseMode::delimiter_statement(buffer).is_some() => {
+                *delimiter = ParseMode::delimiter_statement(buffer).unwrap();
+                buffer.clear();
                 self
             },
             //new line
@@ -380,11 +380,46 @@ impl ParseMode {
             //regular char
             _=> {
                 buffer.push(ch);
+
+                //check for end of line
+                let delimiter: Vec<char> = delimiter.chars().collect();
+                if buffer.ends_with(&delimiter) && !ParseMode::is_delimiter_statement(buffer) {
+                    buffer.truncate(buffer.len() - delimiter.len());
+
+                    match SQL::new(buffer.drain(..).collect::<String>().trim_end()) {
+                        Ok(val) => cmds.push(val),
+                        Err(err) => log::error!("Error - {:?}", err),
+                    }
+                }
                 self
             }
         }
     }
 
+    /// Returns true if the buffer is a `DELIMITER` statement, which ends at the end of the line rather than the current delimiter
+    fn is_delimiter_statement(buffer: &[char]) -> bool {
+        const KEYWORD: &str = "DELIMITER ";
+
+        buffer.len() > KEYWORD.len() &&
+            buffer.iter()
+                .zip(KEYWORD.chars())
+                .all(|(ch, keyword)| ch.to_ascii_uppercase() == keyword)
+    }
+
+    /// Returns the new delimiter defined by a `DELIMITER` statement in the buffer
+    fn delimiter_statement(buffer: &[char]) -> Option<String> {
+        if !ParseMode::is_delimiter_statement(buffer) {
+            return None
+        }
+
+        let delimiter: String = buffer.iter().collect::<String>()[10..].trim().to_string();
+
+        match delimiter.is_empty() || delimiter.contains(' ') {
+            true => None,
+            false => Some(delimiter),
+        }
+    }
+
     fn comment_parse(self, ch: char) -> Self{
         match ch {
             '\n' => ParseMode::Regular,
@@ -460,6 +495,7 @@ impl SQL {
 
         //Querying Data Language
         SQL_Parse!(Select, QDL, "^[Ss][Ee][Ll][Ee][Cc][Tt] .+", query);
+        SQL_Parse!(Select, QDL, "^[Ww][Ii][Tt][Hh] .+", query);
 
         //Query Manipulation Language
         SQL_Parse!(Insert, QML, "^[Ii][Nn][Ss][Ee][Rr][Tt] .+", query);
@@ -547,6 +583,31 @@ impl SQL {
         }
     }
 
+    /// Returns `false` if the command defines a stored program (procedure, function, trigger or event), as these must be sent through the text protocol rather than as a prepared statement.
+    pub fn is_preparable(&self) -> bool {
+        lazy_static!{
+            static ref STORED_PROGRAM : Regex = Regex::new("^(?i)(CREATE|ALTER|DROP)\\s+(DEFINER\\s*=\\s*\\S+\\s+)?(PROCEDURE|FUNCTION|TRIGGER|EVENT)\\s").unwrap();
+        };
+
+        match self.ddl() {
+            Some(ddl) => !STORED_PROGRAM.is_match(ddl),
+            None => true,
+        }
+    }
+
+    /// Returns `true` if the command can lose data: `DROP`, `TRUNCATE`, `DELETE` & `UPDATE` commands, & `ALTER` commands dropping part of a relation (e.g. `ALTER TABLE t DROP COLUMN c`).
+    pub fn is_destructive(&self) -> bool {
+        lazy_static!{
+            static ref ALTER_DROP : Regex = Regex::new("(?i)\\bDROP\\b").unwrap();
+        };
+
+        match self {
+            SQL::Drop(_) | SQL::Truncate(_) | SQL::Delete(_) | SQL::Update(_) => true,
+            SQL::Alter(ddl) => ALTER_DROP.is_match(ddl),
+            _ => false,
+        }
+    }
+
     /// Returns a enum of SQL language type
     pub fn get_language(&self) -> SQLLanguage {
         match &self {
@@ -608,7 +669,8 @@ impl SQL {
     /// 
     /// This function returns a `std::io::Error` if the file cannot be loaded
     pub fn from_file(file_path: &str) -> Result<Vec<SQL>, std::io::Error> {
-        let mut file: File = File::open(file_path)?;
+        //gzip & zstd compressed files are decompressed as they are read
+        let mut file = compression::open(file_path)?;
 
         const BUFFER_SIZE: usize = 100;
 
@@ -618,11 +680,18 @@ impl SQL {
         let mut cmd : Vec<char> = Vec::new();
 
         let mut results: Vec<SQL> = Vec::new();
+
+        let mut delimiter = String::from(";");
         
         loop {
             match file.read(&mut buffer) {
                 Ok(0) => {
-                    match SQL::new(&cmd.clone().into_iter().collect::<String>()) {
+                    //close a trailing DELIMITER statement
+                    parse_mode.parse(&mut cmd, &mut results, &mut delimiter, '\n');
+                    if cmd.is_empty() {
+                        break;
+                    }
+                    match SQL::new(cmd.clone().into_iter().collect::<String>().trim_end()) {
                         Ok(val) => results.push(val),
                         Err(err) => log::error!("Error - {:?}", err),
                     }
@@ -630,7 +699,7 @@ impl SQL {
                 },
                 Ok(n) => {
                     for ch in buffer[..n].iter() {
-                        parse_mode = parse_mode.parse(&mut cmd, &mut results, *ch as char);
+                        parse_mode = parse_mode.parse(&mut cmd, &mut results, &mut delimiter, *ch as char);
                     }
                 }
                 Err(err) => {
@@ -652,16 +721,39 @@ impl SQL {
     /// 
     /// This function return `std::io::Error` if the function fails to create a file with given parameters
     pub fn save_to_file(file_path: &str, queries: &Vec<SQL>) -> Result<(), std::io::Error> {
-        let mut file: File = File::create(file_path)?;
-
-        let mut content = String::new();
+        let mut file = BufWriter::new(File::create(file_path)?);
 
-        queries.iter()
-            .for_each(|query| content.push_str(&format!("{};\n", query.to_string())));
+        for query in queries {
+            query.write_to(&mut file)?;
+        }
 
-        file.write(content.as_bytes())?;
+        file.flush()
+    }
 
-        Ok(())
+    /// Writes the statement to a sql file, in the format read by `from_file`
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The sql file being written
+    ///
+    /// # Errors
+    ///
+    /// This function return `std::io::Error` if the function fails to write to `writer`
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> Result<(), std::io::Error> {
+        let query = self.to_string();
+
+        //stored program bodies contain ';' & must be wrapped in a delimiter that doesn't appear in the statement
+        match self.is_preparable() {
+            true => writeln!(writer, "{};", query),
+            false => {
+                let delimiter = (2..)
+                    .map(|length| "$".repeat(length))
+                    .find(|delimiter| !query.contains(delimiter.as_str()))
+                    .unwrap();
+
+                writeln!(writer, "DELIMITER {}\n{}{}\nDELIMITER ;", delimiter, query, delimiter)
+            },
+        }
     }
 }
 
@@ -782,6 +874,81 @@ mod tests{
         );
     }
 
+    #[test]
+    fn file_parsing_delimiter() {
+        let file_path = "file_parsing_delimiter.sql";
+        let _file = FileEnv::new(
+            file_path,
+            "
+            DELIMITER $$
+            CREATE PROCEDURE add_tag(IN colour CHAR(6))
+            BEGIN
+                INSERT INTO tag (colour) VALUES (colour);
+                SELECT LAST_INSERT_ID();
+            END$$
+            DELIMITER ;
+
+            INSERT INTO tag (colour) VALUES (\"ffffff\");
+            "
+        );
+
+        let actual = SQL::from_file(file_path);
+        let expected = vec![
+            SQL::new("CREATE PROCEDURE add_tag(IN colour CHAR(6)) BEGIN INSERT INTO tag (colour) VALUES (colour); SELECT LAST_INSERT_ID(); END").unwrap(),
+            SQL::new("INSERT INTO tag (colour) VALUES (\"ffffff\")").unwrap(),
+        ];
+        assert_eq!(
+            actual.unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn file_saving_delimiter() {
+        let file_path = "file_saving_delimiter.sql";
+        let _file = FileEnv::new(file_path, "");
+
+        let expected = vec![
+            SQL::new("CREATE FUNCTION tag_count() RETURNS int READS SQL DATA BEGIN DECLARE total INT; SELECT COUNT(*) INTO total FROM tag; RETURN total; END").unwrap(),
+            SQL::new("INSERT INTO tag (colour) VALUES (\"ffffff\")").unwrap(),
+        ];
+
+        SQL::save_to_file(file_path, &expected).unwrap();
+
+        assert_eq!(
+            SQL::from_file(file_path).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn file_saving_delimiter_2() {
+        //';' & '$$' in values & stored program bodies don't end a statement early
+        let file_path = "file_saving_delimiter_2.sql";
+        let _file = FileEnv::new(file_path, "");
+
+        let expected = vec![
+            SQL::new("INSERT INTO tag (colour, symbol) VALUES (\"ffffff\", \"a;b$$c\")").unwrap(),
+            SQL::new("CREATE PROCEDURE price() BEGIN SELECT '$$'; SELECT 1; END").unwrap(),
+        ];
+
+        SQL::save_to_file(file_path, &expected).unwrap();
+
+        assert_eq!(
+            SQL::from_file(file_path).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn preparable_test_1() {
+        assert!(SQL::new("CREATE TABLE tag (id INT)").unwrap().is_preparable());
+        assert!(SQL::new("SELECT * FROM tag").unwrap().is_preparable());
+        assert!(!SQL::new("CREATE PROCEDURE add_tag() BEGIN END").unwrap().is_preparable());
+        assert!(!SQL::new("CREATE DEFINER=`root`@`localhost` FUNCTION f() RETURNS int RETURN 1").unwrap().is_preparable());
+        assert!(!SQL::new("drop procedure add_tag").unwrap().is_preparable());
+    }
+
     #[test]
     fn file_insertion_1() {
         let file_name_1 = "file_insertion_1_1.sql";
@@ -1018,6 +1185,22 @@ mod tests{
         );
     }
 
+    #[test]
+    fn select_test_2() {
+        let input = "WITH cte AS (SELECT 1 AS n) SELECT n FROM cte";
+
+        let actual = SQL::new(input).unwrap();
+
+        assert_eq!(
+            actual,
+            SQL::Select(
+                QDL(
+                    input.to_string()
+                )
+            )
+        );
+    }
+
     //Query Manipulation Language
     #[test]
     fn insert_test_1() {
@@ -1145,4 +1328,19 @@ mod tests{
             )
         );
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn is_destructive_test_1() {
+        let destructive = |query: &str| SQL::new(query).unwrap().is_destructive();
+
+        assert!(destructive("DROP TABLE patients"));
+        assert!(destructive("TRUNCATE TABLE patients"));
+        assert!(destructive("DELETE FROM patients WHERE id = 1"));
+        assert!(destructive("ALTER TABLE patients DROP COLUMN name"));
+        assert!(destructive("UPDATE patients SET name = NULL"));
+
+        assert!(!destructive("ALTER TABLE patients ADD COLUMN dropped int"));
+        assert!(!destructive("INSERT INTO patients(id) VALUES (1)"));
+        assert!(!destructive("SELECT * FROM patients"));
+    }
+}
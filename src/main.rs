@@ -1,4 +1,4 @@
-use std::{io::Stdout, sync::Mutex};
+use std::{io::Stdout, mem, sync::Mutex, time::Duration};
 
 use log::{LevelFilter, info};
 use log4rs::{
@@ -11,13 +11,14 @@ use log4rs::{
 
 use regex::Regex;
 use lazy_static::lazy_static;
+use time::OffsetDateTime;
 
-use backend::relation::Relation;
-use crossterm::event::{self, Event};
+use backend::relation::{self, Relation, schema_file, erd, impact::Impact};
+use crossterm::event::{self, Event, KeyEvent, KeyEventKind, KeyCode};
 use tui::{Terminal, backend::{CrosstermBackend}, layout::{Layout, Direction, Constraint}};
-use ui::{input::Input, gen_terminal, menu::Menu, renderable::Renderable, pages::{schema::{relation_list::RelationListPage, relation_page::RelationPage}, snapshot::SnapShotPage}};
+use ui::{input::Input, gen_terminal, menu::Menu, renderable::Renderable, pages::{schema::{relation_list::RelationListPage, relation_page::RelationPage, diff::DiffPage, migration::MigrationPage, dependency_graph::DependencyGraphPage, impact::ImpactPage}, snapshot::SnapShotPage}};
 
-use crate::{ui::pages::{Pages, query::QueryPage}, backend::{sql::SQL, data_base::{DatabaseExecute, DataBase}, snapshot::SnapShotsFile}};
+use crate::{ui::pages::{Pages, query::QueryPage}, backend::{sql::SQL, data_base::{DatabaseExecute, DataBase}, snapshot::{self, SnapShotOptions, SnapShotsFile}, retention::Retention, migration::{self, Migration, Migrations, MIGRATIONS_TABLE}}};
 
 pub mod ui;
 pub mod backend;
@@ -63,8 +64,19 @@ fn main() {
 
     update_terminal(&mut terminal, &menu, &LAST_PAGE.lock().unwrap(), &input);
     loop {
+        //the terminal is redrawn when a key is pressed, or when work running in the background progressed
+        if !event::poll(Duration::from_millis(100)).unwrap_or(false) {
+            if update_progress() {
+                update_terminal(&mut terminal, &menu, &LAST_PAGE.lock().unwrap(), &input);
+            }
+            continue;
+        }
+
         if let Ok(Event::Key(event)) = event::read() {
-            if let Some(cmd) = input.from_event(event) {
+            if input.is_empty() && navigate_page(event) {
+                //the key was used by the page
+            }
+            else if let Some(cmd) = input.from_event(event) {
                 get_cmd(cmd, &mut menu);
             }
             update_terminal(&mut terminal, &menu, &LAST_PAGE.lock().unwrap(), &input);
@@ -72,6 +84,16 @@ fn main() {
     }
 }
 
+/// Updates the progress of the work running in the background of the shown page.
+///
+/// Returns true if the page changed.
+fn update_progress() -> bool {
+    match &mut *LAST_PAGE.lock().unwrap() {
+        Pages::SnapShot(snapshot) => snapshot.update_progress(),
+        _ => false,
+    }
+}
+
 fn update_terminal(terminal: &mut Terminal<CrosstermBackend<Stdout>>, menu: &Menu, page_content: &Pages, input: &Input ) {
     let _result = terminal.draw(|f| {
         let size = f.size();
@@ -92,11 +114,81 @@ fn update_terminal(terminal: &mut Terminal<CrosstermBackend<Stdout>>, menu: &Men
     });
 }
 
+/// Passes arrow & enter keys to pages navigated with the keyboard.
+///
+/// Returns true if the key was used by the page.
+fn navigate_page(event: KeyEvent) -> bool {
+    if event.kind != KeyEventKind::Press {
+        return false;
+    }
+
+    let mut last_page = LAST_PAGE.lock().unwrap();
+
+    match &mut *last_page {
+        Pages::DependencyGraph(graph) => {
+            match event.code {
+                KeyCode::Up => {
+                    graph.prev();
+                },
+                KeyCode::Down => {
+                    graph.next();
+                },
+                KeyCode::Right => {
+                    graph.expand();
+                },
+                KeyCode::Left => {
+                    graph.collapse();
+                },
+                KeyCode::Enter => {
+                    if let Some(relation) = graph.selected() {
+                        info!("Show specific schema:{}", relation.name());
+                        *last_page = Pages::Relation(RelationPage::new(relation));
+                    }
+                },
+                _ => return false,
+            }
+            true
+        },
+        _ => false,
+    }
+}
+
 fn get_cmd(cmd: String, menu: &mut Menu) {
     info!("CMD:{}", cmd);
     lazy_static!{
         static ref SCHEMA_TAB : Regex = Regex::new("[Ss][Hh][Oo][Ww] (.+)").unwrap();
     };
+    lazy_static!{
+        static ref EVENT_STATUS : Regex = Regex::new("^([Ee][Nn][Aa][Bb][Ll][Ee]|[Dd][Ii][Ss][Aa][Bb][Ll][Ee]) ([a-zA-Z0-9_]+)$").unwrap();
+    }
+    lazy_static!{
+        static ref DIFF_FILE : Regex = Regex::new("^[Dd][Ii][Ff][Ff] ([a-zA-Z0-9:/\\\\._ -]+\\.([Ss][Qq][Ll]|[Jj][Ss][Oo][Nn]|[Rr][Oo][Nn]))$").unwrap();
+    }
+    lazy_static!{
+        static ref DIFF_DATABASE : Regex = Regex::new("^[Dd][Ii][Ff][Ff] [Dd][Aa][Tt][Aa][Bb][Aa][Ss][Ee] ([a-zA-Z0-9_]+)$").unwrap();
+    }
+    //`diff database [name]` also matches, so it is excluded wherever snapshots are diffed
+    lazy_static!{
+        static ref DIFF_SNAPSHOTS : Regex = Regex::new("^[Dd][Ii][Ff][Ff] (\\S+) (\\S+)$").unwrap();
+    }
+    lazy_static!{
+        static ref UNDO_LAST : Regex = Regex::new("^[Uu][Nn][Dd][Oo]-[Ll][Aa][Ss][Tt]$").unwrap();
+    }
+    lazy_static!{
+        static ref APPLY : Regex = Regex::new("^[Aa][Pp][Pp][Ll][Yy] ([a-zA-Z0-9:/\\\\._ -]+\\.([Ss][Qq][Ll]|[Jj][Ss][Oo][Nn]|[Rr][Oo][Nn]))$").unwrap();
+    }
+    lazy_static!{
+        static ref EXPORT_SCHEMA : Regex = Regex::new("^[Ee][Xx][Pp][Oo][Rr][Tt] [Ss][Cc][Hh][Ee][Mm][Aa] ([a-zA-Z0-9:/\\\\._ -]+) [Aa][Ss] ([Jj][Ss][Oo][Nn]|[Rr][Oo][Nn])$").unwrap();
+    }
+    lazy_static!{
+        static ref EXPORT_ERD : Regex = Regex::new("^[Ee][Xx][Pp][Oo][Rr][Tt] [Ee][Rr][Dd] ([a-zA-Z0-9:/\\\\._-]+)( [Dd][Oo][Tt]| [Mm][Ee][Rr][Mm][Aa][Ii][Dd])?$").unwrap();
+    }
+    lazy_static!{
+        static ref IMPACT : Regex = Regex::new("^[Ii][Mm][Pp][Aa][Cc][Tt] ([a-zA-Z0-9_$]+)$").unwrap();
+    }
+    lazy_static!{
+        static ref MIGRATE : Regex = Regex::new("^[Mm][Ii][Gg][Rr][Aa][Tt][Ee] (.+)$").unwrap();
+    }
     lazy_static!{
         static ref FROM_FILE : Regex = Regex::new("^#file:\\(([a-zA-Z][a-zA-Z0-9:/\\\\_ ]+.[Ss][Qq][Ll]) as ([S])\\)$").unwrap();
     }
@@ -117,6 +209,15 @@ fn get_cmd(cmd: String, menu: &mut Menu) {
         lazy_static!{
             static ref ALL_VIEWS : Regex = Regex::new("^[Vv][Ii][Ee][Ww][Ss]$").unwrap();
         };
+        lazy_static!{
+            static ref ALL_ROUTINES : Regex = Regex::new("^[Rr][Oo][Uu][Tt][Ii][Nn][Ee][Ss]$").unwrap();
+        };
+        lazy_static!{
+            static ref ALL_EVENTS : Regex = Regex::new("^[Ee][Vv][Ee][Nn][Tt][Ss]$").unwrap();
+        };
+        lazy_static!{
+            static ref GRAPH : Regex = Regex::new("^[Gg][Rr][Aa][Pp][Hh]$").unwrap();
+        };
 
         if ALL_SCHEMA.is_match(capture) {
             info!("Show all schema");
@@ -131,6 +232,8 @@ fn get_cmd(cmd: String, menu: &mut Menu) {
                     match relation{
                         Relation::Table(_) => true,
                         Relation::View(_) => false,
+                        Relation::Routine(_) => false,
+                        Relation::Event(_) => false,
                     }
                 })
                 .map(|relation| relation.clone())
@@ -147,6 +250,44 @@ fn get_cmd(cmd: String, menu: &mut Menu) {
                     match relation{
                         Relation::Table(_) => false,
                         Relation::View(_) => true,
+                        Relation::Routine(_) => false,
+                        Relation::Event(_) => false,
+                    }
+                })
+                .map(|relation| relation.clone())
+                .collect();
+            
+            let mut last_page = LAST_PAGE.lock().unwrap();
+            *last_page = Pages::RelationList(RelationListPage::from(&relations));
+        }
+        else if ALL_ROUTINES.is_match(capture) {
+            info!("Show all routines schema");
+            let relations: Vec<Relation> = relations.iter()
+                .filter(
+                    |relation| {
+                    match relation{
+                        Relation::Table(_) => false,
+                        Relation::View(_) => false,
+                        Relation::Routine(_) => true,
+                        Relation::Event(_) => false,
+                    }
+                })
+                .map(|relation| relation.clone())
+                .collect();
+            
+            let mut last_page = LAST_PAGE.lock().unwrap();
+            *last_page = Pages::RelationList(RelationListPage::from(&relations));
+        }
+        else if ALL_EVENTS.is_match(capture) {
+            info!("Show all events schema");
+            let relations: Vec<Relation> = relations.iter()
+                .filter(
+                    |relation| {
+                    match relation{
+                        Relation::Table(_) => false,
+                        Relation::View(_) => false,
+                        Relation::Routine(_) => false,
+                        Relation::Event(_) => true,
                     }
                 })
                 .map(|relation| relation.clone())
@@ -155,6 +296,11 @@ fn get_cmd(cmd: String, menu: &mut Menu) {
             let mut last_page = LAST_PAGE.lock().unwrap();
             *last_page = Pages::RelationList(RelationListPage::from(&relations));
         }
+        else if GRAPH.is_match(capture) {
+            info!("Show dependency graph");
+            let mut last_page = LAST_PAGE.lock().unwrap();
+            *last_page = Pages::DependencyGraph(DependencyGraphPage::from(&relations));
+        }
         else {
             let relations: Vec<Relation> = relations.iter()
                 .map(
@@ -202,6 +348,21 @@ fn get_cmd(cmd: String, menu: &mut Menu) {
         let mut last_page = LAST_PAGE.lock().unwrap();
         *last_page = Pages::SnapShot(SnapShotPage::default());
     }
+    else if UNDO_LAST.is_match(&cmd) {
+        info!("Undo last");
+        menu.select(2).unwrap();
+
+        let mut last_page = LAST_PAGE.lock().unwrap();
+
+        //the snapshot list is kept when shown, so work running in the background still reports its progress
+        let mut snapshot = match mem::replace(&mut *last_page, Pages::SnapShot(SnapShotPage::default())) {
+            Pages::SnapShot(snapshot) => snapshot,
+            _ => SnapShotPage::default(),
+        };
+
+        snapshot.undo_last();
+        *last_page = Pages::SnapShot(snapshot);
+    }
     else if FROM_FILE.is_match(&cmd) {
         info!("From file: {}", &cmd);
         let file_path = {
@@ -217,6 +378,10 @@ fn get_cmd(cmd: String, menu: &mut Menu) {
                 for cmd in &cmds{
                     info!("cmds: {:?}", cmd);
                 }
+                if cmds.iter().any(SQL::is_destructive) && !safety_snapshot("before file", &file_path) {
+                    return;
+                }
+
                 let db = DataBase::from_env().unwrap();
 
                 let _result = db.execute_multiple(&cmds);
@@ -226,13 +391,350 @@ fn get_cmd(cmd: String, menu: &mut Menu) {
             },
         };
     }
+    else if EVENT_STATUS.is_match(&cmd) {
+        let captures = EVENT_STATUS.captures(&cmd).unwrap();
+        let enable = captures.get(1).unwrap().as_str().to_ascii_lowercase() == "enable";
+        let event_name = captures.get(2).unwrap().as_str();
+
+        let mut relations = RELATIONS.lock().unwrap();
+
+        *relations = Relation::get_relations().unwrap();
+
+        let event = relations.iter()
+            .find_map(|relation| match relation {
+                Relation::Event(event) if event.name == event_name => Some(event.clone()),
+                _ => None,
+            });
+
+        match event {
+            Some(event) => {
+                info!("Set event {} enabled:{}", event_name, enable);
+                let cmd = match enable {
+                    true => event.enable(),
+                    false => event.disable(),
+                };
+
+                if let Err(err) = cmd.execute(|_| ()) {
+                    log::error!("Failed to alter event({}): {:?}", event_name, err);
+                }
+
+                *relations = Relation::get_relations().unwrap();
+
+                if let Some(relation) = relations.iter().find(|relation| relation.name() == event_name) {
+                    menu.select(0).unwrap();
+
+                    let mut last_page = LAST_PAGE.lock().unwrap();
+                    *last_page = Pages::Relation(RelationPage::new(relation));
+                }
+            },
+            None => log::error!("Event {} does not exist", event_name),
+        }
+    }
+    else if *menu.get_tab() == ui::menu::Tab::SnapShot && DIFF_SNAPSHOTS.is_match(&cmd) && !DIFF_DATABASE.is_match(&cmd) {
+        let captures = DIFF_SNAPSHOTS.captures(&cmd).unwrap();
+        info!("Diff snapshot {} with {}", &captures[1], &captures[2]);
+
+        let mut last_page = LAST_PAGE.lock().unwrap();
+
+        //the snapshot list stays shown if the diff fails, so work running in the background still reports its progress
+        let mut snapshot = match mem::replace(&mut *last_page, Pages::SnapShot(SnapShotPage::default())) {
+            Pages::SnapShot(snapshot) => snapshot,
+            _ => SnapShotPage::default(),
+        };
+
+        *last_page = match snapshot.diff(&captures[1], &captures[2]) {
+            Some(page) => Pages::SnapShotDiff(page),
+            None => Pages::SnapShot(snapshot),
+        };
+    }
+    else if DIFF_FILE.is_match(&cmd) || DIFF_DATABASE.is_match(&cmd) {
+        let mut relations = RELATIONS.lock().unwrap();
+
+        *relations = Relation::get_relations().unwrap();
+
+        let target: Option<(String, Vec<Relation>)> = if let Some(captures) = DIFF_DATABASE.captures(&cmd) {
+            let name = captures.get(1).unwrap().as_str();
+
+            match DataBase::from_env().unwrap().with_name(name).map(|db| relation::information_schema::load(&db)) {
+                Some(Ok(target)) => Some((name.to_string(), target)),
+                Some(Err(err)) => {
+                    log::error!("Failed to load relations of {}: {:?}", name, err);
+                    None
+                },
+                None => {
+                    log::error!("Failed to connect to {}", name);
+                    None
+                },
+            }
+        }
+        else {
+            let file_path = DIFF_FILE.captures(&cmd).unwrap().get(1).unwrap().as_str();
+
+            match schema_file::import(file_path) {
+                Ok(target) => Some((file_path.to_string(), target)),
+                Err(err) => {
+                    log::error!("Failed to read {}: {:?}", file_path, err);
+                    None
+                },
+            }
+        };
+
+        if let Some((name, target)) = target {
+            info!("Diff live schema with {}", name);
+            menu.select(0).unwrap();
+
+            let mut last_page = LAST_PAGE.lock().unwrap();
+            *last_page = Pages::Diff(DiffPage::new(&format!("live -> {}", name), relation::diff::diff(&relations, &target)));
+        }
+    }
+    else if APPLY.is_match(&cmd) {
+        let file_path = APPLY.captures(&cmd).unwrap().get(1).unwrap().as_str();
+
+        if let Some(page) = plan_apply(file_path) {
+            info!("Plan to apply {}", file_path);
+            menu.select(0).unwrap();
+
+            let mut last_page = LAST_PAGE.lock().unwrap();
+            *last_page = Pages::Diff(page);
+        }
+    }
+    else if EXPORT_SCHEMA.is_match(&cmd) {
+        let captures = EXPORT_SCHEMA.captures(&cmd).unwrap();
+        let file_path = captures.get(1).unwrap().as_str();
+        let format = schema_file::Format::from(captures.get(2).unwrap().as_str()).unwrap();
+
+        let mut relations = RELATIONS.lock().unwrap();
+
+        *relations = Relation::get_relations().unwrap();
+
+        info!("Export schema to {} as {}", file_path, format);
+        if let Err(err) = schema_file::export(&relations, file_path, format) {
+            log::error!("Failed to export schema to {}: {:?}", file_path, err);
+        }
+    }
+    else if EXPORT_ERD.is_match(&cmd) {
+        let captures = EXPORT_ERD.captures(&cmd).unwrap();
+        let file_path = captures.get(1).unwrap().as_str();
+
+        //the format defaults to the file extension, then to dot
+        let format = captures.get(2)
+            .map(|format| format.as_str().trim())
+            .or_else(|| std::path::Path::new(file_path).extension().and_then(|extension| extension.to_str()))
+            .and_then(erd::ErdFormat::from)
+            .unwrap_or(erd::ErdFormat::Dot);
+
+        let mut relations = RELATIONS.lock().unwrap();
+
+        *relations = Relation::get_relations().unwrap();
+
+        info!("Export erd to {} as {}", file_path, format);
+        if let Err(err) = erd::export(&relations, file_path, format) {
+            log::error!("Failed to export erd to {}: {:?}", file_path, err);
+        }
+    }
+    else if IMPACT.is_match(&cmd) {
+        let name = IMPACT.captures(&cmd).unwrap().get(1).unwrap().as_str();
+
+        let mut relations = RELATIONS.lock().unwrap();
+
+        *relations = Relation::get_relations().unwrap();
+
+        match Impact::from(&relations, name) {
+            Some(impact) => {
+                info!("Show impact of {}", name);
+                menu.select(0).unwrap();
+
+                let mut last_page = LAST_PAGE.lock().unwrap();
+                *last_page = Pages::Impact(ImpactPage::new(impact));
+            },
+            None => log::error!("No relation named {}", name),
+        }
+    }
+    else if MIGRATE.is_match(&cmd) {
+        let capture = MIGRATE.captures(&cmd).unwrap().get(1).unwrap().as_str();
+
+        lazy_static!{
+            static ref MIGRATE_STATUS : Regex = Regex::new("^[Ss][Tt][Aa][Tt][Uu][Ss]$").unwrap();
+        };
+        lazy_static!{
+            static ref MIGRATE_UP : Regex = Regex::new("^[Uu][Pp]( [0-9]+)?$").unwrap();
+        };
+        lazy_static!{
+            static ref MIGRATE_DOWN : Regex = Regex::new("^[Dd][Oo][Ww][Nn]( [0-9]+)?$").unwrap();
+        };
+        lazy_static!{
+            static ref MIGRATE_NEW : Regex = Regex::new("^[Nn][Ee][Ww] (.+)$").unwrap();
+        };
+        lazy_static!{
+            static ref MIGRATE_RESOLVE : Regex = Regex::new("^[Rr][Ee][Ss][Oo][Ll][Vv][Ee] ([0-9]+) ([Aa][Pp][Pp][Ll][Ii][Ee][Dd]|[Rr][Ee][Vv][Ee][Rr][Tt][Ee][Dd])$").unwrap();
+        };
+
+        let migrations = Migrations::default();
+
+        let count = |captures: regex::Captures| -> Option<usize> {
+            captures.get(1).and_then(|count| count.as_str().trim().parse().ok())
+        };
+
+        let message: Option<String> = if MIGRATE_STATUS.is_match(capture) {
+            info!("Migration status");
+            Some(String::new())
+        }
+        else if let Some(captures) = MIGRATE_UP.captures(capture) {
+            let db = DataBase::from_env().unwrap();
+            let count = count(captures);
+
+            //the up scripts are read before applying, so a safety snapshot can be taken if they drop or overwrite anything
+            let cmds: Vec<SQL> = migrations.pending(&db, count)
+                .and_then(|pending| pending.iter().map(Migration::up_cmds).collect::<Result<Vec<Vec<SQL>>, _>>())
+                .map(|cmds| cmds.concat())
+                .unwrap_or_default();
+
+            if cmds.iter().any(SQL::is_destructive) && !safety_snapshot("before migrate up", &cmd) {
+                Some(String::from("failed to take a safety snapshot, nothing was applied"))
+            }
+            else {
+                match migrations.up(&db, count) {
+                    Ok(applied) => Some(format!("applied {} migration(s)", applied.len())),
+                    //DDL statements commit implicitly, so the failed migration may be partially applied
+                    Err(err @ migration::Error::Execution(_)) => {
+                        log::error!("Failed to apply migrations - Err:{:?}", err);
+                        Some(format!("failed to apply: {:?}, the failed migration is marked dirty until it is resolved with `migrate resolve`", err))
+                    },
+                    Err(err) => {
+                        log::error!("Failed to apply migrations - Err:{:?}", err);
+                        Some(format!("failed to apply: {:?}", err))
+                    },
+                }
+            }
+        }
+        else if let Some(captures) = MIGRATE_DOWN.captures(capture) {
+            let db = DataBase::from_env().unwrap();
+            let count = count(captures).unwrap_or(1);
+
+            //the down scripts are read before reverting, so a safety snapshot can be taken if they drop anything
+            let cmds: Vec<SQL> = migrations.reverting(&db, count)
+                .and_then(|reverting| reverting.iter().map(Migration::down_cmds).collect::<Result<Vec<Vec<SQL>>, _>>())
+                .map(|cmds| cmds.concat())
+                .unwrap_or_default();
+
+            if cmds.iter().any(SQL::is_destructive) && !safety_snapshot("before migrate down", &cmd) {
+                Some(String::from("failed to take a safety snapshot, nothing was reverted"))
+            }
+            else {
+                match migrations.down(&db, count) {
+                    Ok(reverted) => Some(format!("reverted {} migration(s)", reverted.len())),
+                    Err(err) => {
+                        log::error!("Failed to revert migrations - Err:{:?}", err);
+                        Some(format!("failed to revert: {:?}", err))
+                    },
+                }
+            }
+        }
+        else if let Some(captures) = MIGRATE_RESOLVE.captures(capture) {
+            let version = captures.get(1).unwrap().as_str();
+            let applied = captures.get(2).unwrap().as_str().eq_ignore_ascii_case("applied");
+
+            match migrations.resolve(&DataBase::from_env().unwrap(), version, applied) {
+                Ok(true) => Some(format!("resolved migration {}", version)),
+                Ok(false) => Some(format!("migration {} is not dirty", version)),
+                Err(err) => {
+                    log::error!("Failed to resolve migration({}) - Err:{:?}", version, err);
+                    Some(format!("failed to resolve: {:?}", err))
+                },
+            }
+        }
+        else if let Some(captures) = MIGRATE_NEW.captures(capture) {
+            let name = captures.get(1).unwrap().as_str();
+
+            match migrations.create(name, OffsetDateTime::now_utc()) {
+                Ok(migration) => Some(format!("created {}", migration.up.display())),
+                Err(err) => {
+                    log::error!("Failed to create migration({}) - Err:{:?}", name, err);
+                    Some(format!("failed to create: {:?}", err))
+                },
+            }
+        }
+        else {
+            None
+        };
+
+        if let Some(message) = message {
+            menu.select(0).unwrap();
+
+            let mut last_page = LAST_PAGE.lock().unwrap();
+            *last_page = Pages::Migration(MigrationPage::new(&message));
+        }
+    }
     else if let Ok(sql) = SQL::new(&cmd) {
+        let label = format!("before {}", cmd.split_whitespace().next().unwrap_or_default().to_ascii_uppercase());
+
+        if sql.is_destructive() && !safety_snapshot(&label, &cmd) {
+            return;
+        }
+
         let _result = sql.execute(|_| ());
     }
     else {
         match menu.get_tab(){
             ui::menu::Tab::Schema => {
-                //no
+                lazy_static!{
+                    static ref SAVE_DIFF : Regex = Regex::new("^[Ss][Aa][Vv][Ee] ([a-zA-Z0-9:/\\\\._ -]+\\.[Ss][Qq][Ll])$").unwrap();
+                };
+
+                lazy_static!{
+                    static ref CONFIRM : Regex = Regex::new("^[Cc][Oo][Nn][Ff][Ii][Rr][Mm]( [Dd][Ee][Ss][Tt][Rr][Uu][Cc][Tt][Ii][Vv][Ee])?$").unwrap();
+                };
+
+                let mut last_page = LAST_PAGE.lock().unwrap();
+
+                if let (Pages::Diff(diff), Some(captures)) = (&*last_page, SAVE_DIFF.captures(&cmd)) {
+                    let file_path = captures.get(1).unwrap().as_str();
+                    info!("Save diff to {}", file_path);
+
+                    if let Err(err) = SQL::save_to_file(file_path, &diff.cmds()) {
+                        log::error!("Failed to save diff to {}: {:?}", file_path, err);
+                    }
+                }
+                else if let (Pages::Impact(impact), Some(captures)) = (&*last_page, SAVE_DIFF.captures(&cmd)) {
+                    let file_path = captures.get(1).unwrap().as_str();
+                    info!("Save removal script to {}", file_path);
+
+                    if let Err(err) = SQL::save_to_file(file_path, &impact.cmds()) {
+                        log::error!("Failed to save removal script to {}: {:?}", file_path, err);
+                    }
+                }
+                else if let (Pages::Diff(plan), Some(captures)) = (&mut *last_page, CONFIRM.captures(&cmd)) {
+                    let destructive = captures.get(1).is_some();
+
+                    if let Some(file_path) = plan.source().map(|source| source.to_string()) {
+                        if plan.diff().is_destructive() && !destructive {
+                            plan.set_message("contains destructive changes (!), enter `confirm destructive` to apply");
+                        }
+                        else if plan.cmds().iter().any(SQL::is_destructive) && !safety_snapshot("before confirm", &file_path) {
+                            plan.set_message("failed to take a safety snapshot, nothing was applied");
+                        }
+                        else {
+                            info!("Apply {}", file_path);
+                            let cmds = plan.cmds();
+
+                            let result = DataBase::from_env().unwrap().execute_multiple(&cmds);
+
+                            *RELATIONS.lock().unwrap() = Relation::get_relations().unwrap();
+
+                            if let Some(page) = plan_apply(&file_path) {
+                                *plan = page;
+                            }
+
+                            match result {
+                                Ok(_) => plan.set_message(&format!("applied {} statement(s)", cmds.len())),
+                                Err(err) => {
+                                    log::error!("Failed to apply {}: {:?}", file_path, err);
+                                    plan.set_message(&format!("failed to apply: {:?}", err));
+                                },
+                            }
+                        }
+                    }
+                }
             },
             ui::menu::Tab::Query => {
                 let mut last_page = LAST_PAGE.lock().unwrap();
@@ -274,7 +776,10 @@ fn get_cmd(cmd: String, menu: &mut Menu) {
                         let cmd: &str = &cmd;
 
                         lazy_static!{
-                            static ref ADD_SNAPSHOT : Regex = Regex::new("^[Aa][Dd][Dd]$").unwrap();
+                            static ref ADD_SNAPSHOT : Regex = Regex::new("^[Aa][Dd][Dd](?: \"([^\"]*)\")?(?: \"([^\"]*)\")?((?: --[a-z-]+(?: [a-zA-Z0-9_$,]+)?)*)$").unwrap();
+                        };
+                        lazy_static!{
+                            static ref CANCEL_SNAPSHOT : Regex = Regex::new("^[Cc][Aa][Nn][Cc][Ee][Ll]$").unwrap();
                         };
                         lazy_static!{
                             static ref NEXT_PAGE : Regex = Regex::new("^[Nn][Ee][Xx][Tt]$").unwrap();
@@ -287,15 +792,37 @@ fn get_cmd(cmd: String, menu: &mut Menu) {
                         };
 
                         lazy_static!{
-                            static ref ROLLBACK_SNAPSHOT : Regex = Regex::new("^[Rr][Oo][Ll][Ll][Bb][Aa][Cc][Kk] (.+)$").unwrap();
+                            static ref ROLLBACK_SNAPSHOT : Regex = Regex::new("^[Rr][Oo][Ll][Ll][Bb][Aa][Cc][Kk] (.+?)( --atomic)?$").unwrap();
+                        };
+                        lazy_static!{
+                            static ref PIN_SNAPSHOT : Regex = Regex::new("^([Uu][Nn])?[Pp][Ii][Nn] (.+)$").unwrap();
+                        };
+                        lazy_static!{
+                            static ref RETENTION : Regex = Regex::new("^[Rr][Ee][Tt][Ee][Nn][Tt][Ii][Oo][Nn]( --keep-all)?((?: \\S+)*)$").unwrap();
+                        };
+                        lazy_static!{
+                            static ref PRUNE : Regex = Regex::new("^[Pp][Rr][Uu][Nn][Ee]( --dry-run)?$").unwrap();
+                        };
+                        lazy_static!{
+                            static ref AUTO_SNAPSHOT : Regex = Regex::new("^[Aa][Uu][Tt][Oo]-[Ss][Nn][Aa][Pp][Ss][Hh][Oo][Tt](?: ([Oo][Nn]|[Oo][Ff][Ff]))?$").unwrap();
+                        };
+                        lazy_static!{
+                            static ref RESTORE_TABLE : Regex = Regex::new("^[Rr][Ee][Ss][Tt][Oo][Rr][Ee] (.+?) ([a-zA-Z0-9_$]+)( --data-only)?$").unwrap();
                         };
 
-                        if ADD_SNAPSHOT.is_match(cmd) {
-                            let mut file = SnapShotsFile::default();
-        
-                            file.add_snapshot(DataBase::from_env().unwrap().into());
-        
-                            snapshot.update();
+                        if let Some(captures) = ADD_SNAPSHOT.captures(cmd) {
+                            match SnapShotOptions::from_flags(&captures[3]) {
+                                Ok(mut options) => {
+                                    options.label = captures.get(1).map(|label| label.as_str().to_string());
+                                    options.description = captures.get(2).map(|description| description.as_str().to_string());
+
+                                    snapshot.take_snapshot(options)
+                                },
+                                Err(err) => snapshot.set_message(&err),
+                            }
+                        }
+                        else if CANCEL_SNAPSHOT.is_match(cmd) {
+                            snapshot.cancel_snapshot();
                         }
                         else if NEXT_PAGE.is_match(cmd) {
                             let size: usize = unsafe {
@@ -316,8 +843,42 @@ fn get_cmd(cmd: String, menu: &mut Menu) {
                             snapshot.del(cmd);
                         }
                         else if ROLLBACK_SNAPSHOT.is_match(cmd) {
-                            let cmd = ROLLBACK_SNAPSHOT.captures(cmd).unwrap().get(1).unwrap().as_str();
-                            snapshot.rollback(cmd);
+                            let captures = ROLLBACK_SNAPSHOT.captures(cmd).unwrap();
+                            snapshot.rollback(captures.get(1).unwrap().as_str(), captures.get(2).is_some());
+                        }
+                        else if let Some(captures) = RESTORE_TABLE.captures(cmd) {
+                            snapshot.restore_table(&captures[1], &captures[2], captures.get(3).is_some());
+                        }
+                        else if let Some(captures) = PIN_SNAPSHOT.captures(cmd) {
+                            snapshot.pin(&captures[2], captures.get(1).is_none());
+                        }
+                        else if let Some(captures) = RETENTION.captures(cmd) {
+                            match (captures.get(1), &captures[2]) {
+                                (Some(_), "") => snapshot.retention(Some(Retention::default())),
+                                (None, "") => snapshot.retention(None),
+                                (None, flags) => match Retention::from_flags(flags) {
+                                    Ok(retention) => snapshot.retention(Some(retention)),
+                                    Err(err) => snapshot.set_message(&err),
+                                },
+                                (Some(_), _) => snapshot.set_message("--keep-all can't be combined with other flags"),
+                            }
+                        }
+                        else if let Some(captures) = PRUNE.captures(cmd) {
+                            snapshot.prune(captures.get(1).is_some());
+                        }
+                        else if let Some(captures) = AUTO_SNAPSHOT.captures(cmd) {
+                            snapshot.auto_snapshot(captures.get(1).map(|enabled| enabled.as_str().eq_ignore_ascii_case("on")));
+                        }
+                    },
+                    Pages::SnapShotDiff(diff) => {
+                        let size: usize = unsafe {
+                            PAGE_SIZE.clone() as usize - 3usize
+                        };
+
+                        match cmd.to_ascii_lowercase().as_str() {
+                            "next" => diff.next(size),
+                            "prev" => diff.prev(size),
+                            _ => {},
                         }
                     },
                     _=>{}
@@ -327,3 +888,52 @@ fn get_cmd(cmd: String, menu: &mut Menu) {
     }
     
 }
+
+/// Takes a safety snapshot before destructive statements run from the console, if safety snapshots are enabled
+///
+/// Returns false if the snapshot failed, in which case the statements must not run
+///
+/// # Arguments
+///
+/// * `label` - The label of the snapshot (e.g. `before DROP`)
+/// * `description` - The statement or file being run
+fn safety_snapshot(label: &str, description: &str) -> bool {
+    let result = DataBase::from_env()
+        .map_err(|err| snapshot::Error::Err(format!("{:?}", err)))
+        .and_then(|db| SnapShotsFile::default().safety_snapshot(&db, label, description));
+
+    match result {
+        Ok(Some(snapshot)) => {
+            info!("Safety snapshot {} before {}", snapshot.path, description);
+            true
+        },
+        Ok(None) => true,
+        Err(err) => {
+            log::error!("Failed to take safety snapshot before {} - Err:{:?}", description, err);
+            false
+        },
+    }
+}
+
+/// Returns a plan to change the live database into the schema defined by a schema file
+///
+/// # Arguments
+///
+/// * `file_path` - The path of the schema file
+fn plan_apply(file_path: &str) -> Option<DiffPage> {
+    let target = match schema_file::import(file_path) {
+        Ok(target) => target,
+        Err(err) => {
+            log::error!("Failed to read {}: {:?}", file_path, err);
+            return None;
+        },
+    };
+
+    //the migration bookkeeping is never part of a schema file
+    let relations: Vec<Relation> = Relation::get_relations().unwrap()
+        .into_iter()
+        .filter(|relation| relation.name() != MIGRATIONS_TABLE)
+        .collect();
+
+    Some(DiffPage::plan(file_path, relation::diff::diff(&relations, &target)))
+}
\ No newline at end of file
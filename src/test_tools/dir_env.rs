@@ -0,0 +1,27 @@
+use std::fs;
+
+/// Struct representing a directory environment
+pub struct DirEnv{
+    /// The path of the directory
+    path: String
+}
+impl DirEnv{
+    /// Creates a new empty DirEnv at the given path
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the directory to create
+    pub fn new(path: &str) -> DirEnv {
+        let _result = fs::remove_dir_all(path);
+        fs::create_dir_all(path).unwrap();
+
+        DirEnv{ path: path.to_string() }
+    }
+}
+impl Drop for DirEnv{
+    /// Deletes the directory & its content when the DirEnv is dropped
+    fn drop(&mut self) {
+        println!("Dropping {}", &self.path);
+        fs::remove_dir_all(&self.path).unwrap();
+    }
+}
@@ -1,2 +1,3 @@
 pub mod file_env;
-pub mod db_env;
\ No newline at end of file
+pub mod db_env;
+pub mod dir_env;
\ No newline at end of file
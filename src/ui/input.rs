@@ -83,6 +83,11 @@ impl Input {
         self
     }
 
+    /// is_empty method returns true if no characters were inputted
+    pub fn is_empty(&self) -> bool {
+        self.content.is_empty()
+    }
+
     /// cursor method returns cursor position
     pub fn cursor(&self)  -> usize {
         self.cursor
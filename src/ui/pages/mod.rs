@@ -3,12 +3,18 @@ use super::renderable::Renderable;
 pub mod schema;
 pub mod query;
 pub mod snapshot;
+pub mod snapshot_diff;
 
 pub enum Pages {
     RelationList(schema::relation_list::RelationListPage),
     Relation(schema::relation_page::RelationPage),
+    Diff(schema::diff::DiffPage),
+    Migration(schema::migration::MigrationPage),
+    DependencyGraph(schema::dependency_graph::DependencyGraphPage),
+    Impact(schema::impact::ImpactPage),
     Query(query::QueryPage),
-    SnapShot(snapshot::SnapShotPage)
+    SnapShot(snapshot::SnapShotPage),
+    SnapShotDiff(snapshot_diff::SnapShotDiffPage),
 }
 
 impl Renderable for Pages{
@@ -16,8 +22,13 @@ impl Renderable for Pages{
         match self {
             Pages::RelationList(val) => val.render(display_area, frame),
             Pages::Relation(val) => val.render(display_area, frame),
+            Pages::Diff(val) => val.render(display_area, frame),
+            Pages::Migration(val) => val.render(display_area, frame),
+            Pages::DependencyGraph(val) => val.render(display_area, frame),
+            Pages::Impact(val) => val.render(display_area, frame),
             Pages::Query(val) => val.render(display_area, frame),
             Pages::SnapShot(val) => val.render(display_area, frame),
+            Pages::SnapShotDiff(val) => val.render(display_area, frame),
         }
     }
 }
\ No newline at end of file
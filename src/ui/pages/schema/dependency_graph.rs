@@ -0,0 +1,343 @@
+use std::collections::HashSet;
+
+use petgraph::Outgoing;
+use tui::{
+    Frame,
+    style::{Color, Style, Modifier},
+    widgets::{Paragraph, Block, Borders},
+    backend::CrosstermBackend,
+    layout::Rect,
+    text::{Spans, Span}
+};
+
+use crate::{ui::renderable::Renderable, backend::relation::{Relation, paths::get_dependency_tree}};
+
+/// A visible line of the dependency graph
+#[derive(Debug, PartialEq, Eq)]
+struct Line{
+    /// The indexes of the relations from the root to the relation of the line.
+    path: Vec<usize>,
+    /// Whether the relation has dependents.
+    has_children: bool,
+    /// Whether the dependents of the relation are shown.
+    expanded: bool,
+}
+
+/// DependencyGraphPage struct defines the states required in-order to show the dependency tree of the relations as an indented tree on the relation tab
+///
+/// Roots are relations without dependencies (e.g. tables without foreign keys) & the children of a relation are the relations depending on it.
+#[derive(Debug)]
+pub struct DependencyGraphPage{
+    relations: Vec<Relation>,
+    /// The indexes of the relations depending on each relation.
+    children: Vec<Vec<usize>>,
+    /// The indexes of the relations without dependencies, followed by one relation of each group of relations only depending on each other.
+    roots: Vec<usize>,
+    /// The paths of the expanded lines. A relation depended on by several relations can be expanded under each of them independently.
+    expanded: HashSet<Vec<usize>>,
+    /// The index of the selected line.
+    selected: usize,
+}
+
+impl DependencyGraphPage {
+    /// from associative function defines DependencyGraphPage from a vector of Relations
+    pub fn from(relations: &Vec<Relation>) -> DependencyGraphPage {
+        let dependency_tree = get_dependency_tree(relations);
+
+        let mut children: Vec<Vec<usize>> = vec![Vec::new(); relations.len()];
+        let mut has_parent: Vec<bool> = vec![false; relations.len()];
+
+        dependency_tree.node_indices()
+            .for_each(|node| {
+                let parent = dependency_tree[node] as usize;
+
+                dependency_tree.neighbors_directed(node, Outgoing)
+                    .map(|child| dependency_tree[child] as usize)
+                    .for_each(|child| {
+                        if !children[parent].contains(&child) {
+                            children[parent].push(child);
+                        }
+                        has_parent[child] = has_parent[child] || child != parent;
+                    });
+            });
+
+        children.iter_mut()
+            .for_each(|children| children.sort_by_key(|child| relations[*child].name()));
+
+        let mut roots: Vec<usize> = (0..relations.len())
+            .filter(|index| !has_parent[*index])
+            .collect();
+        roots.sort_by_key(|root| relations[*root].name());
+
+        //relations that only depend on each other have no root, so the first of them by name becomes one
+        let mut reachable: Vec<bool> = vec![false; relations.len()];
+        let mut stack: Vec<usize> = roots.clone();
+        let mut by_name: Vec<usize> = (0..relations.len()).collect();
+        by_name.sort_by_key(|index| relations[*index].name());
+
+        loop {
+            while let Some(index) = stack.pop() {
+                if !reachable[index] {
+                    reachable[index] = true;
+                    stack.extend(&children[index]);
+                }
+            }
+
+            match by_name.iter().find(|index| !reachable[**index]) {
+                Some(index) => {
+                    roots.push(*index);
+                    stack.push(*index);
+                },
+                None => break,
+            }
+        }
+
+        DependencyGraphPage {
+            relations: relations.clone(),
+            children,
+            roots,
+            expanded: HashSet::new(),
+            selected: 0,
+        }
+    }
+
+    /// Returns the visible lines in order
+    fn lines(&self) -> Vec<Line> {
+        let mut lines: Vec<Line> = Vec::new();
+
+        self.roots.iter()
+            .for_each(|root| self.add_lines(vec![*root], &mut lines));
+
+        lines
+    }
+
+    fn add_lines(&self, path: Vec<usize>, lines: &mut Vec<Line>) {
+        let index = *path.last().unwrap();
+
+        //relations in a cycle are not expanded a second time
+        let children: Vec<usize> = self.children[index].iter()
+            .filter(|child| !path.contains(child))
+            .cloned()
+            .collect();
+
+        let expanded = self.expanded.contains(&path);
+
+        lines.push(Line { path: path.clone(), has_children: !children.is_empty(), expanded });
+
+        if expanded {
+            children.iter()
+                .for_each(|child| self.add_lines([path.clone(), vec![*child]].concat(), lines));
+        }
+    }
+
+    /// next method moves the selection down by one line
+    pub fn next(&mut self) -> &mut Self {
+        if self.selected + 1 < self.lines().len() {
+            self.selected += 1;
+        }
+        self
+    }
+
+    /// prev method moves the selection up by one line
+    pub fn prev(&mut self) -> &mut Self {
+        self.selected = self.selected.saturating_sub(1);
+        self
+    }
+
+    /// expand method shows the dependents of the selected relation
+    pub fn expand(&mut self) -> &mut Self {
+        if let Some(line) = self.lines().into_iter().nth(self.selected) {
+            if line.has_children {
+                self.expanded.insert(line.path);
+            }
+        }
+        self
+    }
+
+    /// collapse method hides the dependents of the selected relation, or selects its parent if they are already hidden
+    pub fn collapse(&mut self) -> &mut Self {
+        let lines = self.lines();
+
+        if let Some(line) = lines.get(self.selected) {
+            if line.expanded {
+                self.expanded.remove(&line.path);
+            }
+            else if line.path.len() > 1 {
+                let parent = &line.path[..line.path.len() - 1];
+
+                if let Some(index) = lines.iter().position(|line| line.path == parent) {
+                    self.selected = index;
+                }
+            }
+        }
+        self
+    }
+
+    /// selected method returns the selected relation
+    pub fn selected(&self) -> Option<&Relation> {
+        self.lines()
+            .get(self.selected)
+            .map(|line| &self.relations[*line.path.last().unwrap()])
+    }
+}
+
+impl Renderable for DependencyGraphPage{
+    fn render<T: std::io::Write>(&self, display_area: Rect, frame: &mut Frame<CrosstermBackend<T>>) {
+        let height = display_area.height.saturating_sub(2) as usize;
+        let offset = match self.selected < height {
+            true => 0,
+            false => self.selected + 1 - height,
+        };
+
+        let lines: Vec<Spans> = self.lines()
+            .iter()
+            .enumerate()
+            .skip(offset)
+            .take(height)
+            .map(|(index, line)| {
+                let relation = &self.relations[*line.path.last().unwrap()];
+
+                let marker = match (line.has_children, line.expanded) {
+                    (false, _) => "  ",
+                    (true, false) => "▸ ",
+                    (true, true) => "▾ ",
+                };
+
+                let relation_type = match relation {
+                    Relation::Table(_) => "table",
+                    Relation::View(_) => "view",
+                    Relation::Routine(_) => "routine",
+                    Relation::Event(_) => "event",
+                };
+
+                let style = match index == self.selected {
+                    true => Style::default().add_modifier(Modifier::REVERSED),
+                    false => Style::default(),
+                };
+
+                Spans::from(
+                    vec![
+                        Span::from("  ".repeat(line.path.len() - 1)),
+                        Span::from(marker),
+                        Span::styled(relation.name(), style),
+                        Span::styled(format!(" ({})", relation_type), Style::default().fg(Color::DarkGray)),
+                    ]
+                )
+            })
+            .collect();
+
+        let paragraph = Paragraph::new(lines)
+            .block(
+                Block::default()
+                .title("Dependency Graph - ↑/↓ select, →/← expand/collapse, Enter open")
+                .borders(Borders::ALL)
+            );
+
+        frame.render_widget(paragraph, display_area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(unused_imports)]
+    use std::collections::HashSet;
+
+    use crate::backend::{relation::{Relation, table::{Table, Attribute, AttributeType, Constraint}, view::View}, sql::QDL};
+
+    use super::DependencyGraphPage;
+
+    #[allow(dead_code)]
+    fn table(name: &str, foreign_keys: &[&str]) -> Relation {
+        Relation::Table(
+            Table{
+                name: name.to_string(),
+                attributes: [
+                    vec![
+                        Attribute{
+                            name: String::from("id"),
+                            data_type: AttributeType::Int(11),
+                            constraint: HashSet::new()
+                        }
+                    ],
+                    foreign_keys.iter()
+                        .map(|table_name| Attribute{
+                            name: format!("{}_id", table_name),
+                            data_type: AttributeType::Int(11),
+                            constraint: HashSet::from([
+                                Constraint::ForeignKey{
+                                    table_name: table_name.to_string(),
+                                    attribute_name: String::from("id"),
+                                    constraint_name: None,
+                                    other_columns: Vec::new()
+                                }
+                            ])
+                        })
+                        .collect()
+                ].concat(),
+                primary_key: vec![0],
+                triggers: vec![],
+            }
+        )
+    }
+
+    #[allow(dead_code)]
+    fn names(page: &DependencyGraphPage) -> Vec<String> {
+        page.lines()
+            .iter()
+            .map(|line| format!("{}{}", " ".repeat(line.path.len() - 1), page.relations[*line.path.last().unwrap()].name()))
+            .collect()
+    }
+
+    #[test]
+    fn navigation_test_1() {
+        let relations = vec![
+            table("visits", &["patients", "doctors"]),
+            table("patients", &[]),
+            table("doctors", &[]),
+            Relation::View(View::new("patient_visits", QDL(String::from("SELECT * FROM visits")))),
+        ];
+
+        let mut page = DependencyGraphPage::from(&relations);
+
+        assert_eq!(names(&page), vec!["doctors", "patients"]);
+
+        page.next().expand();
+        assert_eq!(names(&page), vec!["doctors", "patients", " visits"]);
+
+        page.next().expand();
+        assert_eq!(names(&page), vec!["doctors", "patients", " visits", "  patient_visits"]);
+        assert_eq!(page.selected().unwrap().name(), "visits");
+
+        page.next().next();
+        assert_eq!(page.selected().unwrap().name(), "patient_visits");
+
+        page.collapse();
+        assert_eq!(page.selected().unwrap().name(), "visits");
+
+        page.collapse();
+        assert_eq!(names(&page), vec!["doctors", "patients", " visits"]);
+
+        page.prev().prev().prev();
+        assert_eq!(page.selected().unwrap().name(), "doctors");
+    }
+
+    #[test]
+    fn navigation_test_2() {
+        //tables referencing each other
+        let relations = vec![
+            table("table_2", &["table_1"]),
+            table("table_1", &["table_2"]),
+            table("table_3", &[]),
+        ];
+
+        let mut page = DependencyGraphPage::from(&relations);
+
+        assert_eq!(names(&page), vec!["table_3", "table_1"]);
+
+        page.next().expand();
+        assert_eq!(names(&page), vec!["table_3", "table_1", " table_2"]);
+
+        page.next().expand();
+        assert_eq!(names(&page), vec!["table_3", "table_1", " table_2"]);
+    }
+}
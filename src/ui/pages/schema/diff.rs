@@ -0,0 +1,127 @@
+use std::cmp::max;
+
+use tui::{
+    Frame,
+    style::{Color, Style, Modifier},
+    widgets::{Table, Row, Cell, Block, Borders},
+    backend::CrosstermBackend,
+    layout::{Rect, Constraint}
+};
+
+use crate::{ui::renderable::Renderable, backend::{relation::diff::SchemaDiff, sql::SQL}};
+
+/// DiffPage struct defines the states required in-order to show the differences between two schemas on the relation tab
+#[derive(Debug)]
+pub struct DiffPage{
+    /// Describes the schemas being compared (e.g. `live -> snapshot.sql`).
+    title: String,
+    diff: SchemaDiff,
+    /// The schema file the live database is planned to be changed into. `None` if the diff is only shown.
+    source: Option<String>,
+    /// The outcome of the last command on the page.
+    message: String,
+}
+
+impl DiffPage {
+    /// new associative function defines a DiffPage from the differences between two schemas
+    pub fn new(title: &str, diff: SchemaDiff) -> DiffPage {
+        DiffPage { title: title.to_string(), diff, source: None, message: String::new() }
+    }
+
+    /// plan associative function defines a DiffPage showing the plan to change the live database into the schema of a file
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The path of the schema file
+    /// * `diff` - The differences between the live database & the schema file
+    pub fn plan(source: &str, diff: SchemaDiff) -> DiffPage {
+        let message = match (diff.is_empty(), diff.is_destructive()) {
+            (true, _) => String::new(),
+            (false, false) => String::from("enter `confirm` to apply"),
+            (false, true) => String::from("contains destructive changes (!), enter `confirm destructive` to apply"),
+        };
+
+        DiffPage { title: format!("live -> {}", source), diff, source: Some(source.to_string()), message }
+    }
+
+    /// Returns the `SQL` commands that apply the differences.
+    pub fn cmds(&self) -> Vec<SQL> {
+        self.diff.to_sql()
+    }
+
+    /// Returns the differences shown on the page.
+    pub fn diff(&self) -> &SchemaDiff {
+        &self.diff
+    }
+
+    /// Returns the schema file the page is a plan for.
+    pub fn source(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+
+    /// Sets the outcome shown in the title of the page.
+    pub fn set_message(&mut self, message: &str) {
+        self.message = message.to_string();
+    }
+}
+
+impl Renderable for DiffPage{
+    fn render<T: std::io::Write>(&self, display_area: Rect, frame: &mut Frame<CrosstermBackend<T>>) {
+        let mut column_length: u16 = "Change".len() as u16;
+
+        let rows: Vec<Row> = match self.diff.is_empty() {
+            true => vec![Row::new(vec![Cell::from("No differences"), Cell::from("")])],
+            false => self.diff.changes.iter()
+                .map(|change| {
+                    let summary = match change.is_destructive() {
+                        true => format!("! {}", change),
+                        false => change.to_string(),
+                    };
+                    column_length = max(column_length, summary.len() as u16);
+
+                    let style = match (change.is_destructive(), summary.chars().next()) {
+                        (true, _) => Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                        (false, Some('+')) => Style::default().fg(Color::Green),
+                        (false, Some('-')) => Style::default().fg(Color::Red),
+                        _ => Style::default().fg(Color::Yellow),
+                    };
+
+                    let cmds: Vec<String> = change.to_sql()
+                        .iter()
+                        .map(|cmd| cmd.to_string())
+                        .collect();
+
+                    Row::new(vec![Cell::from(summary).style(style), Cell::from(cmds.join("; "))])
+                })
+                .collect(),
+        };
+
+        let widths = [
+            Constraint::Length(column_length),
+            Constraint::Min(0)
+        ];
+
+        let table = Table::new(rows)
+            .header(
+                Row::new(
+                    vec![
+                        Cell::from(String::from("Change")),
+                        Cell::from(String::from("SQL")),
+                    ]
+                )
+            )
+            .widths(&widths).column_spacing(3)
+            .block(
+                Block::default()
+                .title(
+                    match self.message.len() {
+                        0 => format!("Diff: {}", self.title),
+                        _ => format!("Diff: {} - {}", self.title, self.message),
+                    }
+                )
+                .borders(Borders::ALL)
+            );
+
+        frame.render_widget(table, display_area);
+    }
+}
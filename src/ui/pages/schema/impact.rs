@@ -0,0 +1,91 @@
+use tui::{
+    Frame,
+    style::{Color, Style, Modifier},
+    widgets::{Paragraph, Block, Borders, Wrap},
+    backend::CrosstermBackend,
+    layout::Rect,
+    text::{Spans, Span}
+};
+
+use crate::{ui::renderable::Renderable, backend::{relation::{Relation, impact::Impact}, sql::SQL}};
+
+/// ImpactPage struct defines the states required in-order to show the relations affected by a relation on the relation tab
+#[derive(Debug)]
+pub struct ImpactPage{
+    impact: Impact,
+}
+
+impl ImpactPage {
+    /// new associative function defines an ImpactPage from the impact of a relation
+    pub fn new(impact: Impact) -> ImpactPage {
+        ImpactPage { impact }
+    }
+
+    /// Returns the `SQL` commands removing the relation.
+    pub fn cmds(&self) -> Vec<SQL> {
+        self.impact.to_sql()
+    }
+}
+
+/// Returns the lines listing relations indented by their distance from the analysed relation
+fn relation_lines(relations: &[(Relation, usize)]) -> Vec<Spans<'_>> {
+    if relations.is_empty() {
+        return vec![Spans::from(Span::styled("  none", Style::default().fg(Color::DarkGray)))];
+    }
+
+    relations.iter()
+        .map(|(relation, distance)| {
+            let relation_type = match relation {
+                Relation::Table(_) => "table",
+                Relation::View(_) => "view",
+                Relation::Routine(_) => "routine",
+                Relation::Event(_) => "event",
+            };
+
+            Spans::from(
+                vec![
+                    Span::from("  ".repeat(*distance)),
+                    Span::from(relation.name()),
+                    Span::styled(format!(" ({})", relation_type), Style::default().fg(Color::DarkGray)),
+                ]
+            )
+        })
+        .collect()
+}
+
+impl Renderable for ImpactPage{
+    fn render<T: std::io::Write>(&self, display_area: Rect, frame: &mut Frame<CrosstermBackend<T>>) {
+        let heading = |text: &str| Spans::from(Span::styled(text.to_string(), Style::default().add_modifier(Modifier::BOLD)));
+
+        let script: Vec<Spans> = self.cmds()
+            .iter()
+            .map(|cmd| {
+                let style = match cmd {
+                    SQL::Drop(_) => Style::default().fg(Color::Red),
+                    _ => Style::default().fg(Color::Yellow),
+                };
+
+                Spans::from(Span::styled(format!("  {};", cmd), style))
+            })
+            .collect();
+
+        let lines: Vec<Spans> = [
+            vec![heading("Dependents")],
+            relation_lines(&self.impact.dependents),
+            vec![Spans::default(), heading("Dependencies")],
+            relation_lines(&self.impact.dependencies),
+            vec![Spans::default(), heading("Removal script")],
+            script,
+        ].concat();
+
+        let paragraph = Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(
+                Block::default()
+                .title(format!("Impact: {}", self.impact.relation.name()))
+                .borders(Borders::ALL)
+            );
+
+        frame.render_widget(paragraph, display_area);
+    }
+}
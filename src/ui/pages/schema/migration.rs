@@ -0,0 +1,102 @@
+use std::cmp::max;
+
+use tui::{
+    Frame,
+    style::{Color, Style},
+    widgets::{Table, Row, Cell, Block, Borders},
+    backend::CrosstermBackend,
+    layout::{Rect, Constraint}
+};
+
+use crate::{ui::renderable::Renderable, backend::{migration::{Migrations, MigrationState, MigrationStatus}, data_base::DataBase}};
+
+/// MigrationPage struct defines the states required in-order to show the status of the migrations on the relation tab
+#[derive(Debug)]
+pub struct MigrationPage{
+    states: Vec<MigrationState>,
+    /// The outcome of the last migrate command.
+    message: String,
+}
+
+impl MigrationPage {
+    /// new associative function defines a MigrationPage from the current status of the migrations
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The outcome of the last migrate command
+    pub fn new(message: &str) -> MigrationPage {
+        let states = DataBase::from_env()
+            .map_err(|err| format!("{:?}", err))
+            .and_then(|db| Migrations::default().status(&db).map_err(|err| format!("{:?}", err)));
+
+        match states {
+            Ok(states) => MigrationPage { states, message: message.to_string() },
+            Err(err) => {
+                log::error!("Failed to load migration status - Err:{}", err);
+                MigrationPage { states: Vec::new(), message: format!("Failed to load migration status: {}", err) }
+            },
+        }
+    }
+}
+
+impl Renderable for MigrationPage{
+    fn render<T: std::io::Write>(&self, display_area: Rect, frame: &mut Frame<CrosstermBackend<T>>) {
+        let mut column_length: (u16, u16) = ("Version".len() as u16, "Name".len() as u16);
+
+        let rows: Vec<Row> = self.states.iter()
+            .map(|state| {
+                column_length.0 = max(column_length.0, state.version.len() as u16);
+                column_length.1 = max(column_length.1, state.name.len() as u16);
+
+                let style = match state.status {
+                    MigrationStatus::Pending => Style::default().fg(Color::Yellow),
+                    MigrationStatus::Applied => Style::default().fg(Color::Green),
+                    MigrationStatus::Modified |
+                    MigrationStatus::Missing |
+                    MigrationStatus::Dirty => Style::default().fg(Color::Red),
+                };
+
+                Row::new(
+                    vec![
+                        Cell::from(state.version.clone()),
+                        Cell::from(state.name.clone()),
+                        Cell::from(state.status.to_string()).style(style),
+                        Cell::from(state.applied_at.clone().unwrap_or_default()),
+                    ]
+                )
+            })
+            .collect();
+
+        let widths = [
+            Constraint::Length(column_length.0),
+            Constraint::Length(column_length.1),
+            Constraint::Length("Modified".len() as u16),
+            Constraint::Min(0),
+        ];
+
+        let table = Table::new(rows)
+            .header(
+                Row::new(
+                    vec![
+                        Cell::from(String::from("Version")),
+                        Cell::from(String::from("Name")),
+                        Cell::from(String::from("Status")),
+                        Cell::from(String::from("Applied At")),
+                    ]
+                )
+            )
+            .widths(&widths).column_spacing(3)
+            .block(
+                Block::default()
+                .title(
+                    match self.message.len() {
+                        0 => String::from("Migrations"),
+                        _ => format!("Migrations - {}", self.message),
+                    }
+                )
+                .borders(Borders::ALL)
+            );
+
+        frame.render_widget(table, display_area);
+    }
+}
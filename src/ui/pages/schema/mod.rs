@@ -1,2 +1,6 @@
 pub mod relation_list;
-pub mod relation_page;
\ No newline at end of file
+pub mod relation_page;
+pub mod diff;
+pub mod migration;
+pub mod dependency_graph;
+pub mod impact;
\ No newline at end of file
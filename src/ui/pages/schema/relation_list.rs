@@ -9,7 +9,7 @@ use tui::{
 };
 
 use crate::{ui::{renderable::Renderable}, backend::{relation::table}};
-use crate::backend::relation::Relation;
+use crate::backend::relation::{Relation, routine::RoutineType};
 
 /// rand_col function returns a random colour
 fn rand_col() -> Color {
@@ -39,24 +39,29 @@ impl RelationListPage {
                 |relation| {
                     match relation {
                         Relation::Table(table) => {
-                            match table.primary_key {
-                                Some(_) => true,
-                                None => false
-                            }
+                            !table.primary_key.is_empty()
                         },
                         Relation::View(_query) => false,
+                        Relation::Routine(_routine) => false,
+                        Relation::Event(_event) => false,
                     }
                 }
             ).for_each(
                 |relation| {
                     if let Relation::Table(table) = relation {
-                        col_map.insert(
-                            (
-                                table.name.clone(),
-                                table.attributes.get(table.primary_key.unwrap()).unwrap().name.clone()
-                            ),
-                            rand_col()
-                        );
+                        table.primary_key_names()
+                            .into_iter()
+                            .for_each(
+                                |primary_key| {
+                                    col_map.insert(
+                                        (
+                                            table.name.clone(),
+                                            primary_key.to_string()
+                                        ),
+                                        rand_col()
+                                    );
+                                }
+                            );
                     }
                 }
             );
@@ -77,11 +82,11 @@ impl RelationListPage {
                             |(index,a)| {
                                 Span::styled(
                                     a.schema_fmt(),
-                                    match table.primary_key {
+                                    match table.primary_key.contains(&index) {
                                         //apply primary key style
-                                        Some(primary_key) => self.get_primary_key(primary_key, index, table, a),
+                                        true => self.get_primary_key(table, a),
                                         //check for foreign key style
-                                        None => self.get_attribute(a),
+                                        false => self.get_attribute(a),
                                     }
                                 )
                             }
@@ -117,7 +122,7 @@ impl RelationListPage {
         match foreign_key {
             Some(val) => {
                 match val {
-                    table::Constraint::ForeignKey{table_name, attribute_name} => {
+                    table::Constraint::ForeignKey{table_name, attribute_name, ..} => {
                         Style::default()
                             .fg(Color::Black)
                             .bg(
@@ -137,26 +142,21 @@ impl RelationListPage {
         }
     }
 
-    fn get_primary_key(&self, primary_key: usize, index: usize, table: &table::Table, a: &table::Attribute) -> Style {
-        if primary_key == index {
-            Style::default()
-            .fg(
-                self.col_map
-                .get(
-                    &(
-                        table.name.clone(),
-                        a.name.clone()
-                    )
+    fn get_primary_key(&self, table: &table::Table, a: &table::Attribute) -> Style {
+        Style::default()
+        .fg(
+            self.col_map
+            .get(
+                &(
+                    table.name.clone(),
+                    a.name.clone()
                 )
-                .unwrap()
-                .clone()
             )
-            .bg(Color::Black)
-            .add_modifier(Modifier::BOLD)
-        }
-        else {
-            Style::default()
-        }
+            .unwrap()
+            .clone()
+        )
+        .bg(Color::Black)
+        .add_modifier(Modifier::BOLD)
     }
 
     fn get_view_row(&self, column: &mut [Cell; 3], view: &crate::backend::relation::view::View, column_length: &mut (u16, u16, u16)) {
@@ -192,6 +192,30 @@ impl RelationListPage {
             spans
         });
     }
+
+    fn get_routine_row(&self, column: &mut [Cell; 3], routine: &crate::backend::relation::routine::Routine, column_length: &mut (u16, u16, u16)) {
+        let routine_type = match routine.routine_type {
+            RoutineType::Procedure => "Procedure",
+            RoutineType::Function => "Function",
+        };
+        column_length.0 = max(column_length.0, routine_type.len() as u16);
+        column[0] = Cell::from(routine_type);
+
+        column[2] = Cell::from({
+            let signature = routine.signature();
+            column_length.2 = max(column_length.2, signature.len() as u16);
+            signature
+        });
+    }
+
+    fn get_event_row(&self, column: &mut [Cell; 3], event: &crate::backend::relation::event::Event, column_length: &mut (u16, u16, u16)) {
+        column[0] = Cell::from("Event");
+        column[2] = Cell::from({
+            let schedule = format!("{} {}", event.schedule, event.status);
+            column_length.2 = max(column_length.2, schedule.len() as u16);
+            schedule
+        });
+    }
 }
 
 impl <'a>Renderable for RelationListPage{
@@ -217,6 +241,8 @@ impl <'a>Renderable for RelationListPage{
                             match r {
                                 Relation::Table(table) => self.get_table_row(&mut column, table, &mut column_length),
                                 Relation::View(view) => self.get_view_row(&mut column, view, &mut column_length),
+                                Relation::Routine(routine) => self.get_routine_row(&mut column, routine, &mut column_length),
+                                Relation::Event(event) => self.get_event_row(&mut column, event, &mut column_length),
                             };
 
                             column
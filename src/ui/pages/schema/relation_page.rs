@@ -24,7 +24,9 @@ impl Renderable for RelationPage{
                 //define relation definition lines
                 match &self.relation {
                     Relation::Table(table) => table_ui(table),
-                    Relation::View(view) => view_ui(display_area, view)
+                    Relation::View(view) => view_ui(display_area, view),
+                    Relation::Routine(routine) => routine_ui(display_area, routine),
+                    Relation::Event(event) => event_ui(display_area, event),
                 }
             ].concat()
         };
@@ -40,9 +42,42 @@ impl Renderable for RelationPage{
     }
 }
 
-fn view_ui(display_area: Rect, view: &crate::backend::relation::view::View) -> Vec<Spans> {
+fn routine_ui(display_area: Rect, routine: &crate::backend::relation::routine::Routine) -> Vec<Spans<'static>> {
+    [
+        vec![
+            Spans::from(format!("├ {}{}", routine.routine_type, routine.signature())),
+            Spans::from(format!("├ {}", routine.characteristics)),
+            Spans::from(format!("└ DEFINER {}", routine.definer)),
+        ],
+        wrap_words(display_area, &routine.body),
+    ].concat()
+}
+
+fn event_ui(display_area: Rect, event: &crate::backend::relation::event::Event) -> Vec<Spans<'static>> {
+    [
+        vec![
+            Spans::from(format!("├ ON SCHEDULE {}", event.schedule)),
+            Spans::from(format!("├ {} ON COMPLETION {}", event.status, if event.preserve { "PRESERVE" } else { "NOT PRESERVE" })),
+            Spans::from(format!("└ DEFINER {}", event.definer)),
+        ],
+        wrap_words(display_area, &event.body),
+    ].concat()
+}
+
+fn view_ui(display_area: Rect, view: &crate::backend::relation::view::View) -> Vec<Spans<'static>> {
+    [
+        vec![
+            Spans::from(format!("├ ALGORITHM {} SQL SECURITY {} CHECK OPTION {}", view.algorithm, view.security, view.check_option)),
+            Spans::from(format!("├ COLUMNS ({})", view.columns.join(", "))),
+            Spans::from(format!("└ DEFINER {}", view.definer)),
+        ],
+        wrap_words(display_area, &view.query.to_string()),
+    ].concat()
+}
+
+fn wrap_words(display_area: Rect, text: &str) -> Vec<Spans<'static>> {
     let max_width = display_area.width - 2 - 2;
-    let tmp = view.query.to_string()
+    let tmp = text
         .split(" ")
         .map(
             |str: &str| {
@@ -95,7 +130,7 @@ fn table_ui(table: &crate::backend::relation::table::Table) -> Vec<Spans> {
                 Span::from(" "),
 
                 {
-                    if Some(index) == table.primary_key {
+                    if table.primary_key.contains(&index) {
                         Span::from("Primary Key ")
                     }
                     else{
@@ -113,5 +148,34 @@ fn table_ui(table: &crate::backend::relation::table::Table) -> Vec<Spans> {
 
             ])
         })
+        .chain(trigger_ui(table))
         .collect()
+}
+
+fn trigger_ui(table: &crate::backend::relation::table::Table) -> Vec<Spans<'static>> {
+    if table.triggers.is_empty() {
+        return Vec::new()
+    }
+
+    let mut lines = vec![Spans::from("Triggers")];
+
+    table.triggers
+        .iter()
+        .enumerate()
+        .for_each(|(index, trigger)| {
+            lines.push(
+                Spans::from(
+                    format!(
+                        "{} {} {} {} #{}",
+                        if table.triggers.len() - 1 == index { "└" } else { "├" },
+                        trigger.name,
+                        trigger.timing,
+                        trigger.event,
+                        trigger.order
+                    )
+                )
+            );
+        });
+
+    lines
 }
\ No newline at end of file
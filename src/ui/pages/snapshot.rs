@@ -1,18 +1,27 @@
-use std::{cmp::{max, min}, mem, path::{ PathBuf}};
+use std::{cmp::{max, min}, mem, path::{ PathBuf}, sync::{Arc, atomic::{AtomicBool, Ordering}, mpsc::{self, Receiver}}, thread};
 
+use time::OffsetDateTime;
 use tui::{layout::{Constraint, Rect}, Frame, backend::CrosstermBackend, widgets::{Table, Row, Cell, Block, Borders}, text::Span};
 
-use crate::{ui::renderable::Renderable, backend::{snapshot::{SnapShotsFile, SnapShot}, data_base::DataBase, sql::SQL}};
+use crate::{ui::{renderable::Renderable, pages::snapshot_diff::SnapShotDiffPage}, backend::{retention::Retention, snapshot::{SnapShotsFile, SnapShot, SnapShotOptions, SnapShotKind, Error}, data_base::DataBase, sql::SQL, restore::{Progress, table_statements}, dump::DumpProgress, snapshot_diff::{SnapShotState, diff}}};
 
 pub struct SnapShotPage{
     snap_shots: Vec<SnapShot>,
     offset: usize,
+    /// The outcome of the last command on the page.
+    message: String,
+    /// The progress of the restore running in the background, if any.
+    progress: Option<Receiver<Progress>>,
+    /// The progress of the snapshot being taken in the background, if any.
+    dump: Option<Receiver<DumpProgress>>,
+    /// Cancels the snapshot being taken in the background once set.
+    cancel: Arc<AtomicBool>,
 }
 
 impl SnapShotPage{
 
     pub fn next(&mut self, offset: usize) {
-        self.offset = min(self.offset + offset, self.snap_shots.len() - 1);
+        self.offset = min(self.offset + offset, self.snap_shots.len().saturating_sub(1));
     }
 
     pub fn prev(&mut self, offset: usize) {
@@ -24,11 +33,136 @@ impl SnapShotPage{
     pub fn update(&mut self){
         let mut snap_shot = SnapShotPage::default();
         snap_shot.next(self.offset);
+        snap_shot.progress = self.progress.take();
+        snap_shot.dump = self.dump.take();
+        snap_shot.cancel = self.cancel.clone();
 
 
         *self = mem::take(&mut snap_shot);
     }
 
+    /// Shows the latest progress of the restore & the snapshot running in the background
+    ///
+    /// Returns true if the progress changed.
+    pub fn update_progress(&mut self) -> bool {
+        let restored = self.update_restore_progress();
+
+        self.update_dump_progress() || restored
+    }
+
+    fn update_restore_progress(&mut self) -> bool {
+        let progress: Vec<Progress> = match &self.progress {
+            Some(receiver) => receiver.try_iter().collect(),
+            None => return false,
+        };
+
+        match progress.last() {
+            Some(last) => {
+                if matches!(last, Progress::Done | Progress::Failed(_)) {
+                    self.progress = None;
+                }
+
+                self.message = last.to_string();
+
+                true
+            },
+            None => false,
+        }
+    }
+
+    fn update_dump_progress(&mut self) -> bool {
+        let progress: Vec<DumpProgress> = match &self.dump {
+            Some(receiver) => receiver.try_iter().collect(),
+            None => return false,
+        };
+
+        match progress.last() {
+            Some(last) => {
+                if matches!(last, DumpProgress::Done { .. } | DumpProgress::Cancelled | DumpProgress::Failed(_)) {
+                    self.dump = None;
+                    //the new snapshot is listed once it is saved
+                    self.update();
+                }
+
+                self.message = last.to_string();
+
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Takes a snapshot of the database in the background, while the page shows the progress
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The options of the snapshot (e.g. read lock & compression)
+    pub fn take_snapshot(&mut self, options: SnapShotOptions) {
+        if self.dump.is_some() {
+            self.message = String::from("a snapshot is already being taken");
+            return ;
+        }
+
+        let (sender, receiver) = mpsc::channel::<DumpProgress>();
+
+        self.cancel = Arc::new(AtomicBool::new(false));
+        let cancel = self.cancel.clone();
+
+        thread::spawn(move || {
+            let mut cycles: Vec<Vec<String>> = Vec::new();
+
+            let snapshot = match DataBase::from_env() {
+                Ok(db) => SnapShot::take(&db, &options, &cancel, |progress| {
+                    if let DumpProgress::Schema { cycles: schema_cycles, .. } = &progress {
+                        cycles = schema_cycles.clone();
+                    }
+                    let _result = sender.send(progress);
+                }),
+                Err(err) => Err(Error::Err(format!("{:?}", err))),
+            };
+
+            let progress = match snapshot {
+                Ok(snapshot) => {
+                    let mut file = SnapShotsFile::default();
+                    file.add_snapshot(snapshot);
+
+                    //the retention is applied once the new snapshot is saved
+                    let pruned = file.prune(OffsetDateTime::now_utc()).len();
+
+                    DumpProgress::Done { cycles, pruned }
+                },
+                Err(Error::Cancelled) => DumpProgress::Cancelled,
+                Err(Error::Err(err)) => DumpProgress::Failed(err),
+                Err(err) => DumpProgress::Failed(format!("{:?}", err)),
+            };
+
+            if let DumpProgress::Failed(err) = &progress {
+                log::error!("Failed to take snapshot - Err:{}", err);
+            }
+
+            let _result = sender.send(progress);
+        });
+
+        self.message = String::from("taking snapshot");
+        self.dump = Some(receiver);
+    }
+
+    /// Cancels the snapshot being taken in the background, removing its file
+    pub fn cancel_snapshot(&mut self) {
+        match self.dump.is_some() {
+            true => {
+                self.cancel.store(true, Ordering::Relaxed);
+                self.message = String::from("cancelling snapshot");
+            },
+            false => self.message = String::from("no snapshot is being taken"),
+        }
+    }
+
+    /// Sets the outcome shown in the title of the page.
+    pub fn set_message(&mut self, message: &str) {
+        self.message = message.to_string();
+    }
+
     pub fn add(&mut self, snapshot: SnapShot) -> & mut Self{
         let mut file = SnapShotsFile::default();
         file.add_snapshot(snapshot);
@@ -77,7 +211,7 @@ impl SnapShotPage{
         };
     }
 
-    fn rollback_by_name(&self, identifier: &str) {
+    fn rollback_by_name(&mut self, identifier: &str, atomic: bool) {
         let index = self.snap_shots
             .iter()
             .map(|snapshot| PathBuf::from(&snapshot.path))
@@ -91,27 +225,353 @@ impl SnapShotPage{
         let index = index.unwrap().0;
 
 
-        self.rollback_by_index(index)
+        self.rollback_by_index(index, atomic, true)
     }
 
-    fn rollback_by_index(& self, index: usize) {
-        if self.snap_shots.len() < index {
+    /// Returns whether the checksum of a snapshot matches its file, otherwise the refused action is reported on the page
+    fn verify(&mut self, index: usize, action: &str) -> bool {
+        //a corrupted or modified snapshot is never restored
+        match self.snap_shots[index].verify() {
+            Ok(()) => true,
+            Err(Error::ChecksumMismatch { expected, actual }) => {
+                self.message = format!("refusing to {}: checksum {} doesn't match the stored checksum {}", action, actual, expected);
+                false
+            },
+            Err(err) => {
+                self.message = format!("refusing to {}: {:?}", action, err);
+                false
+            },
+        }
+    }
+
+    /// Takes a safety snapshot before the database is changed, listing it on the page
+    ///
+    /// Returns false if the snapshot failed, with the refused action reported on the page
+    fn safety_snapshot(&mut self, action: &str, description: &str) -> bool {
+        let result = DataBase::from_env()
+            .map_err(|err| Error::Err(format!("{:?}", err)))
+            .and_then(|db| SnapShotsFile::default().safety_snapshot(&db, &format!("before {}", action), description));
+
+        match result {
+            Ok(Some(snapshot)) => {
+                //snapshots are listed oldest first, so the rows of the listed snapshots don't change
+                self.snap_shots.push(snapshot);
+                true
+            },
+            Ok(None) => true,
+            Err(err) => {
+                self.message = format!("refusing to {}: failed to take safety snapshot: {:?}, enter `auto-snapshot off` to proceed without it", action, err);
+                false
+            },
+        }
+    }
+
+    fn rollback_by_index(&mut self, index: usize, atomic: bool, safety: bool) {
+        if self.snap_shots.len() <= index {
+            return ;
+        }
+
+        if !self.verify(index, "rollback") {
             return ;
         }
 
-        let db = DataBase::from_env().unwrap();
+        let name = PathBuf::from(&self.snap_shots[index].path).file_name().unwrap().to_string_lossy().to_string();
 
-        let _result = db.rollback(SQL::from_file(&self.snap_shots[index].path).unwrap());
+        if safety && !self.safety_snapshot("rollback", &format!("rollback to {}", name)) {
+            return ;
+        }
+
+        //partial snapshots only replace the tables or rows they hold
+        if matches!(self.snap_shots[index].kind, SnapShotKind::DataOnly | SnapShotKind::Tables(_)) {
+            self.message = match (DataBase::from_env(), SQL::from_file(&self.snap_shots[index].path)) {
+                (Ok(db), Ok(cmds)) => match db.partial_rollback(cmds) {
+                    Ok(orphans) => format!("restored {} snapshot{}", self.snap_shots[index].kind, orphans_message(&orphans)),
+                    Err(err) => format!("failed to restore: {}", err),
+                },
+                (Err(err), _) => format!("failed to restore: {:?}", err),
+                (_, Err(err)) => format!("failed to read snapshot: {:?}", err),
+            };
+
+            return ;
+        }
+
+        if atomic {
+            self.message = match (DataBase::from_env(), SQL::from_file(&self.snap_shots[index].path)) {
+                (Ok(db), Ok(cmds)) => match db.rollback(cmds) {
+                    Ok(()) => format!("restored {}", name),
+                    Err(err) => {
+                        log::error!("Failed to restore {} - Err:{:?}", name, err);
+                        format!("failed to restore: {}", err)
+                    },
+                },
+                (Err(err), _) => format!("failed to restore: {:?}", err),
+                (_, Err(err)) => format!("failed to read snapshot: {:?}", err),
+            };
+
+            return ;
+        }
+
+        if self.progress.is_some() {
+            self.message = String::from("a restore is already running");
+            return ;
+        }
+
+        let path = self.snap_shots[index].path.clone();
+        let (sender, receiver) = mpsc::channel::<Progress>();
+
+        //the tables of each layer are restored concurrently in the background, while the page shows the progress
+        thread::spawn(move || {
+            let connections = thread::available_parallelism()
+                .map(|connections| connections.get())
+                .unwrap_or(4);
+
+            let result = match (DataBase::from_env(), SQL::from_file(&path)) {
+                (Ok(db), Ok(cmds)) => db.parallel_rollback(cmds, connections, |progress| {
+                    let _result = sender.send(progress);
+                })
+                .map_err(|err| format!("{:?}", err)),
+                (Err(err), _) => Err(format!("{:?}", err)),
+                (_, Err(err)) => Err(format!("{:?}", err)),
+            };
+
+            if let Err(err) = result {
+                log::error!("Failed to restore {} - Err:{}", path, err);
+                let _result = sender.send(Progress::Failed(err));
+            }
+        });
+
+        self.message = String::from("restoring");
+        self.progress = Some(receiver);
     }
 
-    pub fn rollback(&self, identifier: &str) {
+    /// Rollbacks the database to a snapshot, given by its row or name
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier` - The row or name of the snapshot
+    /// * `atomic` - Whether the snapshot is restored sequentially in a single transaction, instead of concurrently in the background
+    pub fn rollback(&mut self, identifier: &str, atomic: bool) {
         match usize::from_str_radix(identifier, 10) {
-            Ok(index) => self.rollback_by_index(index),
-            Err(_) => self.rollback_by_name(identifier),
+            Ok(index) => self.rollback_by_index(index, atomic, true),
+            Err(_) => self.rollback_by_name(identifier, atomic),
+        }
+    }
+
+    /// Returns the index of a snapshot, given by its row or name
+    fn index_of(&self, identifier: &str) -> Option<usize> {
+        match identifier.parse::<usize>() {
+            Ok(index) => Some(index).filter(|index| *index < self.snap_shots.len()),
+            Err(_) => self.snap_shots
+                .iter()
+                .map(|snapshot| PathBuf::from(&snapshot.path))
+                .position(|file_path| file_path.file_name().unwrap() == identifier || file_path.to_str().unwrap() == identifier),
+        }
+    }
+
+    /// Returns the state held by a snapshot, given by its row or name, or by the live database if the identifier is `live`
+    fn state(&self, identifier: &str) -> Result<SnapShotState, String> {
+        if identifier.eq_ignore_ascii_case("live") {
+            return DataBase::from_env()
+                .map_err(|err| format!("{:?}", err))
+                .and_then(|db| SnapShotState::from_db(&db).map_err(|err| err.to_string()));
         }
+
+        let index = self.index_of(identifier)
+            .ok_or_else(|| format!("no snapshot {}", identifier))?;
+
+        SQL::from_file(&self.snap_shots[index].path)
+            .map(|cmds| SnapShotState::from_sql(&cmds))
+            .map_err(|err| format!("failed to read snapshot {}: {:?}", identifier, err))
+    }
+
+    /// Returns the page showing the differences between two snapshots, or between a snapshot & the live database.
+    ///
+    /// Returns None if either state can't be read, with the reason shown on the page
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The row or name of the snapshot compared from, or `live`
+    /// * `to` - The row or name of the snapshot compared to, or `live`
+    pub fn diff(&mut self, from: &str, to: &str) -> Option<SnapShotDiffPage> {
+        let states = self.state(from)
+            .and_then(|old| self.state(to).map(|new| (old, new)));
+
+        match states {
+            Ok((old, new)) => Some(SnapShotDiffPage::new(&format!("{} -> {}", from, to), diff(&old, &new))),
+            Err(err) => {
+                self.message = err;
+                None
+            },
+        }
+    }
+
+    /// Rollbacks the database to the most recent automatic snapshot, undoing the last rollback or destructive statement
+    ///
+    /// No safety snapshot is taken, so repeating the command restores the same snapshot.
+    pub fn undo_last(&mut self) {
+        //safety snapshots taken outside the page (e.g. before console statements) are only added to the catalog, so the page is reloaded first
+        self.update();
+
+        let index = SnapShotsFile::default()
+            .last_automatic()
+            .and_then(|snapshot| self.index_of(&snapshot.path));
+
+        match index {
+            Some(index) => self.rollback_by_index(index, false, false),
+            None => self.message = String::from("no automatic snapshot to undo"),
+        }
+    }
+
+    /// Enables or disables safety snapshots, or shows whether they are enabled if `None`
+    pub fn auto_snapshot(&mut self, enabled: Option<bool>) {
+        let mut file = SnapShotsFile::default();
+
+        if let Some(enabled) = enabled {
+            file.safety_snapshots = enabled;
+
+            if let Err(err) = file.save() {
+                self.message = format!("failed to save setting: {:?}", err);
+                return ;
+            }
+        }
+
+        self.message = format!("safety snapshots {}", if file.safety_snapshots { "enabled" } else { "disabled" });
+    }
+
+    /// Pins or unpins a snapshot, given by its row or name, exempting it from pruning while pinned
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier` - The row or name of the snapshot
+    /// * `pinned` - Whether the snapshot is pinned
+    pub fn pin(&mut self, identifier: &str, pinned: bool) {
+        let index = match self.index_of(identifier) {
+            Some(index) => index,
+            None => {
+                self.message = format!("no snapshot {}", identifier);
+                return ;
+            },
+        };
+
+        //the catalog is read afresh, so snapshots taken since the page was loaded are kept
+        let result = SnapShotsFile::default().set_pinned(&self.snap_shots[index].path, pinned);
+
+        self.update();
+        self.message = match result {
+            Ok(true) => format!("{} snapshot {}", if pinned { "pinned" } else { "unpinned" }, identifier),
+            Ok(false) => format!("no snapshot {}", identifier),
+            Err(err) => format!("failed to save snapshots: {:?}", err),
+        };
+    }
+
+    /// Sets the retention applied when snapshots are pruned, or shows the current retention if `None`
+    pub fn retention(&mut self, retention: Option<Retention>) {
+        let mut file = SnapShotsFile::default();
+
+        if let Some(retention) = retention {
+            file.retention = retention;
+
+            if let Err(err) = file.save() {
+                self.message = format!("failed to save retention: {:?}", err);
+                return ;
+            }
+        }
+
+        self.message = format!("retention: {}", file.retention);
+    }
+
+    /// Removes the snapshots expired by the retention along with their files, or only lists them with `dry_run`
+    pub fn prune(&mut self, dry_run: bool) {
+        let mut file = SnapShotsFile::default();
+        let now = OffsetDateTime::now_utc();
+
+        let expired = match dry_run {
+            true => file.expired(now),
+            false => file.prune(now),
+        };
+
+        let names: Vec<String> = expired.iter()
+            .map(|snapshot| PathBuf::from(&snapshot.path).file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+
+        self.update();
+        self.message = match (names.is_empty(), dry_run) {
+            (true, _) => format!("nothing to prune ({})", file.retention),
+            (false, true) => format!("would prune {} snapshot(s): {}", names.len(), names.join(", ")),
+            (false, false) => format!("pruned {} snapshot(s): {}", names.len(), names.join(", ")),
+        };
+    }
+
+    /// Restores a single table from a snapshot, given by its row or name, leaving the other relations untouched
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier` - The row or name of the snapshot
+    /// * `table` - The name of the table
+    /// * `data_only` - Whether only the rows of the table are replaced, instead of recreating the table
+    pub fn restore_table(&mut self, identifier: &str, table: &str, data_only: bool) {
+        let index = match self.index_of(identifier) {
+            Some(index) => index,
+            None => {
+                self.message = format!("no snapshot {}", identifier);
+                return ;
+            },
+        };
+
+        if !self.verify(index, "restore") {
+            return ;
+        }
+
+        let name = PathBuf::from(&self.snap_shots[index].path).file_name().unwrap().to_string_lossy().to_string();
+
+        let cmds = match SQL::from_file(&self.snap_shots[index].path) {
+            Ok(cmds) => cmds,
+            Err(err) => {
+                self.message = format!("failed to read snapshot: {:?}", err);
+                return ;
+            },
+        };
+
+        let cmds = match table_statements(cmds, table, data_only) {
+            Some(cmds) => cmds,
+            None => {
+                self.message = format!("snapshot {} doesn't hold table {}", identifier, table);
+                return ;
+            },
+        };
+
+        if !self.safety_snapshot("restore", &format!("restore {} from {}", table, name)) {
+            return ;
+        }
+
+        //foreign key checks are disabled while the table is replaced, so the rows of dependent tables are kept
+        self.message = match DataBase::from_env().map(|db| db.partial_rollback(cmds)) {
+            Ok(Ok(orphans)) => format!("restored {}{}{}", table, if data_only { " rows" } else { "" }, orphans_message(&orphans)),
+            Ok(Err(err)) => format!("failed to restore {}: {}", table, err),
+            Err(err) => format!("failed to restore {}: {:?}", table, err),
+        };
     }
 }
 
+/// Returns the part of a restore message reporting the rows left referencing missing rows, or an empty string if there are none
+///
+/// # Arguments
+///
+/// * `orphans` - The foreign keys with rows referencing missing rows & the number of these rows
+fn orphans_message(orphans: &[(String, u64)]) -> String {
+    if orphans.is_empty() {
+        return String::new();
+    }
+
+    format!(
+        ", but rows reference missing rows: {}",
+        orphans.iter()
+            .map(|(foreign_key, rows)| format!("{} ({})", foreign_key, rows))
+            .collect::<Vec<String>>()
+            .join(", ")
+    )
+}
+
 impl Default for SnapShotPage {
     fn default() -> Self {
         let file = SnapShotsFile::default();
@@ -128,103 +588,103 @@ impl Default for SnapShotPage {
 
         SnapShotPage{
             snap_shots: snap_shots,
-            offset: 0
+            offset: 0,
+            message: String::new(),
+            progress: None,
+            dump: None,
+            cancel: Arc::new(AtomicBool::new(false)),
         }
     }
 }
 
+/// Returns a size in bytes in the largest unit it is at least one of (e.g. `1.5 MiB`)
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    let mut size = bytes as f64;
+    let mut unit = "B";
+
+    for next_unit in UNITS {
+        if size < 1024.0 {
+            break;
+        }
+        size /= 1024.0;
+        unit = next_unit;
+    }
+
+    match unit {
+        "B" => format!("{} B", bytes),
+        unit => format!("{:.1} {}", size, unit),
+    }
+}
+
+/// Returns the cells of a snapshot's row, in the order of the headers of the page
+fn snapshot_cells(index: usize, snapshot: &SnapShot) -> [String; 10] {
+    let timestamp = snapshot.time_stamp;
+    let path: PathBuf = snapshot.path.clone().into();
+
+    //snapshots added before metadata was stored have empty cells
+    let optional = |value: &Option<String>| value.clone().unwrap_or_default();
+
+    [
+        match snapshot.pinned {
+            true => format!("{} *", index),
+            false => format!("{}", index),
+        },
+        format!(
+            "{}-{}-{} {}:{}",
+            timestamp.month(),
+            timestamp.day(),
+            timestamp.year(),
+
+            timestamp.hour(),
+            timestamp.minute()
+        ),
+        optional(&snapshot.label),
+        match snapshot.automatic {
+            true => format!("{} (auto)", snapshot.kind),
+            false => snapshot.kind.to_string(),
+        },
+        optional(&snapshot.description),
+        match (&snapshot.host, &snapshot.database) {
+            (Some(host), Some(database)) => format!("{}/{}", host, database),
+            _ => String::new(),
+        },
+        optional(&snapshot.server_version),
+        snapshot.size.map(format_size).unwrap_or_default(),
+        snapshot.row_counts.as_ref()
+            .map(|row_counts| format!("{} ({} tables)", row_counts.values().sum::<usize>(), row_counts.len()))
+            .unwrap_or_default(),
+        path.file_name().unwrap().to_str().unwrap().to_string(),
+    ]
+}
+
 impl Renderable for SnapShotPage{
     fn render<T: std::io::Write>(&self, display_area: Rect, frame: &mut Frame<CrosstermBackend<T>>) {
-        const HEADERS: [&str;3] = ["", "TimeStamp", "Path"];
-        let mut widths: [Constraint;3] = [
-            Constraint::Length(HEADERS[0].len() as u16),
-            Constraint::Length(HEADERS[1].len() as u16),
-            Constraint::Length(HEADERS[2].len() as u16)
-        ];
+        const HEADERS: [&str;10] = ["", "TimeStamp", "Label", "Kind", "Description", "Source", "Server", "Size", "Rows", "Path"];
+
+        let cells: Vec<[String; 10]> = self.snap_shots
+            .iter()
+            .enumerate()
+            .skip(self.offset)
+            .map(|(i, snapshot)| snapshot_cells(i, snapshot))
+            .collect();
+
+        let widths: Vec<Constraint> = HEADERS.iter()
+            .enumerate()
+            .map(|(column, header)| {
+                let width = cells.iter()
+                    .map(|row| row[column].len())
+                    .fold(header.len(), max);
+
+                Constraint::Length(width as u16)
+            })
+            .collect();
 
         let table: Table = Table::new(
-                {
-                    let rows: Vec<Row> = self.snap_shots
-                        .iter()
-                        .enumerate()
-                        .skip(self.offset)
-                        .map(|(i, snapshot)| {
-                            Row::new(
-                                vec![
-                                    Cell::from(
-                                        Span::from(
-                                            {
-                                                let index_str: String = format!("{}", i);
-
-                                                widths[0] = match widths[0] {
-                                                    Constraint::Length(l) => {
-                                                        Constraint::Length(max(l, index_str.len() as u16))
-                                                    }
-                                                    _ => {
-                                                        panic!()
-                                                    }
-                                                };
-
-                                                index_str
-                                            }
-                                        )
-                                    ),
-                                    Cell::from(
-                                        Span::from(
-                                            {
-                                                let timestamp = snapshot.time_stamp;
-                                                let tmp_str = format!(
-                                                    "{}-{}-{} {}:{}",
-                                                    timestamp.month(),
-                                                    timestamp.day(),
-                                                    timestamp.year(),
-
-                                                    timestamp.hour(),
-                                                    timestamp.minute()
-                                                );
-
-                                                widths[1] = match widths[1] {
-                                                    Constraint::Length(l) => {
-                                                        Constraint::Length(max(l, tmp_str.len() as u16))
-                                                    }
-                                                    _ => {
-                                                        panic!()
-                                                    }
-                                                };
-
-                                                tmp_str
-                                            }
-                                        )
-                                    ),
-                                    Cell::from(
-                                        Span::from(
-                                            {
-                                                let path: PathBuf = snapshot.path.clone().into();
-
-                                                let file_name = &path.file_name().unwrap().to_str().unwrap();
-
-                                                let tmp_str = file_name.to_string();
-
-                                                widths[2] = match widths[2] {
-                                                    Constraint::Length(l) => {
-                                                        Constraint::Length(max(l, tmp_str.len() as u16))
-                                                    }
-                                                    _ => {
-                                                        panic!()
-                                                    }
-                                                };
-
-                                                tmp_str
-                                            }
-                                        )
-                                    ),
-                                    
-                                ]
-                            )
-                        })
-                        .collect();
-                    rows
-                }
+                cells.iter()
+                    .map(|row| Row::new(row.iter().map(|cell| Cell::from(Span::from(cell.clone())))))
+                    .collect::<Vec<Row>>()
             ).header(
                 Row::new(
                     HEADERS.iter()
@@ -235,7 +695,12 @@ impl Renderable for SnapShotPage{
             .column_spacing(3)
             .block(
                 Block::default()
-                    .title("Snap Shots")
+                    .title(
+                        match self.message.is_empty() {
+                            true => String::from("Snap Shots"),
+                            false => format!("Snap Shots - {}", self.message),
+                        }
+                    )
                     .borders(Borders::ALL)
             );
 
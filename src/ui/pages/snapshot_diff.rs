@@ -0,0 +1,113 @@
+use std::cmp::{max, min};
+
+use tui::{
+    Frame,
+    style::{Color, Style, Modifier},
+    widgets::{Table, Row, Cell, Block, Borders},
+    backend::CrosstermBackend,
+    layout::{Rect, Constraint}
+};
+
+use crate::{ui::renderable::Renderable, backend::snapshot_diff::{SnapShotDiff, RowChange}};
+
+/// SnapShotDiffPage struct defines the states required in-order to show the differences between two snapshots, or a snapshot & the live database, on the snapshot tab
+#[derive(Debug)]
+pub struct SnapShotDiffPage{
+    /// Describes the states being compared (e.g. `3 -> live`).
+    title: String,
+    diff: SnapShotDiff,
+    /// The index of the first change shown.
+    offset: usize,
+}
+
+impl SnapShotDiffPage {
+    /// new associative function defines a SnapShotDiffPage from the differences between two states
+    pub fn new(title: &str, diff: SnapShotDiff) -> SnapShotDiffPage {
+        SnapShotDiffPage { title: title.to_string(), diff, offset: 0 }
+    }
+
+    pub fn next(&mut self, offset: usize) {
+        let changes = self.diff.schema.changes.len() + self.diff.rows.len();
+
+        self.offset = min(self.offset + offset, changes.saturating_sub(1));
+    }
+
+    pub fn prev(&mut self, offset: usize) {
+        self.offset = self.offset.saturating_sub(offset);
+    }
+}
+
+impl Renderable for SnapShotDiffPage{
+    fn render<T: std::io::Write>(&self, display_area: Rect, frame: &mut Frame<CrosstermBackend<T>>) {
+        let mut column_length: u16 = "Relation".len() as u16;
+
+        let schema = self.diff.schema.changes.iter()
+            .map(|change| {
+                let style = match (change.is_destructive(), change.to_string().chars().next()) {
+                    (true, _) => Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                    (false, Some('+')) => Style::default().fg(Color::Green),
+                    (false, Some('-')) => Style::default().fg(Color::Red),
+                    _ => Style::default().fg(Color::Yellow),
+                };
+
+                (String::from("schema"), change.to_string(), style)
+            });
+
+        let rows = self.diff.rows.iter()
+            .map(|change| {
+                let (table, style) = match change {
+                    RowChange::Inserted { table, .. } => (table, Style::default().fg(Color::Green)),
+                    RowChange::Deleted { table, .. } => (table, Style::default().fg(Color::Red)),
+                    RowChange::Updated { table, .. } => (table, Style::default().fg(Color::Yellow)),
+                };
+
+                (table.clone(), change.to_string(), style)
+            });
+
+        let rows: Vec<Row> = match self.diff.is_empty() {
+            true => vec![Row::new(vec![Cell::from(""), Cell::from("No differences")])],
+            false => schema.chain(rows)
+                .skip(self.offset)
+                .map(|(relation, change, style)| {
+                    column_length = max(column_length, relation.len() as u16);
+
+                    Row::new(vec![Cell::from(relation), Cell::from(change).style(style)])
+                })
+                .collect(),
+        };
+
+        let widths = [
+            Constraint::Length(column_length),
+            Constraint::Min(0)
+        ];
+
+        let (inserted, deleted, updated) = self.diff.counts();
+
+        let table = Table::new(rows)
+            .header(
+                Row::new(
+                    vec![
+                        Cell::from(String::from("Relation")),
+                        Cell::from(String::from("Change")),
+                    ]
+                )
+            )
+            .widths(&widths).column_spacing(3)
+            .block(
+                Block::default()
+                .title(
+                    format!(
+                        "Diff: {} - {} schema change(s), {} inserted, {} deleted, {} updated row(s)",
+                        self.title,
+                        self.diff.schema.changes.len(),
+                        inserted,
+                        deleted,
+                        updated
+                    )
+                )
+                .borders(Borders::ALL)
+            );
+
+        frame.render_widget(table, display_area);
+    }
+}